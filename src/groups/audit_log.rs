@@ -0,0 +1,444 @@
+//! The details of audit log entries, typed by the action they're for.
+//!
+//! Roblox sends the details of every action as an untyped object, with different fields
+//! for each action. [`GroupAuditLogEntry::action`] reads them into an [`AuditLogAction`]:
+//!
+//! ```no_run
+//! # use rustyroblox::{groups::{self, AuditLogAction}, util::{ids::GroupId, jar::RequestJar, paging::PageLimit}};
+//! # async fn ranks(jar: &RequestJar) -> Result<(), Box<rustyroblox::util::Error>> {
+//! let entries = groups::audit_log(jar, GroupId(7370273), PageLimit::Limit100, None, None, None).await?;
+//! for entry in &entries {
+//!     if let AuditLogAction::ChangeRank { target, old_role, new_role } = entry.action() {
+//!         println!(
+//!             "{} ranked {} from {} to {}",
+//!             entry.actor.user.username, target.name, old_role.name, new_role.name
+//!         );
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Actions that aren't typed, and details that don't look like they should, are kept as
+//! [`AuditLogAction::Other`] rather than failing.
+
+use rspc::Type;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::util::ids::{RoleId, UserId};
+
+use super::{GroupAuditLogActionType, GroupAuditLogEntry};
+
+/// A user an action was done to
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogUser {
+    pub id: UserId,
+    pub name: String,
+}
+
+/// A role as it was named when the action was done
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogRole {
+    pub id: RoleId,
+    pub name: String,
+}
+
+/// What an audit log entry is about, with the details of the action
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Type)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum AuditLogAction {
+    ChangeRank {
+        target: AuditLogUser,
+        old_role: AuditLogRole,
+        new_role: AuditLogRole,
+    },
+    DeletePost {
+        /// The author of the post
+        target: AuditLogUser,
+        /// What the post said
+        post: String,
+    },
+    RemoveMember {
+        target: AuditLogUser,
+    },
+    AcceptJoinRequest {
+        target: AuditLogUser,
+    },
+    DeclineJoinRequest {
+        target: AuditLogUser,
+    },
+    /// The group shout was changed
+    PostStatus {
+        text: String,
+    },
+    SpendGroupFunds {
+        amount: i64,
+        /// The name of the currency, e.g. `Robux`
+        currency: String,
+        /// What the funds were spent on
+        item: String,
+    },
+    UpdateRolesetRank {
+        role: AuditLogRole,
+        old_rank: i64,
+        new_rank: i64,
+    },
+    UpdateRolesetData {
+        /// The role with its new name
+        role: AuditLogRole,
+        old_name: String,
+        new_name: String,
+        old_description: String,
+        new_description: String,
+    },
+    ChangeOwner {
+        /// `None` when the group had no owner
+        old_owner: Option<AuditLogUser>,
+        new_owner: AuditLogUser,
+    },
+    Rename {
+        new_name: String,
+    },
+    ChangeDescription {
+        new_description: String,
+    },
+    /// An action without typed details, or with details that couldn't be read
+    Other {
+        action_type: GroupAuditLogActionType,
+        description: serde_json::Value,
+    },
+}
+
+// The details as Roblox sends them
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TargetDescription {
+    target_id: UserId,
+    target_name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ChangeRankDescription {
+    target_id: UserId,
+    target_name: String,
+    old_role_set_id: RoleId,
+    old_role_set_name: String,
+    new_role_set_id: RoleId,
+    new_role_set_name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DeletePostDescription {
+    target_id: UserId,
+    target_name: String,
+    post_desc: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PostStatusDescription {
+    text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SpendGroupFundsDescription {
+    amount: i64,
+    currency_type_name: String,
+    item_description: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct UpdateRolesetRankDescription {
+    role_set_id: RoleId,
+    role_set_name: String,
+    old_rank: i64,
+    new_rank: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct UpdateRolesetDataDescription {
+    role_set_id: RoleId,
+    old_name: String,
+    new_name: String,
+    #[serde(default)]
+    old_description: String,
+    #[serde(default)]
+    new_description: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ChangeOwnerDescription {
+    old_owner_id: Option<UserId>,
+    old_owner_name: Option<String>,
+    new_owner_id: UserId,
+    new_owner_name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RenameDescription {
+    new_name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ChangeDescriptionDescription {
+    new_description: String,
+}
+
+fn target(id: UserId, name: String) -> AuditLogUser {
+    AuditLogUser { id, name }
+}
+
+impl GroupAuditLogEntry {
+    /// Reads the details of the action, falling back to [`AuditLogAction::Other`]
+    pub fn action(&self) -> AuditLogAction {
+        use GroupAuditLogActionType as Action;
+
+        let action =
+            match self.action_type {
+                Action::ChangeRank => {
+                    self.parse()
+                        .map(|d: ChangeRankDescription| AuditLogAction::ChangeRank {
+                            target: target(d.target_id, d.target_name),
+                            old_role: AuditLogRole {
+                                id: d.old_role_set_id,
+                                name: d.old_role_set_name,
+                            },
+                            new_role: AuditLogRole {
+                                id: d.new_role_set_id,
+                                name: d.new_role_set_name,
+                            },
+                        })
+                }
+                Action::DeletePost => {
+                    self.parse()
+                        .map(|d: DeletePostDescription| AuditLogAction::DeletePost {
+                            target: target(d.target_id, d.target_name),
+                            post: d.post_desc,
+                        })
+                }
+                Action::RemoveMember => {
+                    self.parse()
+                        .map(|d: TargetDescription| AuditLogAction::RemoveMember {
+                            target: target(d.target_id, d.target_name),
+                        })
+                }
+                Action::AcceptJoinRequest => {
+                    self.parse()
+                        .map(|d: TargetDescription| AuditLogAction::AcceptJoinRequest {
+                            target: target(d.target_id, d.target_name),
+                        })
+                }
+                Action::DeclineJoinRequest => {
+                    self.parse()
+                        .map(|d: TargetDescription| AuditLogAction::DeclineJoinRequest {
+                            target: target(d.target_id, d.target_name),
+                        })
+                }
+                Action::PostStatus => self
+                    .parse()
+                    .map(|d: PostStatusDescription| AuditLogAction::PostStatus { text: d.text }),
+                Action::SpendGroupFunds => self.parse().map(|d: SpendGroupFundsDescription| {
+                    AuditLogAction::SpendGroupFunds {
+                        amount: d.amount,
+                        currency: d.currency_type_name,
+                        item: d.item_description,
+                    }
+                }),
+                Action::UpdateRolesetRank => self.parse().map(|d: UpdateRolesetRankDescription| {
+                    AuditLogAction::UpdateRolesetRank {
+                        role: AuditLogRole {
+                            id: d.role_set_id,
+                            name: d.role_set_name,
+                        },
+                        old_rank: d.old_rank,
+                        new_rank: d.new_rank,
+                    }
+                }),
+                Action::UpdateRolesetData => self.parse().map(|d: UpdateRolesetDataDescription| {
+                    AuditLogAction::UpdateRolesetData {
+                        role: AuditLogRole {
+                            id: d.role_set_id,
+                            name: d.new_name.clone(),
+                        },
+                        old_name: d.old_name,
+                        new_name: d.new_name,
+                        old_description: d.old_description,
+                        new_description: d.new_description,
+                    }
+                }),
+                Action::ChangeOwner => {
+                    self.parse()
+                        .map(|d: ChangeOwnerDescription| AuditLogAction::ChangeOwner {
+                            old_owner: d
+                                .old_owner_id
+                                .map(|id| target(id, d.old_owner_name.unwrap_or_default())),
+                            new_owner: target(d.new_owner_id, d.new_owner_name),
+                        })
+                }
+                Action::Rename => self
+                    .parse()
+                    .map(|d: RenameDescription| AuditLogAction::Rename {
+                        new_name: d.new_name,
+                    }),
+                Action::ChangeDescription => self.parse().map(|d: ChangeDescriptionDescription| {
+                    AuditLogAction::ChangeDescription {
+                        new_description: d.new_description,
+                    }
+                }),
+                _ => None,
+            };
+
+        action.unwrap_or_else(|| AuditLogAction::Other {
+            action_type: self.action_type.clone(),
+            description: self.description.clone(),
+        })
+    }
+
+    fn parse<T: DeserializeOwned>(&self) -> Option<T> {
+        T::deserialize(&self.description).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuditLogAction, AuditLogRole, AuditLogUser};
+    use crate::{
+        groups::GroupAuditLogActionType,
+        mock::{MockResponse, MockServer},
+        util::{
+            hosts::Domain,
+            ids::{GroupId, RoleId, UserId},
+            paging::PageLimit,
+        },
+    };
+
+    #[tokio::test]
+    async fn typed_audit_log() {
+        let entry = |action_type: &str, description: serde_json::Value| {
+            serde_json::json!({
+                "actor": {
+                    "user": { "hasVerifiedBadge": false, "userId": 1444131924, "username": "Clanny", "displayName": "Clanny" },
+                    "created": "2019-09-03T17:43:18.513Z",
+                    "updated": "2019-09-03T17:43:18.513Z",
+                },
+                "actionType": action_type,
+                "description": description,
+                "created": "2023-11-20T12:00:00.000Z",
+            })
+        };
+
+        let server = MockServer::start().await;
+        server.mock(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/audit-log",
+            MockResponse::page(
+                vec![
+                        entry("Change Rank", serde_json::json!({
+                            "TargetId": 375760054, "TargetName": "piano1029",
+                            "OldRoleSetId": 11, "OldRoleSetName": "Member",
+                            "NewRoleSetId": 13, "NewRoleSetName": "Admin",
+                        })),
+                        entry("SpendGroupFunds", serde_json::json!({
+                            "Amount": 500, "CurrencyTypeId": 1, "CurrencyTypeName": "Robux", "ItemDescription": "One-time payout",
+                        })),
+                        entry("UpdateRolesetRank", serde_json::json!({
+                            "RoleSetId": 12, "RoleSetName": "Trusted", "OldRank": 50, "NewRank": 60,
+                        })),
+                        entry("Change Rank", serde_json::json!({ "TargetId": "not a user" })),
+                        entry("Configure Something New", serde_json::json!({ "Anything": true })),
+                ],
+                None,
+            ),
+        );
+        let jar = server.jar().await;
+
+        let entries =
+            crate::groups::audit_log(&jar, GroupId(1), PageLimit::Limit10, None, None, None)
+                .await
+                .unwrap();
+        assert_eq!(entries.len(), 5);
+
+        assert_eq!(
+            entries[0].action(),
+            AuditLogAction::ChangeRank {
+                target: AuditLogUser {
+                    id: UserId(375760054),
+                    name: "piano1029".to_string(),
+                },
+                old_role: AuditLogRole {
+                    id: RoleId(11),
+                    name: "Member".to_string(),
+                },
+                new_role: AuditLogRole {
+                    id: RoleId(13),
+                    name: "Admin".to_string(),
+                },
+            }
+        );
+        assert!(matches!(
+            entries[1].action(),
+            AuditLogAction::SpendGroupFunds { amount: 500, ref currency, .. } if currency == "Robux"
+        ));
+        assert!(matches!(
+            entries[2].action(),
+            AuditLogAction::UpdateRolesetRank {
+                old_rank: 50,
+                new_rank: 60,
+                ..
+            }
+        ));
+
+        // Details that can't be read and actions that aren't known are kept as they are
+        assert!(matches!(
+            entries[3].action(),
+            AuditLogAction::Other {
+                action_type: GroupAuditLogActionType::ChangeRank,
+                ..
+            }
+        ));
+        let AuditLogAction::Other {
+            action_type,
+            description,
+        } = entries[4].action()
+        else {
+            panic!("expected an untyped action");
+        };
+        assert_eq!(
+            action_type,
+            GroupAuditLogActionType::Unknown("Configure Something New".to_string())
+        );
+        assert_eq!(description["Anything"], true);
+
+        // Action types are serialized by name, unknown ones with the name Roblox sent
+        assert_eq!(
+            serde_json::to_value(&entries[0].action_type).unwrap(),
+            "ChangeRank"
+        );
+        let unknown = serde_json::to_string(&entries[4].action_type).unwrap();
+        assert_eq!(unknown, r#""Configure Something New""#);
+        assert_eq!(
+            serde_json::from_str::<GroupAuditLogActionType>(&unknown).unwrap(),
+            entries[4].action_type
+        );
+
+        let serialized = serde_json::to_value(entries[2].action()).unwrap();
+        assert_eq!(serialized["type"], "updateRolesetRank");
+        assert_eq!(serialized["oldRank"], 50);
+    }
+}
@@ -0,0 +1,370 @@
+//! Watching a group's audit log for new entries.
+//!
+//! An [`AuditLogWatcher`] polls the audit log newest first and stops reading at the entries it
+//! has already seen, which are remembered with a [`HighWaterMark`]. Saving the mark to a file
+//! lets the watcher continue where it left off after a restart:
+//!
+//! ```no_run
+//! # use futures::StreamExt;
+//! # use rustyroblox::{groups::{AuditLogWatcher, GroupAuditLogActionType}, util::{ids::GroupId, jar::RequestJar}};
+//! # async fn alerts(jar: &RequestJar) -> Result<(), Box<rustyroblox::util::Error>> {
+//! let mut exiles = AuditLogWatcher::new(jar, GroupId(7370273))
+//!     .action_types(vec![GroupAuditLogActionType::RemoveMember])
+//!     .mark_path("exiles.mark")?
+//!     .stream();
+//!
+//! while let Some(entry) = exiles.next().await {
+//!     match entry {
+//!         Ok(entry) => println!("{} exiled someone", entry.actor.user.username),
+//!         Err(error) => println!("Couldn't read the audit log: {}", error),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use futures::{stream::BoxStream, FutureExt};
+use serde::{Deserialize, Serialize};
+
+use crate::util::{
+    export::{load_json, save_json_atomically},
+    ids::{GroupId, UserId},
+    jar::RequestJar,
+    paging::SortOrder,
+    timestamp::Timestamp,
+    Error,
+};
+
+use super::{audit_log_stream, poll_stream, GroupAuditLogActionType, GroupAuditLogEntry};
+
+/// How often the audit log is polled by default
+pub const DEFAULT_AUDIT_LOG_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The newest entry a watcher has seen. Several entries can share a timestamp, so every entry
+/// at the newest one is kept to tell them apart.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HighWaterMark {
+    pub created: Timestamp,
+    pub entries: Vec<MarkedEntry>,
+}
+
+/// An entry at the timestamp of a [`HighWaterMark`], told apart by who did what
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkedEntry {
+    pub actor: UserId,
+    pub action_type: GroupAuditLogActionType,
+    pub description: serde_json::Value,
+}
+
+impl MarkedEntry {
+    fn new(entry: &GroupAuditLogEntry) -> MarkedEntry {
+        MarkedEntry {
+            actor: entry.actor.user.user_id,
+            action_type: entry.action_type.clone(),
+            description: entry.description.clone(),
+        }
+    }
+}
+
+impl HighWaterMark {
+    /// Whether an entry is at or before the mark. Timestamps are compared by the point in time
+    /// they stand for, not by how Roblox wrote them.
+    pub fn has_seen(&self, entry: &GroupAuditLogEntry) -> bool {
+        entry.created < self.created
+            || (entry.created == self.created && self.entries.contains(&MarkedEntry::new(entry)))
+    }
+
+    /// Reads a mark, `None` if there is no file yet
+    ///
+    /// # Error codes
+    /// - Io: The file couldn't be read or isn't a mark
+    pub fn load(path: impl AsRef<Path>) -> Result<Option<HighWaterMark>, Box<Error>> {
+        load_json(path.as_ref())
+    }
+
+    /// Writes the mark to a temporary file first and then moves it into place
+    ///
+    /// # Error codes
+    /// - Io: The file couldn't be written
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<Error>> {
+        save_json_atomically(path.as_ref(), self)
+    }
+}
+
+/// Polls a group's audit log for entries that haven't been seen before
+pub struct AuditLogWatcher<'a> {
+    jar: &'a RequestJar,
+    group_id: GroupId,
+    actor: Option<UserId>,
+    action_types: Vec<GroupAuditLogActionType>,
+    interval: Duration,
+    max_pages: usize,
+    emit_existing: bool,
+    path: Option<PathBuf>,
+    mark: Option<HighWaterMark>,
+}
+
+impl<'a> AuditLogWatcher<'a> {
+    pub fn new(jar: &'a RequestJar, group_id: GroupId) -> AuditLogWatcher<'a> {
+        AuditLogWatcher {
+            jar,
+            group_id,
+            actor: None,
+            action_types: Vec::new(),
+            interval: DEFAULT_AUDIT_LOG_POLL_INTERVAL,
+            max_pages: 10,
+            emit_existing: false,
+            path: None,
+            mark: None,
+        }
+    }
+
+    /// Only watches the entries of a single user, filtered by Roblox
+    pub fn actor(mut self, user_id: UserId) -> Self {
+        self.actor = Some(user_id);
+        self
+    }
+
+    /// Only emits entries with one of these action types, an empty list emits everything
+    pub fn action_types(mut self, action_types: Vec<GroupAuditLogActionType>) -> Self {
+        self.action_types = action_types;
+        self
+    }
+
+    /// How long the stream waits between polls
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// How many pages of 100 entries a poll reads at most, when more entries than that were
+    /// added since the last poll the oldest of them are skipped
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
+
+    /// Whether the first poll without a mark emits the entries that are already in the log.
+    /// By default it only remembers where the log is, so only entries added later are emitted.
+    pub fn emit_existing(mut self, emit_existing: bool) -> Self {
+        self.emit_existing = emit_existing;
+        self
+    }
+
+    /// Starts at a mark from an earlier run
+    pub fn mark(mut self, mark: Option<HighWaterMark>) -> Self {
+        self.mark = mark;
+        self
+    }
+
+    /// Saves the mark to a file after every poll that found new entries, continuing from the
+    /// mark in it if it exists
+    ///
+    /// # Error codes
+    /// - Io: The mark couldn't be read
+    pub fn mark_path(mut self, path: impl AsRef<Path>) -> Result<Self, Box<Error>> {
+        let path = path.as_ref().to_path_buf();
+        self.mark = HighWaterMark::load(&path)?;
+        self.path = Some(path);
+        Ok(self)
+    }
+
+    /// The newest entry seen so far
+    pub fn high_water_mark(&self) -> Option<&HighWaterMark> {
+        self.mark.as_ref()
+    }
+
+    /// Reads the entries added since the last poll, oldest first
+    ///
+    /// # Error codes
+    /// Typed as [`GroupError`](crate::util::error_codes::GroupError).
+    /// - 1: Group is invalid or does not exist.
+    /// - 23: Insufficient permissions to complete the request.
+    ///
+    /// - Io: The mark couldn't be saved
+    pub async fn poll(&mut self) -> Result<Vec<GroupAuditLogEntry>, Box<Error>> {
+        let first_run = self.mark.is_none();
+        let mut paginator =
+            audit_log_stream(self.jar, self.group_id, self.actor, Some(SortOrder::Desc));
+
+        let mut fresh = Vec::new();
+        let mut pages = 0;
+        'pages: while let Some(page) = paginator.next_page().await? {
+            for entry in page {
+                if self.mark.as_ref().is_some_and(|mark| mark.has_seen(&entry)) {
+                    break 'pages;
+                }
+                fresh.push(entry);
+            }
+
+            pages += 1;
+            if pages >= self.max_pages || (first_run && !self.emit_existing) {
+                break;
+            }
+        }
+
+        if let Some(newest) = fresh.first() {
+            let mut entries = match &self.mark {
+                Some(mark) if mark.created == newest.created => mark.entries.clone(),
+                _ => Vec::new(),
+            };
+            for entry in fresh
+                .iter()
+                .take_while(|entry| entry.created == newest.created)
+            {
+                let entry = MarkedEntry::new(entry);
+                if !entries.contains(&entry) {
+                    entries.push(entry);
+                }
+            }
+
+            let mark = HighWaterMark {
+                created: newest.created.clone(),
+                entries,
+            };
+            if let Some(path) = &self.path {
+                mark.save(path)?;
+            }
+            self.mark = Some(mark);
+        }
+
+        if first_run && !self.emit_existing {
+            return Ok(Vec::new());
+        }
+
+        fresh.retain(|entry| {
+            self.action_types.is_empty() || self.action_types.contains(&entry.action_type)
+        });
+        fresh.reverse();
+        Ok(fresh)
+    }
+
+    /// Polls forever, emitting every new entry oldest first. A failed poll is emitted as an
+    /// error and polled again after the interval.
+    pub fn stream(self) -> BoxStream<'a, Result<GroupAuditLogEntry, Box<Error>>> {
+        let interval = self.interval;
+        poll_stream(self, interval, |watcher| watcher.poll().boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::{AuditLogWatcher, GroupAuditLogActionType, HighWaterMark};
+    use crate::{
+        mock::{mock_user, testing::temp_path, MockResponse, MockServer},
+        util::{
+            hosts::Domain,
+            ids::{GroupId, UserId},
+        },
+    };
+
+    /// A page of audit log entries made from (actor, action type, created)
+    fn audit_log_page(entries: &[(i64, &str, &str)]) -> MockResponse {
+        let data = entries
+            .iter()
+            .map(|(user_id, action_type, created)| {
+                serde_json::json!({
+                    "actor": {
+                        "user": mock_user(*user_id),
+                        "created": "2019-09-03T17:43:18.513Z",
+                        "updated": "2019-09-03T17:43:18.513Z",
+                    },
+                    "actionType": action_type,
+                    "description": {},
+                    "created": created,
+                })
+            })
+            .collect();
+        MockResponse::page(data, None)
+    }
+
+    #[tokio::test]
+    async fn audit_log_watcher() {
+        let path = temp_path("audit-log.mark");
+        let server = MockServer::start().await;
+        server.script(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/audit-log",
+            vec![
+                audit_log_page(&[
+                    (1, "Change Rank", "2023-11-20T12:00:03.000Z"),
+                    (2, "Change Rank", "2023-11-20T12:00:02.000Z"),
+                ]),
+                audit_log_page(&[
+                    (3, "Remove Member", "2023-11-20T12:00:04.000Z"),
+                    (2, "Change Rank", "2023-11-20T12:00:04.000Z"),
+                    (1, "Change Rank", "2023-11-20T12:00:03.000Z"),
+                    (2, "Change Rank", "2023-11-20T12:00:02.000Z"),
+                ]),
+                audit_log_page(&[
+                    (4, "Remove Member", "2023-11-20T12:00:05.000Z"),
+                    (1, "Change Rank", "2023-11-20T12:00:05.000Z"),
+                    (5, "Remove Member", "2023-11-20T12:00:04.000Z"),
+                    (2, "Remove Member", "2023-11-20T12:00:04Z"),
+                    (3, "Remove Member", "2023-11-20T12:00:04Z"),
+                    (2, "Change Rank", "2023-11-20T12:00:04.000Z"),
+                ]),
+            ],
+        );
+        let jar = server.jar().await;
+
+        // The first poll only remembers where the log is
+        let mut watcher = AuditLogWatcher::new(&jar, GroupId(1))
+            .mark_path(&path)
+            .unwrap();
+        assert!(watcher.poll().await.unwrap().is_empty());
+        let mark = watcher.high_water_mark().unwrap();
+        assert_eq!(mark.entries.len(), 1);
+        assert_eq!(mark.entries[0].actor, UserId(1));
+        assert_eq!(
+            mark.entries[0].action_type,
+            GroupAuditLogActionType::ChangeRank
+        );
+
+        // Entries after the mark are returned oldest first
+        let entries = watcher.poll().await.unwrap();
+        let actors = entries
+            .iter()
+            .map(|entry| entry.actor.user.user_id)
+            .collect::<Vec<_>>();
+        assert_eq!(actors, vec![UserId(2), UserId(3)]);
+        drop(watcher);
+
+        // A new watcher continues from the saved mark. An entry at the same time as the mark is
+        // still new when someone else did it or it's another action, and the mark's time is
+        // recognized however it's written.
+        let exiles = AuditLogWatcher::new(&jar, GroupId(1))
+            .action_types(vec![GroupAuditLogActionType::RemoveMember])
+            .interval(std::time::Duration::from_millis(1))
+            .mark_path(&path)
+            .unwrap()
+            .stream()
+            .take(3)
+            .map(|entry| entry.unwrap().actor.user.user_id)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(exiles, vec![UserId(2), UserId(5), UserId(4)]);
+
+        let mark = HighWaterMark::load(&path).unwrap().unwrap();
+        let actors = mark
+            .entries
+            .iter()
+            .map(|entry| entry.actor)
+            .collect::<Vec<_>>();
+        assert_eq!(actors, vec![UserId(4), UserId(1)]);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].query_param("sortOrder"), Some("Desc"));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,465 @@
+//! Membership changes for many users at once, like re-ranking or exiling everyone on a list.
+//!
+//! Every user gets an entry in the [`BulkReport`] saying whether they were changed, skipped or
+//! what went wrong, a failure for one user doesn't stop the others:
+//!
+//! ```no_run
+//! # use rustyroblox::{groups::{self, BulkOptions}, util::{ids::*, jar::RequestJar}};
+//! # async fn rank(jar: &RequestJar, user_ids: Vec<UserId>) {
+//! let report = groups::bulk_set_role(
+//!     jar,
+//!     GroupId(7370273),
+//!     user_ids,
+//!     RoleId(47932104),
+//!     BulkOptions::default(),
+//! )
+//! .await;
+//!
+//! for entry in report.failed() {
+//!     println!("Couldn't rank {}: {:?}", entry.user_id, entry.outcome);
+//! }
+//! # }
+//! ```
+//!
+//! The requests go through the jar, so they're throttled by its rate limiter as usual.
+
+use std::{collections::HashSet, future::Future};
+
+use futures::{stream, StreamExt};
+
+use crate::util::{
+    ids::{GroupId, RoleId, UserId},
+    jar::RequestJar,
+    Error,
+};
+
+use super::{join_requests, remove_user, set_role, user_role};
+
+/// How a bulk operation is run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BulkOptions {
+    /// How many users are handled at the same time
+    pub concurrency: usize,
+    /// Only works out what would be changed. Reads that decide whether a user is skipped are
+    /// still sent, but nothing is changed.
+    pub dry_run: bool,
+}
+
+impl Default for BulkOptions {
+    fn default() -> Self {
+        BulkOptions {
+            concurrency: 4,
+            dry_run: false,
+        }
+    }
+}
+
+impl BulkOptions {
+    /// The default options, without changing anything
+    pub fn dry_run() -> BulkOptions {
+        BulkOptions {
+            dry_run: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// Why a user was left alone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The user already has the role they'd be given
+    AlreadyOnRole,
+    /// The user isn't in the group
+    NotInGroup,
+    /// The user is on the list more than once, only the first one is handled
+    Duplicate,
+    /// The user has no pending join request, found in a dry run
+    NoJoinRequest,
+}
+
+/// What happened to a single user
+#[derive(Debug)]
+pub enum BulkOutcome {
+    /// The change was made
+    Done,
+    /// The change would be made, in a dry run
+    Planned,
+    Skipped(SkipReason),
+    /// The change failed, use [`Error::code`] to get the typed error code
+    Failed(Box<Error>),
+}
+
+#[derive(Debug)]
+pub struct BulkEntry {
+    pub user_id: UserId,
+    pub outcome: BulkOutcome,
+}
+
+/// The outcome for every user, in the order they were given
+#[derive(Debug, Default)]
+pub struct BulkReport {
+    pub entries: Vec<BulkEntry>,
+}
+
+impl BulkReport {
+    pub fn done(&self) -> impl Iterator<Item = &BulkEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry.outcome, BulkOutcome::Done))
+    }
+
+    pub fn planned(&self) -> impl Iterator<Item = &BulkEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry.outcome, BulkOutcome::Planned))
+    }
+
+    pub fn skipped(&self) -> impl Iterator<Item = &BulkEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry.outcome, BulkOutcome::Skipped(_)))
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &BulkEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| matches!(entry.outcome, BulkOutcome::Failed(_)))
+    }
+
+    /// Whether no user failed
+    pub fn is_success(&self) -> bool {
+        self.failed().next().is_none()
+    }
+}
+
+/// Handles every user with at most `concurrency` at a time, and puts the outcomes back in the
+/// order the users were given
+async fn run_bulk<F, Fut>(user_ids: Vec<UserId>, options: BulkOptions, operation: F) -> BulkReport
+where
+    F: Fn(UserId) -> Fut,
+    Fut: Future<Output = BulkOutcome>,
+{
+    let mut seen = HashSet::new();
+    let mut outcomes = stream::iter(user_ids.into_iter().enumerate())
+        .map(|(index, user_id)| {
+            let first = seen.insert(user_id);
+            let operation = &operation;
+            async move {
+                let outcome = if first {
+                    operation(user_id).await
+                } else {
+                    BulkOutcome::Skipped(SkipReason::Duplicate)
+                };
+                (index, BulkEntry { user_id, outcome })
+            }
+        })
+        .buffer_unordered(options.concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
+    outcomes.sort_by_key(|(index, _)| *index);
+    BulkReport {
+        entries: outcomes.into_iter().map(|(_, entry)| entry).collect(),
+    }
+}
+
+/// Runs a change unless it's a dry run
+async fn apply(
+    options: BulkOptions,
+    change: impl Future<Output = Result<(), Box<Error>>>,
+) -> BulkOutcome {
+    if options.dry_run {
+        return BulkOutcome::Planned;
+    }
+
+    match change.await {
+        Ok(()) => BulkOutcome::Done,
+        Err(error) => BulkOutcome::Failed(error),
+    }
+}
+
+/// Runs a join request change unless it's a dry run. A dry run checks that the user has a
+/// pending join request instead, and skips them when they don't.
+async fn apply_join_request(
+    jar: &RequestJar,
+    group_id: GroupId,
+    user_id: UserId,
+    options: BulkOptions,
+    change: impl Future<Output = Result<(), Box<Error>>>,
+) -> BulkOutcome {
+    if !options.dry_run {
+        return apply(options, change).await;
+    }
+
+    match join_requests::single::get(jar, group_id, user_id).await {
+        Ok(Some(_)) => BulkOutcome::Planned,
+        Ok(None) => BulkOutcome::Skipped(SkipReason::NoJoinRequest),
+        Err(error) => BulkOutcome::Failed(error),
+    }
+}
+
+/// Sets the role of many users in a group. Users who already have the role or aren't in the
+/// group are skipped, which takes a read per user but saves ranking requests, which are
+/// limited much more strictly.
+///
+/// # Error codes
+/// Failures are in the report, typed as [`SetRoleError`](crate::util::error_codes::SetRoleError).
+/// - 1: The group is invalid or does not exist.
+/// - 2: The roleset is invalid or does not exist.
+/// - 4: You do not have permission to manage this member.
+/// - 23: You cannot change your own role.
+pub async fn bulk_set_role(
+    jar: &RequestJar,
+    group_id: GroupId,
+    user_ids: Vec<UserId>,
+    role_id: RoleId,
+    options: BulkOptions,
+) -> BulkReport {
+    run_bulk(user_ids, options, |user_id| async move {
+        match user_role(jar, group_id, user_id).await {
+            Ok(role) if role.id == role_id => BulkOutcome::Skipped(SkipReason::AlreadyOnRole),
+            Ok(_) => apply(options, set_role(jar, group_id, user_id, role_id)).await,
+            Err(error) if matches!(*error, Error::UserNotInGroup { .. }) => {
+                BulkOutcome::Skipped(SkipReason::NotInGroup)
+            }
+            Err(error) => BulkOutcome::Failed(error),
+        }
+    })
+    .await
+}
+
+/// Removes many users from a group, users who aren't in it are skipped.
+///
+/// # Error codes
+/// Failures are in the report, typed as [`SetRoleError`](crate::util::error_codes::SetRoleError).
+/// - 1: The group is invalid or does not exist.
+/// - 4: You do not have permission to manage this member.
+pub async fn bulk_remove_users(
+    jar: &RequestJar,
+    group_id: GroupId,
+    user_ids: Vec<UserId>,
+    options: BulkOptions,
+) -> BulkReport {
+    run_bulk(user_ids, options, |user_id| async move {
+        match user_role(jar, group_id, user_id).await {
+            Ok(_) => apply(options, remove_user(jar, group_id, user_id)).await,
+            Err(error) if matches!(*error, Error::UserNotInGroup { .. }) => {
+                BulkOutcome::Skipped(SkipReason::NotInGroup)
+            }
+            Err(error) => BulkOutcome::Failed(error),
+        }
+    })
+    .await
+}
+
+/// Accepts the join requests of many users. Each one is accepted on its own rather than
+/// with [`join_requests::all::accept`], so one invalid request doesn't fail the others.
+/// A dry run skips the users without a pending join request.
+///
+/// # Error codes
+/// Failures are in the report, typed as [`JoinRequestError`](crate::util::error_codes::JoinRequestError).
+/// - 1: The group is invalid or does not exist.
+/// - 6: You are already in the maximum number of groups.
+/// - 19: You have insufficient permissions for this request.
+/// - 20: The group join request is invalid.
+pub async fn bulk_accept_join_requests(
+    jar: &RequestJar,
+    group_id: GroupId,
+    user_ids: Vec<UserId>,
+    options: BulkOptions,
+) -> BulkReport {
+    run_bulk(user_ids, options, |user_id| {
+        apply_join_request(
+            jar,
+            group_id,
+            user_id,
+            options,
+            join_requests::single::accept(jar, group_id, user_id),
+        )
+    })
+    .await
+}
+
+/// Declines the join requests of many users, each one on its own. A dry run skips the users
+/// without a pending join request.
+///
+/// # Error codes
+/// Failures are in the report, typed as [`JoinRequestError`](crate::util::error_codes::JoinRequestError).
+/// - 3: The user is invalid or does not exist.
+/// - 4: You do not have permission to manage this member.
+pub async fn bulk_decline_join_requests(
+    jar: &RequestJar,
+    group_id: GroupId,
+    user_ids: Vec<UserId>,
+    options: BulkOptions,
+) -> BulkReport {
+    run_bulk(user_ids, options, |user_id| {
+        apply_join_request(
+            jar,
+            group_id,
+            user_id,
+            options,
+            join_requests::single::decline(jar, group_id, user_id),
+        )
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BulkOptions, BulkOutcome, SkipReason};
+    use crate::{
+        mock::{mock_user, testing::ranked_group_server, MockResponse, MockServer},
+        util::{
+            hosts::Domain,
+            ids::{GroupId, RoleId, UserId},
+        },
+    };
+
+    #[tokio::test]
+    async fn bulk_set_role_report() {
+        use crate::util::error_codes::SetRoleError;
+
+        let server = ranked_group_server().await;
+        server.mock(
+            Domain::Groups,
+            "PATCH",
+            "/v1/groups/1/users/4",
+            MockResponse::errors(
+                403,
+                &[(4, "You do not have permission to manage this member.")],
+            ),
+        );
+        let jar = server.jar().await;
+        let user_ids = [2, 3, 2, 5, 4].map(UserId).to_vec();
+
+        let report = crate::groups::bulk_set_role(
+            &jar,
+            GroupId(1),
+            user_ids.clone(),
+            RoleId(13),
+            BulkOptions::dry_run(),
+        )
+        .await;
+        assert_eq!(report.planned().count(), 2);
+        assert!(server
+            .requests()
+            .iter()
+            .all(|request| request.method == "GET"));
+
+        let options = BulkOptions {
+            concurrency: 2,
+            ..Default::default()
+        };
+        let report =
+            crate::groups::bulk_set_role(&jar, GroupId(1), user_ids, RoleId(13), options).await;
+        let entries = &report.entries;
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.user_id)
+                .collect::<Vec<_>>(),
+            [2, 3, 2, 5, 4].map(UserId)
+        );
+        assert!(matches!(entries[0].outcome, BulkOutcome::Done));
+        assert!(matches!(
+            entries[1].outcome,
+            BulkOutcome::Skipped(SkipReason::AlreadyOnRole)
+        ));
+        assert!(matches!(
+            entries[2].outcome,
+            BulkOutcome::Skipped(SkipReason::Duplicate)
+        ));
+        assert!(matches!(
+            entries[3].outcome,
+            BulkOutcome::Skipped(SkipReason::NotInGroup)
+        ));
+        let BulkOutcome::Failed(error) = &entries[4].outcome else {
+            panic!("expected a failure, got {:?}", entries[4].outcome);
+        };
+        assert_eq!(
+            error.code::<SetRoleError>(),
+            Some(SetRoleError::CannotManageMember)
+        );
+        assert!(!report.is_success());
+
+        let patches = server
+            .requests()
+            .into_iter()
+            .filter(|request| request.method == "PATCH")
+            .map(|request| request.path)
+            .collect::<Vec<_>>();
+        assert_eq!(patches.len(), 2);
+        assert!(patches.contains(&"/groups/v1/groups/1/users/2".to_string()));
+    }
+
+    #[tokio::test]
+    async fn bulk_join_requests() {
+        let server = MockServer::start().await;
+        for user_id in [1, 2] {
+            server.mock(
+                Domain::Groups,
+                "POST",
+                &format!("/v1/groups/1/join-requests/users/{}", user_id),
+                MockResponse::json("{}"),
+            );
+        }
+        server.mock(
+            Domain::Groups,
+            "POST",
+            "/v1/groups/1/join-requests/users/3",
+            MockResponse::errors(400, &[(20, "The group join request is invalid.")]),
+        );
+        let jar = server.jar().await;
+
+        let report = crate::groups::bulk_accept_join_requests(
+            &jar,
+            GroupId(1),
+            [1, 2, 3].map(UserId).to_vec(),
+            BulkOptions::default(),
+        )
+        .await;
+        assert_eq!(report.done().count(), 2);
+        assert_eq!(report.failed().next().unwrap().user_id, UserId(3));
+
+        // A dry run only plans to decline the users that asked to join
+        server.mock(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/join-requests/users/1",
+            MockResponse::json(
+                &serde_json::json!({
+                    "requestor": mock_user(1),
+                    "created": "2023-11-20T12:00:00.000Z",
+                })
+                .to_string(),
+            ),
+        );
+        server.mock(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/join-requests/users/2",
+            MockResponse::json("null"),
+        );
+        let report = crate::groups::bulk_decline_join_requests(
+            &jar,
+            GroupId(1),
+            [1, 2].map(UserId).to_vec(),
+            BulkOptions::dry_run(),
+        )
+        .await;
+        assert!(matches!(report.entries[0].outcome, BulkOutcome::Planned));
+        assert!(matches!(
+            report.entries[1].outcome,
+            BulkOutcome::Skipped(SkipReason::NoJoinRequest)
+        ));
+        assert_eq!(
+            server
+                .requests()
+                .iter()
+                .filter(|request| request.method == "POST")
+                .count(),
+            3
+        );
+    }
+}
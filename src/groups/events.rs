@@ -0,0 +1,560 @@
+//! Typed events for a group's activity, found by polling the endpoints that list it.
+//!
+//! [`GroupEvents`] polls each enabled [`EventSource`] on its own interval and merges what it
+//! finds into one stream:
+//!
+//! ```no_run
+//! # use std::time::Duration;
+//! # use futures::StreamExt;
+//! # use rustyroblox::{groups::{EventSource, GroupEvent, GroupEvents}, util::{ids::GroupId, jar::RequestJar}};
+//! # async fn announce(jar: &RequestJar) {
+//! let mut events = GroupEvents::new(jar, GroupId(7370273))
+//!     .source(EventSource::JoinRequests, Duration::from_secs(30))
+//!     .source(EventSource::Group, Duration::from_secs(60))
+//!     .stream();
+//!
+//! while let Some(event) = events.next().await {
+//!     match event {
+//!         Ok(GroupEvent::JoinRequest { request }) => {
+//!             println!("{} wants to join", request.requestor.username)
+//!         }
+//!         Ok(GroupEvent::MemberCountChanged { old, new }) => println!("{} -> {} members", old, new),
+//!         Ok(_) => {}
+//!         Err(error) => println!("Couldn't poll: {}", error),
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! Sources are only polled while the stream is, so a consumer that falls behind slows the
+//! polling down instead of events piling up in memory. Events found by a single poll are kept
+//! until they have been taken from the stream.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    time::Duration,
+};
+
+use futures::{
+    future::BoxFuture,
+    stream::{self, BoxStream},
+    FutureExt, StreamExt,
+};
+use rspc::Type;
+use serde::{Deserialize, Serialize};
+
+use crate::util::{
+    ids::{GroupId, UserId, WallPostId},
+    jar::RequestJar,
+    paging::{PageLimit, SortOrder},
+    timestamp::Timestamp,
+    Error,
+};
+
+use super::{
+    ally_requests, enemy_requests, group_by_id, join_requests::all::JoinRequest, wall,
+    AuditLogWatcher, Group, GroupAuditLogEntry, GroupShout, WallPost,
+};
+
+/// Something that happened in a group
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum GroupEvent {
+    JoinRequest {
+        request: JoinRequest,
+    },
+    WallPost {
+        post: WallPost,
+    },
+    /// The shout was changed, `None` when there was or is no shout
+    ShoutChanged {
+        old: Option<GroupShout>,
+        new: Option<GroupShout>,
+    },
+    MemberCountChanged {
+        old: i64,
+        new: i64,
+    },
+    /// Another group asked to become allies
+    AllyRequest {
+        group: Group,
+    },
+    /// Another group declared the group an enemy and is waiting for it to be accepted
+    EnemyRequest {
+        group: Group,
+    },
+    AuditLog {
+        entry: GroupAuditLogEntry,
+    },
+}
+
+/// The endpoints events are found with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventSource {
+    /// New join requests, from [`join_requests::all::get`](super::join_requests::all::get)
+    JoinRequests,
+    /// New wall posts, from [`wall`]
+    Wall,
+    /// Shout and member count changes, from [`group_by_id`]
+    Group,
+    /// New ally and enemy requests, from [`ally_requests`] and [`enemy_requests`]
+    Relationships,
+    /// New audit log entries, from an [`AuditLogWatcher`]
+    AuditLog,
+}
+
+enum SourceState<'a> {
+    /// The newest request seen so far, with the requesters at that time. A user who requests
+    /// again gets a newer request, so they're noticed again.
+    JoinRequests {
+        newest: Option<Timestamp>,
+        requesters: HashSet<UserId>,
+    },
+    Wall(Option<WallPostId>),
+    Group {
+        shout: Option<GroupShout>,
+        member_count: Option<i64>,
+    },
+    Relationships {
+        allies: HashSet<GroupId>,
+        enemies: HashSet<GroupId>,
+    },
+    AuditLog(Box<AuditLogWatcher<'a>>),
+}
+
+/// A single source with what it has seen so far
+struct Poller<'a> {
+    jar: &'a RequestJar,
+    group_id: GroupId,
+    interval: Duration,
+    emit_existing: bool,
+    started: bool,
+    state: SourceState<'a>,
+}
+
+fn same_shout(a: &Option<GroupShout>, b: &Option<GroupShout>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            a.body == b.body && a.updated == b.updated && a.poster.user_id == b.poster.user_id
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// The groups that weren't in `seen` before, `seen` is replaced by the groups now listed
+fn new_groups(seen: &mut HashSet<GroupId>, groups: Vec<Group>) -> Vec<Group> {
+    let previous = std::mem::replace(seen, groups.iter().map(|group| group.id).collect());
+    groups
+        .into_iter()
+        .filter(|group| !previous.contains(&group.id))
+        .collect()
+}
+
+impl<'a> Poller<'a> {
+    async fn poll(&mut self) -> Result<Vec<GroupEvent>, Box<Error>> {
+        let (jar, group_id) = (self.jar, self.group_id);
+
+        let events = match &mut self.state {
+            SourceState::JoinRequests { newest, requesters } => {
+                let seen = |request: &JoinRequest| {
+                    newest.as_ref().is_some_and(|newest| {
+                        request.created < *newest
+                            || (request.created == *newest
+                                && requesters.contains(&request.requestor.user_id))
+                    })
+                };
+
+                let mut paginator =
+                    super::join_requests::all::stream(jar, group_id, Some(SortOrder::Desc));
+                let mut requests = Vec::new();
+                'pages: while let Some(page) = paginator.next_page().await? {
+                    for request in page {
+                        if seen(&request) {
+                            break 'pages;
+                        }
+                        requests.push(request);
+                    }
+
+                    // Only the newest requests are needed to know where to stop next time
+                    if !self.started && !self.emit_existing {
+                        break;
+                    }
+                }
+
+                if let Some(first) = requests.first() {
+                    if newest.as_ref() != Some(&first.created) {
+                        requesters.clear();
+                    }
+                    requesters.extend(
+                        requests
+                            .iter()
+                            .take_while(|request| request.created == first.created)
+                            .map(|request| request.requestor.user_id),
+                    );
+                    *newest = Some(first.created.clone());
+                }
+
+                requests
+                    .into_iter()
+                    .rev()
+                    .map(|request| GroupEvent::JoinRequest { request })
+                    .collect()
+            }
+            SourceState::Wall(newest) => {
+                let posts = wall(
+                    jar,
+                    group_id,
+                    PageLimit::Limit100,
+                    Some(SortOrder::Desc),
+                    None,
+                )
+                .await?;
+                let previous = *newest;
+                *newest = posts.iter().map(|post| post.id).max().or(previous);
+
+                posts
+                    .into_iter()
+                    .rev()
+                    .filter(|post| previous.is_none_or(|previous| post.id > previous))
+                    .map(|post| GroupEvent::WallPost { post })
+                    .collect()
+            }
+            SourceState::Group {
+                shout,
+                member_count,
+            } => {
+                let group = group_by_id(jar, group_id).await?;
+                let mut events = Vec::new();
+
+                if self.started && !same_shout(shout, &group.shout) {
+                    events.push(GroupEvent::ShoutChanged {
+                        old: shout.clone(),
+                        new: group.shout.clone(),
+                    });
+                }
+                if let (true, Some(old), Some(new)) =
+                    (self.started, *member_count, group.member_count)
+                {
+                    if old != new {
+                        events.push(GroupEvent::MemberCountChanged { old, new });
+                    }
+                }
+
+                *shout = group.shout;
+                *member_count = group.member_count.or(*member_count);
+                events
+            }
+            SourceState::Relationships { allies, enemies } => {
+                let ally_groups = ally_requests(jar, group_id).await?.groups;
+                let enemy_groups = enemy_requests(jar, group_id).await?.groups;
+
+                new_groups(allies, ally_groups)
+                    .into_iter()
+                    .map(|group| GroupEvent::AllyRequest { group })
+                    .chain(
+                        new_groups(enemies, enemy_groups)
+                            .into_iter()
+                            .map(|group| GroupEvent::EnemyRequest { group }),
+                    )
+                    .collect()
+            }
+            // The watcher keeps its own mark and handles the first poll itself
+            SourceState::AuditLog(watcher) => {
+                let entries = watcher.poll().await?;
+                self.started = true;
+                return Ok(entries
+                    .into_iter()
+                    .map(|entry| GroupEvent::AuditLog { entry })
+                    .collect());
+            }
+        };
+
+        let first = !self.started;
+        self.started = true;
+        if first && !self.emit_existing {
+            return Ok(Vec::new());
+        }
+        Ok(events)
+    }
+
+    fn stream(self) -> BoxStream<'a, Result<GroupEvent, Box<Error>>> {
+        let interval = self.interval;
+        poll_stream(self, interval, |poller| poller.poll().boxed())
+    }
+}
+
+/// Polls right away and then after every interval, emitting what each poll found one at a
+/// time. A failed poll is emitted as an error and polled again after the interval.
+pub(crate) fn poll_stream<'a, S, T, F>(
+    state: S,
+    interval: Duration,
+    poll: F,
+) -> BoxStream<'a, Result<T, Box<Error>>>
+where
+    S: Send + 'a,
+    T: Send + 'a,
+    F: for<'s> FnMut(&'s mut S) -> BoxFuture<'s, Result<Vec<T>, Box<Error>>> + Send + 'a,
+{
+    stream::unfold(
+        (state, poll, VecDeque::new(), true),
+        move |(mut state, mut poll, mut pending, mut first)| async move {
+            loop {
+                if let Some(item) = pending.pop_front() {
+                    return Some((Ok(item), (state, poll, pending, first)));
+                }
+
+                if !first {
+                    tokio::time::sleep(interval).await;
+                }
+                first = false;
+
+                match poll(&mut state).await {
+                    Ok(items) => pending.extend(items),
+                    Err(error) => return Some((Err(error), (state, poll, pending, first))),
+                }
+            }
+        },
+    )
+    .boxed()
+}
+
+/// Polls a group for events from the enabled sources, merged into one stream
+pub struct GroupEvents<'a> {
+    jar: &'a RequestJar,
+    group_id: GroupId,
+    sources: Vec<(EventSource, Duration)>,
+    emit_existing: bool,
+}
+
+impl<'a> GroupEvents<'a> {
+    /// No sources are enabled until they're added with [`source`](Self::source)
+    pub fn new(jar: &'a RequestJar, group_id: GroupId) -> GroupEvents<'a> {
+        GroupEvents {
+            jar,
+            group_id,
+            sources: Vec::new(),
+            emit_existing: false,
+        }
+    }
+
+    /// Polls a source every `interval`, adding a source again changes its interval
+    pub fn source(mut self, source: EventSource, interval: Duration) -> Self {
+        self.sources.retain(|(existing, _)| *existing != source);
+        self.sources.push((source, interval));
+        self
+    }
+
+    /// Whether the first poll of each source emits what's already there, like the join requests
+    /// that are pending at the start. By default the first poll only remembers what it found.
+    /// Changes to the shout and member count always need a second poll to be noticed.
+    pub fn emit_existing(mut self, emit_existing: bool) -> Self {
+        self.emit_existing = emit_existing;
+        self
+    }
+
+    /// Polls every source forever. A failed poll is emitted as an error and polled again after
+    /// the source's interval.
+    pub fn stream(self) -> BoxStream<'a, Result<GroupEvent, Box<Error>>> {
+        let streams = self.sources.into_iter().map(|(source, interval)| {
+            let state = match source {
+                EventSource::JoinRequests => SourceState::JoinRequests {
+                    newest: None,
+                    requesters: HashSet::new(),
+                },
+                EventSource::Wall => SourceState::Wall(None),
+                EventSource::Group => SourceState::Group {
+                    shout: None,
+                    member_count: None,
+                },
+                EventSource::Relationships => SourceState::Relationships {
+                    allies: HashSet::new(),
+                    enemies: HashSet::new(),
+                },
+                EventSource::AuditLog => SourceState::AuditLog(Box::new(
+                    AuditLogWatcher::new(self.jar, self.group_id).emit_existing(self.emit_existing),
+                )),
+            };
+
+            Poller {
+                jar: self.jar,
+                group_id: self.group_id,
+                interval,
+                emit_existing: self.emit_existing,
+                started: false,
+                state,
+            }
+            .stream()
+        });
+
+        stream::select_all(streams).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::{EventSource, GroupEvent, GroupEvents};
+    use crate::{
+        mock::{mock_user, MockResponse, MockServer},
+        util::{
+            hosts::Domain,
+            ids::{GroupId, UserId, WallPostId},
+        },
+    };
+
+    fn group_event_group(member_count: i64, shout: Option<&str>) -> MockResponse {
+        let shout = shout.map(|body| {
+            serde_json::json!({
+                "body": body,
+                "poster": mock_user(1),
+                "created": "2023-11-20T12:00:00.000Z",
+                "updated": "2023-11-20T12:00:00.000Z",
+            })
+        });
+        MockResponse::json(
+            &serde_json::json!({
+                "id": 1,
+                "name": "Group",
+                "description": "",
+                "owner": mock_user(1),
+                "shout": shout,
+                "memberCount": member_count,
+                "isBuildersClubOnly": false,
+                "publicEntryAllowed": true,
+                "isLocked": false,
+                "hasVerifiedBadge": false,
+            })
+            .to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn group_events() {
+        let join_request = |user_id: i64, created: &str| serde_json::json!({ "requestor": mock_user(user_id), "created": created });
+        let wall_post = |id: i64| {
+            serde_json::json!({
+                "id": id,
+                "poster": mock_user(1),
+                "body": format!("post {}", id),
+                "created": "2023-11-20T12:00:00.000Z",
+                "updated": "2023-11-20T12:00:00.000Z",
+            })
+        };
+
+        let server = MockServer::start().await;
+        server.script(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/join-requests",
+            vec![
+                MockResponse::page(
+                    vec![
+                        join_request(2, "2023-11-20T12:00:00.000Z"),
+                        join_request(1, "2023-11-20T11:00:00.000Z"),
+                    ],
+                    None,
+                ),
+                // User 1 asks again after their first request was declined
+                MockResponse::page(
+                    vec![
+                        join_request(1, "2023-11-20T12:00:03.000Z"),
+                        join_request(4, "2023-11-20T12:00:02.000Z"),
+                        join_request(3, "2023-11-20T12:00:01.000Z"),
+                    ],
+                    Some("page2"),
+                ),
+            ],
+        );
+        // Paging stops at the first request that was seen before
+        server.mock(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/join-requests?cursor=page2",
+            MockResponse::page(
+                vec![join_request(2, "2023-11-20T12:00:00.000Z")],
+                Some("page3"),
+            ),
+        );
+        server.script(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/wall/posts",
+            vec![
+                MockResponse::page(vec![wall_post(10)], None),
+                MockResponse::page(vec![wall_post(12), wall_post(11), wall_post(10)], None),
+            ],
+        );
+        server.script(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1",
+            vec![
+                group_event_group(100, None),
+                group_event_group(101, Some("Hello")),
+            ],
+        );
+        let jar = server.jar().await;
+
+        let interval = std::time::Duration::from_millis(1);
+        let events = GroupEvents::new(&jar, GroupId(1))
+            .source(EventSource::JoinRequests, interval)
+            .source(EventSource::Wall, interval)
+            .source(EventSource::Group, interval)
+            .stream()
+            .filter_map(|event| async move { event.ok() })
+            .take(7)
+            .collect::<Vec<_>>()
+            .await;
+
+        // Only what's new since the first poll, oldest first within each source
+        let join_requests = events
+            .iter()
+            .filter_map(|event| match event {
+                GroupEvent::JoinRequest { request } => Some(request.requestor.user_id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(join_requests, vec![UserId(3), UserId(4), UserId(1)]);
+
+        let posts = events
+            .iter()
+            .filter_map(|event| match event {
+                GroupEvent::WallPost { post } => Some(post.id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(posts, vec![WallPostId(11), WallPostId(12)]);
+
+        assert!(events.iter().any(|event| matches!(
+            event,
+            GroupEvent::ShoutChanged { old: None, new: Some(shout) } if shout.body == "Hello"
+        )));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, GroupEvent::MemberCountChanged { old: 100, new: 101 })));
+
+        let requests = server.requests();
+        for path in [
+            "/groups/v1/groups/1/wall/posts",
+            "/groups/v1/groups/1/join-requests",
+        ] {
+            let request = requests
+                .iter()
+                .find(|request| request.path == path)
+                .unwrap();
+            assert_eq!(request.query_param("sortOrder"), Some("Desc"));
+        }
+        assert!(requests
+            .iter()
+            .any(|request| request.query_param("cursor") == Some("page2")));
+        assert!(!requests
+            .iter()
+            .any(|request| request.query_param("cursor") == Some("page3")));
+    }
+}
@@ -5,9 +5,13 @@ use strum_macros::{Display, EnumString};
 use crate::{
     users::{whoami, MinimalGroupUser},
     util::{
+        hosts::Domain,
+        ids::{GroupId, UserId},
         jar::RequestJar,
-        paging::{get_page, PageLimit, SortOrder},
-        responses::DataWrapper,
+        paging::{get_page, PageLimit, Paginator, SortOrder},
+        responses::{DataWrapper, EmptyResponse},
+        timestamp::Timestamp,
+        url::UrlBuilder,
         Error,
     },
 };
@@ -17,7 +21,7 @@ use super::{permissions::GroupPermissions, roles::GroupRole};
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct Group {
-    pub id: i64,
+    pub id: GroupId,
     pub name: String,
     pub description: String,
     pub owner: MinimalGroupUser,
@@ -32,7 +36,7 @@ pub struct Group {
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct MinimalGroup {
-    pub id: i64,
+    pub id: GroupId,
     pub name: String,
     pub member_count: i64,
     pub has_verified_badge: bool,
@@ -43,16 +47,17 @@ pub struct MinimalGroup {
 pub struct GroupShout {
     pub body: String,
     pub poster: MinimalGroupUser,
-    pub created: String,
-    pub updated: String,
+    pub created: Timestamp,
+    pub updated: Timestamp,
 }
 
 /// Gets a group by its group ID
 ///
 /// # Error codes
+/// Typed as [`GroupError`](crate::util::error_codes::GroupError).
 /// - 1: Group is invalid or does not exist.
-pub async fn group_by_id(jar: &RequestJar, group_id: i64) -> Result<Group, Box<Error>> {
-    let url = format!("https://groups.roblox.com/v1/groups/{}", group_id);
+pub async fn group_by_id(jar: &RequestJar, group_id: GroupId) -> Result<Group, Box<Error>> {
+    let url = format!("{}/v1/groups/{}", jar.base_url(Domain::Groups), group_id);
     let response = jar.get_json::<Group>(&url).await?;
     Ok(response)
 }
@@ -62,19 +67,24 @@ pub async fn group_by_id(jar: &RequestJar, group_id: i64) -> Result<Group, Box<E
 pub struct GroupAuditLogEntry {
     pub actor: GroupAuditLogActor,
     pub action_type: GroupAuditLogActionType,
-    //description: , // FIXME: ??? It shows an empty object in the docs
-    pub created: String,
+    /// The details of the action as Roblox sent them, use [`action`](Self::action) to read them
+    #[serde(default)]
+    pub description: serde_json::Value,
+    pub created: Timestamp,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct GroupAuditLogActor {
     pub user: MinimalGroupUser,
-    pub created: String,
-    pub updated: String,
+    pub created: Timestamp,
+    pub updated: Timestamp,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Display, EnumString, Type)]
+/// Deserialized leniently, Roblox sends some of these with spaces (`Change Rank`) and adds new
+/// ones over time, which become [`Unknown`](GroupAuditLogActionType::Unknown) with the name
+/// Roblox sent. Serialized as that name, so unknown actions survive a round trip.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Display, EnumString, Type)]
 pub enum GroupAuditLogActionType {
     // This is imported from the docs's HTML
     DeletePost,
@@ -121,58 +131,107 @@ pub enum GroupAuditLogActionType {
     PublishPlace,
     UpdateRolesetRank,
     UpdateRolesetData,
+    /// An action this version doesn't know about, with its name as Roblox sent it
+    #[strum(default)]
+    #[specta(skip)]
+    Unknown(String),
+}
+
+impl Serialize for GroupAuditLogActionType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for GroupAuditLogActionType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Ok(match raw.replace(' ', "").parse() {
+            Ok(GroupAuditLogActionType::Unknown(_)) | Err(_) => {
+                GroupAuditLogActionType::Unknown(raw)
+            }
+            Ok(action_type) => action_type,
+        })
+    }
 }
 
 /// Gets the audit log for a group
 ///
 /// # Error codes
+/// Typed as [`GroupError`](crate::util::error_codes::GroupError).
 /// - 1: Group is invalid or does not exist.
 /// - 23: Insufficient permissions to complete the request.
 pub async fn audit_log(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
     limit: PageLimit,
-    user_id: Option<i64>,
+    user_id: Option<UserId>,
     sort_order: Option<SortOrder>,
-    //cursor: Option<String>,
+    cursor: Option<String>,
 ) -> Result<Vec<GroupAuditLogEntry>, Box<Error>> {
-    let mut url = format!("https://groups.roblox.com/v1/groups/{}/audit-log", group_id);
-    if user_id.is_some() {
-        let user_id = user_id.unwrap();
-        url = format!("{}?userId={}", url, user_id);
-    }
-    url = format!("{}&sortOrder={}", url, sort_order.unwrap_or(SortOrder::Asc));
-
-    let response = get_page(jar, url.as_str(), limit, None).await?; // TODO: cursor
+    let url = audit_log_url(jar, group_id, user_id, sort_order);
+    let response = get_page(jar, url, limit, cursor).await?;
     Ok(response)
 }
 
+/// Goes through a group's entire audit log, see [`audit_log`] for the error codes
+pub fn audit_log_stream(
+    jar: &RequestJar,
+    group_id: GroupId,
+    user_id: Option<UserId>,
+    sort_order: Option<SortOrder>,
+) -> Paginator<'_, GroupAuditLogEntry> {
+    Paginator::new(jar, audit_log_url(jar, group_id, user_id, sort_order))
+}
+
+fn audit_log_url(
+    jar: &RequestJar,
+    group_id: GroupId,
+    user_id: Option<UserId>,
+    sort_order: Option<SortOrder>,
+) -> UrlBuilder {
+    jar.url(
+        Domain::Groups,
+        &format!("/v1/groups/{}/audit-log", group_id),
+    )
+    .query_opt("userId", user_id)
+    .query(
+        "sortOrder",
+        sort_order.unwrap_or(SortOrder::Asc).get_sort_order_string(),
+    )
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct GroupNameHistoryEntry {
     pub name: String,
-    pub created: String,
+    pub created: Timestamp,
 }
 
 /// Gets the name history for a group
 ///
 /// # Error codes
+/// Typed as [`GroupError`](crate::util::error_codes::GroupError).
 /// - 1: Group is invalid or does not exist.
 /// - 23: Insufficient permissions to complete the request.
 pub async fn name_history(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
     limit: PageLimit,
     sort_order: Option<SortOrder>,
-    //cursor: Option<String>,
+    cursor: Option<String>,
 ) -> Result<Vec<GroupNameHistoryEntry>, Box<Error>> {
-    let mut url = format!(
-        "https://groups.roblox.com/v1/groups/{}/name-history",
-        group_id
-    );
-    url = format!("{}?sortOrder={}", url, sort_order.unwrap_or(SortOrder::Asc));
-
-    let response = get_page(jar, url.as_str(), limit, None).await?; // TODO: cursor
+    let url = jar
+        .url(
+            Domain::Groups,
+            &format!("/v1/groups/{}/name-history", group_id),
+        )
+        .query(
+            "sortOrder",
+            sort_order.unwrap_or(SortOrder::Asc).get_sort_order_string(),
+        );
+
+    let response = get_page(jar, url, limit, cursor).await?;
     Ok(response)
 }
 
@@ -190,10 +249,15 @@ pub struct GroupSettings {
 /// Gets a group's settings
 ///
 /// # Error codes
+/// Typed as [`GroupError`](crate::util::error_codes::GroupError).
 /// - 1: Group is invalid or does not exist.
 /// - 23: Insufficient permissions to complete the request.
-pub async fn settings(jar: &RequestJar, group_id: i64) -> Result<GroupSettings, Box<Error>> {
-    let url = format!("https://groups.roblox.com/v1/groups/{}/settings", group_id);
+pub async fn settings(jar: &RequestJar, group_id: GroupId) -> Result<GroupSettings, Box<Error>> {
+    let url = format!(
+        "{}/v1/groups/{}/settings",
+        jar.base_url(Domain::Groups),
+        group_id
+    );
     let response = jar.get_json::<GroupSettings>(&url).await?;
     Ok(response)
 }
@@ -214,15 +278,20 @@ pub struct GroupSettingsUpdateResponse {}
 /// Updates a group's settings
 ///
 /// # Error codes
+/// Typed as [`GroupError`](crate::util::error_codes::GroupError).
 /// - 1: Group is invalid or does not exist.
 /// - 23: Insufficient permissions to complete the request.
 /// - 31: Service is currently unavailable.
 pub async fn update_settings(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
     request: GroupSettingsUpdateRequest,
 ) -> Result<GroupSettingsUpdateResponse, Box<Error>> {
-    let url = format!("https://groups.roblox.com/v1/groups/{}/settings", group_id);
+    let url = format!(
+        "{}/v1/groups/{}/settings",
+        jar.base_url(Domain::Groups),
+        group_id
+    );
     let response = jar
         .patch_json::<GroupSettingsUpdateResponse, GroupSettingsUpdateRequest>(&url, request)
         .await?;
@@ -235,7 +304,7 @@ pub async fn update_settings(
 #[serde(rename_all = "camelCase")]
 pub struct GroupComplianceItem {
     pub can_view_group: bool,
-    pub group_id: i64,
+    pub group_id: GroupId,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
@@ -247,7 +316,7 @@ pub struct GroupComplianceResponse {
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct GroupComplianceRequest {
-    pub group_ids: Vec<i64>,
+    pub group_ids: Vec<GroupId>,
 }
 
 /// Gets group policy info used for compliance
@@ -256,9 +325,9 @@ pub struct GroupComplianceRequest {
 /// - 2: Ids could not be parsed from request.
 pub async fn compliance(
     jar: &RequestJar,
-    group_ids: Vec<i64>,
+    group_ids: Vec<GroupId>,
 ) -> Result<GroupComplianceResponse, Box<Error>> {
-    let url = format!("https://groups.roblox.com/v1/groups/policies");
+    let url = format!("{}/v1/groups/policies", jar.base_url(Domain::Groups));
     let request = GroupComplianceRequest { group_ids };
     let response = jar
         .post_json::<GroupComplianceResponse, GroupComplianceRequest>(&url, request)
@@ -280,17 +349,19 @@ pub struct NewDescriptionResponse {
 
 /// Updates a group's description
 /// # Error codes
+/// Typed as [`GroupError`](crate::util::error_codes::GroupError).
 /// - 1: Group is invalid or does not exist.
 /// - 18: The description is too long.
 /// - 23: Insufficient permissions to complete the request.
 /// - 29: Your group description was empty.
 pub async fn update_description(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
     description: String,
 ) -> Result<NewDescriptionResponse, Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/description",
+        "{}/v1/groups/{}/description",
+        jar.base_url(Domain::Groups),
         group_id
     );
     let request = NewDescriptionRequest { description };
@@ -317,17 +388,19 @@ pub struct NewNameResponse {
 /// **THIS COSTS ROBUX!**
 ///
 /// # Error codes
+/// Typed as [`GroupError`](crate::util::error_codes::GroupError).
 /// - 1: Group is invalid or does not exist.
 /// - 18: The description is too long.
 /// - 23: Insufficient permissions to complete the request.
 /// - 29: Your group description was empty.
 pub async fn update_name(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
     name: String,
 ) -> Result<NewNameResponse, Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/description",
+        "{}/v1/groups/{}/description",
+        jar.base_url(Domain::Groups),
         group_id
     );
     let request = NewNameRequest { name };
@@ -344,7 +417,7 @@ pub async fn update_name(
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct GroupMembership {
-    pub group_id: i64,
+    pub group_id: GroupId,
     pub is_primary: bool,
     pub is_pending_join: bool,
     pub group_role: Option<GroupMembershipUserRole>,
@@ -365,10 +438,15 @@ pub struct GroupMembershipUserRole {
 /// Gets a user's group membership
 ///
 /// # Error codes
+/// Typed as [`GroupError`](crate::util::error_codes::GroupError).
 /// - 1: Group is invalid or does not exist.
-pub async fn membership(jar: &RequestJar, group_id: i64) -> Result<GroupMembership, Box<Error>> {
+pub async fn membership(
+    jar: &RequestJar,
+    group_id: GroupId,
+) -> Result<GroupMembership, Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/membership",
+        "{}/v1/groups/{}/membership",
+        jar.base_url(Domain::Groups),
         group_id
     );
     let response = jar.get_json::<GroupMembership>(&url).await?;
@@ -378,23 +456,37 @@ pub async fn membership(jar: &RequestJar, group_id: i64) -> Result<GroupMembersh
 /// Gets a list of users in a group
 ///
 /// # Error codes
+/// Typed as [`GroupError`](crate::util::error_codes::GroupError).
 /// - 1: The group is invalid or does not exist.
 pub async fn members(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
     limit: PageLimit,
     sort_order: Option<SortOrder>,
+    cursor: Option<String>,
 ) -> Result<Vec<GroupMembershipUserRole>, Box<Error>> {
-    let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/users?sortOrder={}",
-        group_id,
-        sort_order.unwrap_or(SortOrder::Asc).get_sort_order_string()
-    );
-    //let response = jar.get_json::<GroupRoleResponse>(&url).await?;
-    let response = get_page(jar, url.as_str(), limit, None).await?;
+    let url = members_url(jar, group_id, sort_order);
+    let response = get_page(jar, url, limit, cursor).await?;
     Ok(response)
 }
 
+/// Goes through every member of a group, see [`members`] for the error codes
+pub fn members_stream(
+    jar: &RequestJar,
+    group_id: GroupId,
+    sort_order: Option<SortOrder>,
+) -> Paginator<'_, GroupMembershipUserRole> {
+    Paginator::new(jar, members_url(jar, group_id, sort_order))
+}
+
+fn members_url(jar: &RequestJar, group_id: GroupId, sort_order: Option<SortOrder>) -> UrlBuilder {
+    jar.url(Domain::Groups, &format!("/v1/groups/{}/users", group_id))
+        .query(
+            "sortOrder",
+            sort_order.unwrap_or(SortOrder::Asc).get_sort_order_string(),
+        )
+}
+
 // Note: Joining a group is not implemented and will not be implemented, as it is not needed and requires a captcha.
 
 /// Gets all groups the authenticated user is pending for
@@ -403,7 +495,7 @@ pub async fn members(
 /// # Error codes
 /// There are no error codes for this endpoint.
 pub async fn pending_requests(jar: &RequestJar) -> Result<Vec<Group>, Box<Error>> {
-    let url = format!("https://groups.roblox.com/v1/user/groups/pending");
+    let url = format!("{}/v1/user/groups/pending", jar.base_url(Domain::Groups));
     let response = jar
         .get_json::<DataWrapper<Vec<Group>>>(url.as_str())
         .await?;
@@ -432,7 +524,8 @@ pub struct FriendGroupsItem {
 pub async fn friend_groups(jar: &RequestJar) -> Result<Vec<FriendGroupsItem>, Box<Error>> {
     let user_id = whoami(jar).await?.id;
     let url = format!(
-        "https://groups.roblox.com/v1/users/{}/friends/groups/roles",
+        "{}/v1/users/{}/friends/groups/roles",
+        jar.base_url(Domain::Groups),
         user_id
     );
     let response = jar
@@ -455,10 +548,11 @@ pub struct UserMembershipsGroupItem {
 /// - 3: The user is invalid or does not exist.
 pub async fn user_memberships(
     jar: &RequestJar,
-    user_id: i64,
+    user_id: UserId,
 ) -> Result<Vec<UserMembershipsGroupItem>, Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v2/users/{}/groups/roles",
+        "{}/v2/users/{}/groups/roles",
+        jar.base_url(Domain::Groups),
         user_id
     );
     let response = jar
@@ -470,41 +564,50 @@ pub async fn user_memberships(
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct GroupOwnershipChangeRequest {
-    pub user_id: i64,
+    pub user_id: UserId,
 }
 
 /// Changes the owner of a group
 ///
 /// # Error codes
+/// Typed as [`OwnershipError`](crate::util::error_codes::OwnershipError).
 /// - 1: The group is invalid or does not exist.
 /// - 3: The user is invalid or does not exist.
 /// - 15: User is not a member of the group.
 /// - 16: The user does not have the necessary level of premium membership.
 /// - 17: You are not authorized to change the owner of this group.
 /// - 25: 2-Step Verification is required to make further transactions. Go to Settings > Security to complete 2-Step Verification.
-pub async fn change_owner(jar: &RequestJar, group_id: i64, user_id: i64) -> Result<(), Box<Error>> {
+pub async fn change_owner(
+    jar: &RequestJar,
+    group_id: GroupId,
+    user_id: UserId,
+) -> Result<(), Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/change-owner",
+        "{}/v1/groups/{}/change-owner",
+        jar.base_url(Domain::Groups),
         group_id
     );
     let request = GroupOwnershipChangeRequest { user_id };
-    jar.post_json(url.as_str(), &request).await?;
+    jar.post_json::<EmptyResponse, _>(url.as_str(), &request)
+        .await?;
     Ok(())
 }
 
 /// Claims the ownership of a group
 ///
 /// # Error codes
+/// Typed as [`OwnershipError`](crate::util::error_codes::OwnershipError).
 /// - 1: The group is invalid or does not exist.
 /// - 11: You are not authorized to claim this group.
 /// - 12: This group already has an owner.
 /// - 13: Too many attempts to claim groups. Please try again later.
 /// - 18: The operation is temporarily unavailable. Please try again later.
-pub async fn claim_ownership(jar: &RequestJar, group_id: i64) -> Result<(), Box<Error>> {
+pub async fn claim_ownership(jar: &RequestJar, group_id: GroupId) -> Result<(), Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/claim-ownership",
+        "{}/v1/groups/{}/claim-ownership",
+        jar.base_url(Domain::Groups),
         group_id
     );
-    jar.post(url.as_str(), "".to_string()).await?;
+    jar.post_empty(url.as_str()).await?;
     Ok(())
 }
@@ -0,0 +1,627 @@
+//! Accepting or declining join requests by a set of rules.
+//!
+//! A [`JoinRequestPolicy`] lists the rules a user has to pass to be accepted, a user who fails
+//! any of them is declined. Every request gets a [`JoinRequestVerdict`] explaining which rules
+//! passed and why, and the decisions are sent in one batch for accepts and one for declines:
+//!
+//! ```no_run
+//! # use rustyroblox::{groups::{self, BulkOptions, JoinRequestPolicy}, util::{ids::GroupId, jar::RequestJar, paging::PageLimit}};
+//! # async fn moderate(jar: &RequestJar) -> Result<(), Box<rustyroblox::util::Error>> {
+//! let policy: JoinRequestPolicy = serde_json::from_str(
+//!     r#"{ "rules": [
+//!         { "rule": "bannedUsers", "userIds": [1, 2] },
+//!         { "rule": "blacklistedGroups", "groupIds": [3] }
+//!     ] }"#,
+//! )
+//! .unwrap();
+//!
+//! let review = groups::moderate_join_requests(
+//!     jar,
+//!     GroupId(7370273),
+//!     &policy,
+//!     PageLimit::Limit100,
+//!     BulkOptions::default(),
+//! )
+//! .await?;
+//!
+//! for verdict in review.declined() {
+//!     println!("Declined {}: {}", verdict.request.requestor.username, verdict.explain());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Only the lookups the rules need are made, a user is only requested from the users API when
+//! a rule looks at their account.
+
+use std::collections::HashSet;
+
+use futures::{stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    users::{self, User},
+    util::{
+        ids::{GroupId, UserId},
+        jar::RequestJar,
+        paging::PageLimit,
+        timestamp::Timestamp,
+        Error,
+    },
+};
+
+use super::{join_requests, join_requests::all::JoinRequest, user_memberships, BulkOptions};
+
+/// Something a user has to pass to be accepted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "rule",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum JoinRule {
+    /// The account has to be at least this many days old
+    MinAccountAge { days: i64 },
+    /// The account has to be created before this
+    CreatedBefore { timestamp: Timestamp },
+    /// The user has to have a verified badge
+    VerifiedBadge,
+    /// The user can't be on this list
+    BannedUsers { user_ids: HashSet<UserId> },
+    /// The user can't have one of these usernames now or in their username history, compared
+    /// ignoring case
+    BannedUsernames { usernames: Vec<String> },
+    /// The user can't be in any of these groups
+    BlacklistedGroups { group_ids: HashSet<GroupId> },
+}
+
+impl JoinRule {
+    fn needs_user(&self) -> bool {
+        matches!(
+            self,
+            JoinRule::MinAccountAge { .. } | JoinRule::CreatedBefore { .. }
+        )
+    }
+}
+
+/// The rules join requests are checked with, a policy without rules accepts everyone
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JoinRequestPolicy {
+    pub rules: Vec<JoinRule>,
+}
+
+/// What is done with a join request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinDecision {
+    Accept,
+    Decline,
+    /// A lookup for one of the rules failed, the request is left as it is
+    Undecided,
+}
+
+/// Whether a user passed a single rule, and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleCheck {
+    pub passed: bool,
+    pub explanation: String,
+}
+
+impl RuleCheck {
+    fn new(passed: bool, explanation: String) -> RuleCheck {
+        RuleCheck {
+            passed,
+            explanation,
+        }
+    }
+}
+
+/// The decision for a join request with the checks it's based on, in the order of the rules
+#[derive(Debug)]
+pub struct JoinRequestVerdict {
+    pub request: JoinRequest,
+    pub decision: JoinDecision,
+    pub checks: Vec<RuleCheck>,
+    /// Why the request is undecided
+    pub error: Option<Box<Error>>,
+}
+
+impl JoinRequestVerdict {
+    /// The rules the user failed, or why they were accepted or left undecided
+    pub fn explain(&self) -> String {
+        if let Some(error) = &self.error {
+            return format!("couldn't be checked: {}", error);
+        }
+
+        let failed = self
+            .checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.explanation.as_str())
+            .collect::<Vec<_>>();
+        if failed.is_empty() {
+            "passed every rule".to_string()
+        } else {
+            failed.join(", ")
+        }
+    }
+}
+
+/// The verdicts for every join request, in the order Roblox listed them
+#[derive(Debug, Default)]
+pub struct JoinRequestReview {
+    pub verdicts: Vec<JoinRequestVerdict>,
+}
+
+impl JoinRequestReview {
+    pub fn accepted(&self) -> impl Iterator<Item = &JoinRequestVerdict> {
+        self.with_decision(JoinDecision::Accept)
+    }
+
+    pub fn declined(&self) -> impl Iterator<Item = &JoinRequestVerdict> {
+        self.with_decision(JoinDecision::Decline)
+    }
+
+    pub fn undecided(&self) -> impl Iterator<Item = &JoinRequestVerdict> {
+        self.with_decision(JoinDecision::Undecided)
+    }
+
+    fn with_decision(&self, decision: JoinDecision) -> impl Iterator<Item = &JoinRequestVerdict> {
+        self.verdicts
+            .iter()
+            .filter(move |verdict| verdict.decision == decision)
+    }
+}
+
+/// What was looked up about a user, only what the rules need
+struct Lookups {
+    user: Option<User>,
+    usernames: Vec<String>,
+    groups: Vec<(GroupId, String)>,
+}
+
+async fn lookup(
+    jar: &RequestJar,
+    policy: &JoinRequestPolicy,
+    request: &JoinRequest,
+) -> Result<Lookups, Box<Error>> {
+    let user_id = request.requestor.user_id;
+    let mut lookups = Lookups {
+        user: None,
+        usernames: vec![request.requestor.username.clone()],
+        groups: Vec::new(),
+    };
+
+    if policy.rules.iter().any(JoinRule::needs_user) {
+        lookups.user = Some(users::user_by_id(jar, user_id).await?);
+    }
+    if policy
+        .rules
+        .iter()
+        .any(|rule| matches!(rule, JoinRule::BannedUsernames { .. }))
+    {
+        let history = users::username_history(jar, user_id).await?;
+        lookups
+            .usernames
+            .extend(history.into_iter().map(|entry| entry.name));
+    }
+    if policy
+        .rules
+        .iter()
+        .any(|rule| matches!(rule, JoinRule::BlacklistedGroups { .. }))
+    {
+        lookups.groups = user_memberships(jar, user_id)
+            .await?
+            .into_iter()
+            .map(|membership| (membership.group.id, membership.group.name))
+            .collect();
+    }
+
+    Ok(lookups)
+}
+
+fn check(rule: &JoinRule, request: &JoinRequest, lookups: &Lookups) -> RuleCheck {
+    // A rule that needs the account fails without it
+    let not_looked_up = || RuleCheck::new(false, "account wasn't looked up".to_string());
+
+    match rule {
+        JoinRule::MinAccountAge { days } => {
+            let Some(user) = &lookups.user else {
+                return not_looked_up();
+            };
+            if user.created.unix_timestamp().is_none() {
+                return RuleCheck::new(
+                    false,
+                    format!("account creation date {} couldn't be read", user.created),
+                );
+            }
+
+            // Only an account created in the future has no age
+            let age = user
+                .created
+                .elapsed()
+                .map_or(0, |age| (age.as_secs() / 86_400) as i64);
+            RuleCheck::new(
+                age >= *days,
+                format!("account is {} days old, needs to be {}", age, days),
+            )
+        }
+        JoinRule::CreatedBefore { timestamp } => {
+            let Some(user) = &lookups.user else {
+                return not_looked_up();
+            };
+            RuleCheck::new(
+                user.created < *timestamp,
+                format!(
+                    "account was created {}, needs to be before {}",
+                    user.created, timestamp
+                ),
+            )
+        }
+        JoinRule::VerifiedBadge if request.requestor.has_verified_badge => {
+            RuleCheck::new(true, "has a verified badge".to_string())
+        }
+        JoinRule::VerifiedBadge => RuleCheck::new(false, "has no verified badge".to_string()),
+        JoinRule::BannedUsers { user_ids } if user_ids.contains(&request.requestor.user_id) => {
+            RuleCheck::new(false, "is on the banned user list".to_string())
+        }
+        JoinRule::BannedUsers { .. } => {
+            RuleCheck::new(true, "isn't on the banned user list".to_string())
+        }
+        JoinRule::BannedUsernames { usernames } => {
+            let banned = lookups.usernames.iter().find(|name| {
+                usernames
+                    .iter()
+                    .any(|banned| banned.eq_ignore_ascii_case(name))
+            });
+            match banned {
+                Some(name) => RuleCheck::new(false, format!("has been named {}", name)),
+                None => RuleCheck::new(true, "has no banned usernames".to_string()),
+            }
+        }
+        JoinRule::BlacklistedGroups { group_ids } => {
+            let blacklisted = lookups
+                .groups
+                .iter()
+                .find(|(group_id, _)| group_ids.contains(group_id));
+            match blacklisted {
+                Some((group_id, name)) => {
+                    RuleCheck::new(false, format!("is in {} ({})", name, group_id))
+                }
+                None => RuleCheck::new(true, "isn't in a blacklisted group".to_string()),
+            }
+        }
+    }
+}
+
+/// Checks a join request against every rule of a policy
+///
+/// A failed lookup makes the request [`JoinDecision::Undecided`] rather than failing.
+pub async fn evaluate_join_request(
+    jar: &RequestJar,
+    policy: &JoinRequestPolicy,
+    request: JoinRequest,
+) -> JoinRequestVerdict {
+    let lookups = match lookup(jar, policy, &request).await {
+        Ok(lookups) => lookups,
+        Err(error) => {
+            return JoinRequestVerdict {
+                request,
+                decision: JoinDecision::Undecided,
+                checks: Vec::new(),
+                error: Some(error),
+            }
+        }
+    };
+
+    let checks = policy
+        .rules
+        .iter()
+        .map(|rule| check(rule, &request, &lookups))
+        .collect::<Vec<_>>();
+    let decision = if checks.iter().all(|check| check.passed) {
+        JoinDecision::Accept
+    } else {
+        JoinDecision::Decline
+    };
+
+    JoinRequestVerdict {
+        request,
+        decision,
+        checks,
+        error: None,
+    }
+}
+
+/// Checks a group's pending join requests against a policy, without accepting or declining
+/// anything. `options.concurrency` requests are checked at the same time.
+///
+/// # Error codes
+/// Typed as [`JoinRequestError`](crate::util::error_codes::JoinRequestError).
+/// - 1: The group is invalid or does not exist.
+/// - 19: You have insufficient permissions for this request.
+pub async fn review_join_requests(
+    jar: &RequestJar,
+    group_id: GroupId,
+    policy: &JoinRequestPolicy,
+    limit: PageLimit,
+    options: BulkOptions,
+) -> Result<JoinRequestReview, Box<Error>> {
+    let requests = join_requests::all::get(jar, group_id, limit).await?;
+    let verdicts = stream::iter(requests)
+        .map(|request| evaluate_join_request(jar, policy, request))
+        .buffered(options.concurrency.max(1))
+        .collect()
+        .await;
+
+    Ok(JoinRequestReview { verdicts })
+}
+
+/// Accepts and declines the join requests of a review, in one batch each. Undecided requests
+/// are left as they are.
+///
+/// The accepts are sent first, if they fail the declines aren't sent.
+///
+/// # Error codes
+/// Typed as [`JoinRequestError`](crate::util::error_codes::JoinRequestError).
+/// - 1: The group is invalid or does not exist.
+/// - 3: The user is invalid or does not exist.
+/// - 6: You are already in the maximum number of groups.
+/// - 18: The operation is temporarily unavailable. Please try again later.
+/// - 19: You have insufficient permissions for this request.
+/// - 20: The group join request is invalid.
+pub async fn apply_join_request_review(
+    jar: &RequestJar,
+    group_id: GroupId,
+    review: &JoinRequestReview,
+) -> Result<(), Box<Error>> {
+    let user_ids = |verdicts: &mut dyn Iterator<Item = &JoinRequestVerdict>| {
+        verdicts
+            .map(|verdict| verdict.request.requestor.user_id)
+            .collect::<Vec<_>>()
+    };
+
+    let accepted = user_ids(&mut review.accepted());
+    if !accepted.is_empty() {
+        join_requests::all::accept(jar, group_id, accepted).await?;
+    }
+
+    let declined = user_ids(&mut review.declined());
+    if !declined.is_empty() {
+        join_requests::all::decline(jar, group_id, declined).await?;
+    }
+
+    Ok(())
+}
+
+/// Checks a group's pending join requests against a policy and accepts or declines them,
+/// unless `options.dry_run` is set. Returns the review either way.
+///
+/// # Error codes
+/// See [`review_join_requests`] and [`apply_join_request_review`].
+pub async fn moderate_join_requests(
+    jar: &RequestJar,
+    group_id: GroupId,
+    policy: &JoinRequestPolicy,
+    limit: PageLimit,
+    options: BulkOptions,
+) -> Result<JoinRequestReview, Box<Error>> {
+    let review = review_join_requests(jar, group_id, policy, limit, options).await?;
+    if !options.dry_run {
+        apply_join_request_review(jar, group_id, &review).await?;
+    }
+    Ok(review)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check, moderate_join_requests, JoinDecision, JoinRequest, JoinRequestPolicy, JoinRule,
+        Lookups,
+    };
+    use crate::{
+        groups::BulkOptions,
+        mock::{mock_user, MockResponse, MockServer},
+        users::User,
+        util::{
+            hosts::Domain,
+            ids::{GroupId, UserId},
+            paging::PageLimit,
+            timestamp::Timestamp,
+        },
+    };
+
+    async fn join_request_server() -> MockServer {
+        let server = MockServer::start().await;
+        let requests = (2..=6)
+            .map(|user_id| {
+                serde_json::json!({
+                    "requestor": mock_user(user_id),
+                    "created": "2023-11-20T12:00:00.000Z",
+                })
+            })
+            .collect();
+        server.mock(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/join-requests",
+            MockResponse::page(requests, None),
+        );
+
+        for user_id in 2..=5 {
+            let history = if user_id == 4 {
+                vec![serde_json::json!({ "name": "BadGuy" })]
+            } else {
+                Vec::new()
+            };
+            server.mock(
+                Domain::Users,
+                "GET",
+                &format!("/v1/users/{}/username-history", user_id),
+                MockResponse::page(history, None),
+            );
+
+            let group_id = if user_id == 5 { 99 } else { 50 };
+            server.mock(
+                Domain::Groups,
+                "GET",
+                &format!("/v2/users/{}/groups/roles", user_id),
+                MockResponse::json(
+                    &serde_json::json!({ "data": [{
+                        "group": { "id": group_id, "name": "Raiders", "memberCount": 10, "hasVerifiedBadge": false },
+                        "role": { "id": 1, "name": "Member", "rank": 1 },
+                    }] })
+                    .to_string(),
+                ),
+            );
+        }
+        server.mock(
+            Domain::Users,
+            "GET",
+            "/v1/users/6/username-history",
+            MockResponse::errors(400, &[(3, "The user id is invalid")]),
+        );
+
+        server.mock(
+            Domain::Groups,
+            "POST",
+            "/v1/groups/1/join-requests",
+            MockResponse::json("{}"),
+        );
+        server.mock(
+            Domain::Groups,
+            "DELETE",
+            "/v1/groups/1/join-requests",
+            MockResponse::json("{}"),
+        );
+        server
+    }
+
+    #[tokio::test]
+    async fn join_request_policy() {
+        let server = join_request_server().await;
+        let jar = server.jar().await;
+        let policy: JoinRequestPolicy = serde_json::from_str(
+            r#"{ "rules": [
+                { "rule": "bannedUsers", "userIds": [3] },
+                { "rule": "bannedUsernames", "usernames": ["badguy"] },
+                { "rule": "blacklistedGroups", "groupIds": [99] }
+            ] }"#,
+        )
+        .unwrap();
+
+        // A dry run decides without accepting or declining anyone
+        let review = moderate_join_requests(
+            &jar,
+            GroupId(1),
+            &policy,
+            PageLimit::Limit100,
+            BulkOptions::dry_run(),
+        )
+        .await
+        .unwrap();
+        assert!(server
+            .requests()
+            .iter()
+            .all(|request| request.method == "GET"));
+
+        let decisions = review
+            .verdicts
+            .iter()
+            .map(|verdict| (verdict.request.requestor.user_id, verdict.decision))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            decisions,
+            vec![
+                (UserId(2), JoinDecision::Accept),
+                (UserId(3), JoinDecision::Decline),
+                (UserId(4), JoinDecision::Decline),
+                (UserId(5), JoinDecision::Decline),
+                (UserId(6), JoinDecision::Undecided),
+            ]
+        );
+        assert_eq!(review.verdicts[0].explain(), "passed every rule");
+        assert_eq!(review.verdicts[1].explain(), "is on the banned user list");
+        assert_eq!(review.verdicts[2].explain(), "has been named BadGuy");
+        assert_eq!(review.verdicts[3].explain(), "is in Raiders (99)");
+        assert!(review.verdicts[4].error.is_some());
+
+        // The decisions are sent in one batch each, undecided requests are left alone
+        moderate_join_requests(
+            &jar,
+            GroupId(1),
+            &policy,
+            PageLimit::Limit100,
+            BulkOptions::default(),
+        )
+        .await
+        .unwrap();
+        let requests = server.requests();
+        let writes = requests
+            .iter()
+            .filter(|request| request.method != "GET")
+            .collect::<Vec<_>>();
+        assert_eq!(writes.len(), 2);
+        assert_eq!(writes[0].method, "POST");
+        assert_eq!(writes[0].body, r#"{"userIds":[2]}"#);
+        assert_eq!(writes[1].method, "DELETE");
+        assert_eq!(writes[1].body, r#"{"userIds":[3,4,5]}"#);
+
+        // Users are only looked up when a rule needs them
+        assert!(!requests
+            .iter()
+            .any(|request| request.path == "/users/v1/users/2"));
+    }
+
+    #[test]
+    fn account_rules() {
+        let request: JoinRequest = serde_json::from_value(serde_json::json!({
+            "requestor": mock_user(2),
+            "created": "2023-11-20T12:00:00.000Z",
+        }))
+        .unwrap();
+        let lookups = |created: &str| Lookups {
+            user: Some(User {
+                description: String::new(),
+                created: Timestamp::new(created),
+                is_banned: false,
+                external_app_display_name: None,
+                has_verified_badge: false,
+                id: UserId(2),
+                name: "user2".to_string(),
+                display_name: "user2".to_string(),
+            }),
+            usernames: Vec::new(),
+            groups: Vec::new(),
+        };
+        let min_age = JoinRule::MinAccountAge { days: 30 };
+        let before = JoinRule::CreatedBefore {
+            timestamp: Timestamp::new("2020-01-01T00:00:00Z"),
+        };
+
+        let old = lookups("2019-09-03T17:43:18.513Z");
+        assert!(check(&min_age, &request, &old).passed);
+        assert!(check(&before, &request, &old).passed);
+
+        let future = lookups("2999-01-01T00:00:00Z");
+        let age = check(&min_age, &request, &future);
+        assert!(!age.passed);
+        assert_eq!(age.explanation, "account is 0 days old, needs to be 30");
+        assert!(!check(&before, &request, &future).passed);
+
+        // A creation date that can't be read, or an account that wasn't looked up, fails
+        let unreadable = check(&min_age, &request, &lookups("yesterday"));
+        assert!(!unreadable.passed);
+        assert_eq!(
+            unreadable.explanation,
+            "account creation date yesterday couldn't be read"
+        );
+        let missing = Lookups {
+            user: None,
+            usernames: Vec::new(),
+            groups: Vec::new(),
+        };
+        assert!(!check(&min_age, &request, &missing).passed);
+        assert!(!check(&before, &request, &missing).passed);
+    }
+}
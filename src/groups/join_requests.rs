@@ -1,12 +1,17 @@
-pub mod join_requests {
+/// Management of join requests in batches
+pub mod all {
     use rspc::Type;
     use serde::{Deserialize, Serialize};
 
     use crate::{
         users::MinimalGroupUser,
         util::{
+            hosts::Domain,
+            ids::{GroupId, UserId},
             jar::RequestJar,
-            paging::{get_page, PageLimit},
+            paging::{get_page, PageLimit, Paginator, SortOrder},
+            responses::EmptyResponse,
+            timestamp::Timestamp,
             Error,
         },
     };
@@ -14,116 +19,149 @@ pub mod join_requests {
     #[derive(Debug, Serialize, Deserialize, Clone, Type)]
     #[serde(rename_all = "camelCase")]
     pub struct BatchRequest {
-        pub user_ids: Vec<i64>,
+        pub user_ids: Vec<UserId>,
     }
 
     #[derive(Debug, Serialize, Deserialize, Clone, Type)]
     #[serde(rename_all = "camelCase")]
     pub struct JoinRequest {
         pub requestor: MinimalGroupUser,
-        pub created: String,
+        pub created: Timestamp,
     }
 
     /// Retrieves join requests for a group.
     ///
     /// # Error codes
+    /// Typed as [`JoinRequestError`](crate::util::error_codes::JoinRequestError).
     /// - 1: The group is invalid or does not exist.
     /// - 19: You have insufficient permissions for this request.
-    #[allow(unused)] // It appears to be a bug in the checker, this kinda fixes it.
     pub async fn get(
         jar: &RequestJar,
-        group_id: i64,
+        group_id: GroupId,
         limit: PageLimit,
     ) -> Result<Vec<JoinRequest>, Box<Error>> {
-        let url = format!(
-            "https://groups.roblox.com/v1/groups/{}/join-requests",
-            group_id
+        let url = jar.url(
+            Domain::Groups,
+            &format!("/v1/groups/{}/join-requests", group_id),
         );
-        let response = get_page(jar, url.as_str(), limit, None).await?;
+        let response = get_page(jar, url, limit, None).await?;
         Ok(response)
     }
 
+    /// Goes through all of a group's join requests, see [`get`] for the error codes
+    pub fn stream(
+        jar: &RequestJar,
+        group_id: GroupId,
+        sort_order: Option<SortOrder>,
+    ) -> Paginator<'_, JoinRequest> {
+        let url = jar
+            .url(
+                Domain::Groups,
+                &format!("/v1/groups/{}/join-requests", group_id),
+            )
+            .query(
+                "sortOrder",
+                sort_order.unwrap_or(SortOrder::Asc).get_sort_order_string(),
+            );
+        Paginator::new(jar, url)
+    }
+
     /// Accepts a batch of join requests for a group.
     ///
     /// # Error codes
+    /// Typed as [`JoinRequestError`](crate::util::error_codes::JoinRequestError).
     /// - 1: The group is invalid or does not exist.
     /// - 3: The user is invalid or does not exist.
     /// - 6: You are already in the maximum number of groups.
     /// - 18: The operation is temporarily unavailable. Please try again later.
     /// - 19: You have insufficient permissions for this request.
     /// - 20: The group join request is invalid.
-    #[allow(unused)] // It appears to be a bug in the checker, this kinda fixes it.
     pub async fn accept(
         jar: &RequestJar,
-        group_id: i64,
-        user_ids: Vec<i64>,
+        group_id: GroupId,
+        user_ids: Vec<UserId>,
     ) -> Result<(), Box<Error>> {
         let url = format!(
-            "https://groups.roblox.com/v1/groups/{}/join-requests",
+            "{}/v1/groups/{}/join-requests",
+            jar.base_url(Domain::Groups),
             group_id
         );
         let request = BatchRequest { user_ids };
-        let response = jar.post_json::<(), BatchRequest>(&url, request).await?;
-        Ok(response)
+        jar.post_json::<EmptyResponse, BatchRequest>(&url, request)
+            .await?;
+        Ok(())
     }
 
     /// Declines a batch of join requests for a group.
     ///
     /// # Error codes
+    /// Typed as [`JoinRequestError`](crate::util::error_codes::JoinRequestError).
     /// - 1: The group is invalid or does not exist.
     /// - 3: The user is invalid or does not exist.
-    #[allow(unused)] // It appears to be a bug in the checker, this kinda fixes it.
     pub async fn decline(
         jar: &RequestJar,
-        group_id: i64,
-        user_ids: Vec<i64>,
+        group_id: GroupId,
+        user_ids: Vec<UserId>,
     ) -> Result<(), Box<Error>> {
         let url = format!(
-            "https://groups.roblox.com/v1/groups/{}/join-requests",
+            "{}/v1/groups/{}/join-requests",
+            jar.base_url(Domain::Groups),
             group_id
         );
         let request = BatchRequest { user_ids };
-        let response = jar.delete_json::<(), BatchRequest>(&url, request).await?;
-        Ok(response)
+        jar.delete_json::<EmptyResponse, BatchRequest>(&url, request)
+            .await?;
+        Ok(())
     }
 }
 
-pub mod join_request {
+/// Management of 1 join request at a time
+pub mod single {
     use rspc::Type;
     use serde::{Deserialize, Serialize};
 
     use crate::{
         users::MinimalGroupUser,
-        util::{jar::RequestJar, Error},
+        util::{
+            hosts::Domain,
+            ids::{GroupId, UserId},
+            jar::RequestJar,
+            timestamp::Timestamp,
+            Error,
+        },
     };
 
     #[derive(Debug, Serialize, Deserialize, Clone, Type)]
     #[serde(rename_all = "camelCase")]
     pub struct BatchRequest {
-        pub user_ids: Vec<i64>,
+        pub user_ids: Vec<UserId>,
     }
 
     #[derive(Debug, Serialize, Deserialize, Clone, Type)]
     #[serde(rename_all = "camelCase")]
     pub struct JoinRequest {
         pub requestor: MinimalGroupUser,
-        pub created: String,
+        pub created: Timestamp,
     }
 
-    /// Retrieves a join request for a group.
+    /// Retrieves a join request for a group, `None` when the user has no pending request.
     ///
     /// # Error codes
+    /// Typed as [`JoinRequestError`](crate::util::error_codes::JoinRequestError).
     /// - 1: The group is invalid or does not exist.
     /// - 19: You have insufficient permissions for this request.
     pub async fn get(
         jar: &RequestJar,
-        group_id: i64,
-        user_id: i64,
-    ) -> Result<JoinRequest, Box<Error>> {
+        group_id: GroupId,
+        user_id: UserId,
+    ) -> Result<Option<JoinRequest>, Box<Error>> {
         let url = format!(
-            "https://groups.roblox.com/v1/groups/{}/join-requests/users/{}",
-            group_id, user_id
+            "{}/v1/groups/{}/join-requests/users/{}",
+            jar.base_url(Domain::Groups),
+            group_id,
+            user_id
         );
+        // Roblox responds with null when there is no request
         let response = jar.get_json(url.as_str()).await?;
         Ok(response)
     }
@@ -131,32 +169,46 @@ pub mod join_request {
     /// Accepts a join request for a group.
     ///
     /// # Error codes
+    /// Typed as [`JoinRequestError`](crate::util::error_codes::JoinRequestError).
     /// - 1: The group is invalid or does not exist.
     /// - 3: The user is invalid or does not exist.
     /// - 6: You are already in the maximum number of groups.
     /// - 18: The operation is temporarily unavailable. Please try again later.
     /// - 19: You have insufficient permissions for this request.
     /// - 20: The group join request is invalid.
-    pub async fn accept(jar: &RequestJar, group_id: i64, user_id: i64) -> Result<(), Box<Error>> {
+    pub async fn accept(
+        jar: &RequestJar,
+        group_id: GroupId,
+        user_id: UserId,
+    ) -> Result<(), Box<Error>> {
         let url = format!(
-            "https://groups.roblox.com/v1/groups/{}/join-requests/users/{}",
-            group_id, user_id
+            "{}/v1/groups/{}/join-requests/users/{}",
+            jar.base_url(Domain::Groups),
+            group_id,
+            user_id
         );
-        jar.post(&url, "".to_string()).await?;
+        jar.post_empty(&url).await?;
         Ok(())
     }
 
     /// Declines a join request for a group.
     ///
     /// # Error codes
+    /// Typed as [`JoinRequestError`](crate::util::error_codes::JoinRequestError).
     /// - 3: The user is invalid or does not exist.
     /// - 4: You do not have permission to manage this member.
-    pub async fn decline(jar: &RequestJar, group_id: i64, user_id: i64) -> Result<(), Box<Error>> {
+    pub async fn decline(
+        jar: &RequestJar,
+        group_id: GroupId,
+        user_id: UserId,
+    ) -> Result<(), Box<Error>> {
         let url = format!(
-            "https://groups.roblox.com/v1/groups/{}/join-requests/users/{}",
-            group_id, user_id
+            "{}/v1/groups/{}/join-requests/users/{}",
+            jar.base_url(Domain::Groups),
+            group_id,
+            user_id
         );
-        jar.delete(&url, "".to_string()).await?;
+        jar.delete_empty(&url).await?;
         Ok(())
     }
 }
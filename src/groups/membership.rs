@@ -1,36 +1,50 @@
 use rspc::Type;
 use serde::{Deserialize, Serialize};
 
-use crate::util::{jar::RequestJar, responses::RobloxError, Error};
+use crate::util::{
+    hosts::Domain,
+    ids::{GroupId, RoleId, UserId},
+    jar::RequestJar,
+    responses::EmptyResponse,
+    Error,
+};
 
-use super::{roles, user_role};
+use super::{role_by_name, role_by_rank, roles, user_role, GroupRole, RoleCache};
 
 /// Removes a user from a group
 ///
 /// # Error codes
+/// Typed as [`SetRoleError`](crate::util::error_codes::SetRoleError).
 /// - 1: The group is invalid or does not exist.
 /// - 3: The user is invalid or does not exist.
 /// - 4: You do not have permission to manage this member.
 /// - 18: The operation is temporarily unavailable. Please try again later.
 /// - 25: 2-Step Verification is required to make further transactions. Go to Settings > Security to complete 2-Step Verification.
-pub async fn remove_user(jar: &RequestJar, group_id: i64, user_id: i64) -> Result<(), Box<Error>> {
+pub async fn remove_user(
+    jar: &RequestJar,
+    group_id: GroupId,
+    user_id: UserId,
+) -> Result<(), Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/users/{}",
-        group_id, user_id
+        "{}/v1/groups/{}/users/{}",
+        jar.base_url(Domain::Groups),
+        group_id,
+        user_id
     );
-    jar.delete(&url, "".to_string()).await?;
+    jar.delete_empty(&url).await?;
     Ok(())
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateUserRoleRequest {
-    pub role_id: i64,
+    pub role_id: RoleId,
 }
 
 /// Sets a user's role in a group.
 ///
 /// # Error codes
+/// Typed as [`SetRoleError`](crate::util::error_codes::SetRoleError).
 /// - 1: The group is invalid or does not exist.
 /// - 2: The roleset is invalid or does not exist.
 /// - 3: The user is invalid or does not exist.
@@ -40,23 +54,26 @@ pub struct UpdateUserRoleRequest {
 /// - 26: You cannot change the user's role to the same role.
 pub async fn set_role(
     jar: &RequestJar,
-    group_id: i64,
-    user_id: i64,
-    role_id: i64,
+    group_id: GroupId,
+    user_id: UserId,
+    role_id: RoleId,
 ) -> Result<(), Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/users/{}",
-        group_id, user_id
+        "{}/v1/groups/{}/users/{}",
+        jar.base_url(Domain::Groups),
+        group_id,
+        user_id
     );
     let request = UpdateUserRoleRequest { role_id };
-    jar.patch_json(&url, request).await?;
+    jar.patch_json::<EmptyResponse, _>(&url, request).await?;
     Ok(())
 }
 
 /// Sets a user's rank in a group.
-/// (Alias for set_role)
+/// (Alias for set_role, see [`set_rank_by_number`] to set a rank from 1 to 254)
 ///
 /// # Error codes
+/// Typed as [`SetRoleError`](crate::util::error_codes::SetRoleError).
 /// - 1: The group is invalid or does not exist.
 /// - 2: The roleset is invalid or does not exist.
 /// - 3: The user is invalid or does not exist.
@@ -66,84 +83,391 @@ pub async fn set_role(
 /// - 26: You cannot change the user's role to the same role.
 pub async fn set_rank(
     jar: &RequestJar,
-    group_id: i64,
-    user_id: i64,
-    role_id: i64,
+    group_id: GroupId,
+    user_id: UserId,
+    role_id: RoleId,
 ) -> Result<(), Box<Error>> {
     set_role(jar, group_id, user_id, role_id).await?;
     Ok(())
 }
 
-/// Moves a user up by x amount of ranks.
+/// Sets a user's role in a group to the role with a rank (1-254), looked up in the cached roles.
+/// Returns the role the user was given.
 ///
 /// # Error codes
+/// Typed as [`SetRoleError`](crate::util::error_codes::SetRoleError).
 /// - 1: The group is invalid or does not exist.
-/// - 2: The roleset is invalid or does not exist.
 /// - 3: The user is invalid or does not exist.
 /// - 4: You do not have permission to manage this member.
 /// - 18: The operation is temporarily unavailable. Please try again later.
 /// - 23: You cannot change your own role.
 /// - 26: You cannot change the user's role to the same role.
 ///
-/// - 200: The user's rank was not found.
-pub async fn modify_rank_by_amount(
+/// - RoleNotFound: No role has the rank.
+/// - RoleNotAssignable: The rank is the Guest (0) or Owner (255) rank.
+pub async fn set_rank_by_number(
     jar: &RequestJar,
-    group_id: i64,
-    user_id: i64,
+    cache: &RoleCache,
+    group_id: GroupId,
+    user_id: UserId,
+    rank: i64,
+) -> Result<GroupRole, Box<Error>> {
+    let role = role_by_rank(jar, cache, group_id, rank).await?;
+    set_assignable_role(jar, group_id, user_id, role).await
+}
+
+/// Sets a user's role in a group to the role with a name, looked up in the cached roles.
+/// The case of the name doesn't matter when no role matches it exactly.
+/// Returns the role the user was given.
+///
+/// # Error codes
+/// Typed as [`SetRoleError`](crate::util::error_codes::SetRoleError).
+/// - 1: The group is invalid or does not exist.
+/// - 3: The user is invalid or does not exist.
+/// - 4: You do not have permission to manage this member.
+/// - 18: The operation is temporarily unavailable. Please try again later.
+/// - 23: You cannot change your own role.
+/// - 26: You cannot change the user's role to the same role.
+///
+/// - RoleNotFound: No role has the name.
+/// - RoleNotAssignable: The role is the Guest or Owner role.
+pub async fn set_role_by_name(
+    jar: &RequestJar,
+    cache: &RoleCache,
+    group_id: GroupId,
+    user_id: UserId,
+    name: &str,
+) -> Result<GroupRole, Box<Error>> {
+    let role = role_by_name(jar, cache, group_id, name).await?;
+    set_assignable_role(jar, group_id, user_id, role).await
+}
+
+async fn set_assignable_role(
+    jar: &RequestJar,
+    group_id: GroupId,
+    user_id: UserId,
+    role: GroupRole,
+) -> Result<GroupRole, Box<Error>> {
+    if role.rank == GUEST_RANK || role.rank == OWNER_RANK {
+        return Err(Box::new(Error::RoleNotAssignable {
+            group_id,
+            role_id: role.id,
+        }));
+    }
+
+    set_role(jar, group_id, user_id, role.id).await?;
+    Ok(role)
+}
+
+/// The rank of a group's Guest role, which is given to everyone who isn't a member
+pub const GUEST_RANK: i64 = 0;
+/// The rank of a group's Owner role, which only the owner can have
+pub const OWNER_RANK: i64 = 255;
+
+/// What to do when a rank change would move a user past the lowest or highest role
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Type)]
+pub enum RankBounds {
+    /// Stop at the lowest or highest role, leaving the user where they are if they're already on it
+    Clamp,
+    /// Fail with [`Error::RankOutOfBounds`] without changing the user's role
+    Error,
+}
+
+/// A user's role before and after their rank was changed
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RankChange {
+    pub before: GroupRole,
+    pub after: GroupRole,
+}
+
+impl RankChange {
+    /// Whether the user was moved to another role, a clamped change can leave them where they were
+    pub fn changed(&self) -> bool {
+        self.before.id != self.after.id
+    }
+}
+
+/// Moves a user up (or down, for a negative amount) by a number of roles.
+///
+/// The roles are ordered by rank, and the Guest (rank 0) and Owner (rank 255) roles are skipped
+/// since they can't be given to anyone. When the user is already on the role they'd be moved to,
+/// nothing is sent.
+///
+/// # Error codes
+/// Typed as [`SetRoleError`](crate::util::error_codes::SetRoleError).
+/// - 1: The group is invalid or does not exist.
+/// - 2: The roleset is invalid or does not exist.
+/// - 3: The user is invalid or does not exist.
+/// - 4: You do not have permission to manage this member.
+/// - 18: The operation is temporarily unavailable. Please try again later.
+/// - 23: You cannot change your own role.
+///
+/// - UserNotInGroup: The user is not in the group.
+/// - RoleNotAssignable: The user is the group's owner.
+/// - RankOutOfBounds: There are not enough roles above or below the user, with [`RankBounds::Error`].
+pub async fn change_rank(
+    jar: &RequestJar,
+    group_id: GroupId,
+    user_id: UserId,
     amount: i64,
-) -> Result<(), Box<Error>> {
-    let ranks = roles(jar, group_id).await?;
-    let user_rank = user_role(jar, group_id, user_id).await?;
-
-    // Get the index of the user's rank
-    let user_rank_index = ranks.iter().position(|r| r.id == user_rank.id);
-    if user_rank_index.is_none() {
-        return Err(Box::new(Error::RobloxError(RobloxError {
-            code: 200,
-            message: "The user's rank was not found.".to_string(),
-            user_facing_message: Some("The user's rank was not found.".to_string()),
-        })));
+    bounds: RankBounds,
+) -> Result<RankChange, Box<Error>> {
+    let mut ranks = roles(jar, group_id).await?;
+    ranks.retain(|role| role.rank != GUEST_RANK && role.rank != OWNER_RANK);
+    ranks.sort_by_key(|role| role.rank);
+
+    let before = user_role(jar, group_id, user_id).await?;
+    if before.rank == GUEST_RANK {
+        return Err(Box::new(Error::UserNotInGroup { group_id, user_id }));
     }
 
-    // Get the index of the new rank
-    let new_rank_index = user_rank_index.unwrap() + amount as usize;
-    if new_rank_index >= ranks.len() {
-        return Err(Box::new(Error::RobloxError(RobloxError {
-            code: 2,
-            message: "The roleset is invalid or does not exist.".to_string(),
-            user_facing_message: Some("The rank does not exist.".to_string()),
-        })));
+    let Some(index) = ranks.iter().position(|role| role.id == before.id) else {
+        return Err(Box::new(Error::RoleNotAssignable {
+            group_id,
+            role_id: before.id,
+        }));
+    };
+
+    let target = index as i64 + amount;
+    let target = match bounds {
+        RankBounds::Clamp => target.clamp(0, ranks.len() as i64 - 1),
+        RankBounds::Error if target < 0 || target >= ranks.len() as i64 => {
+            return Err(Box::new(Error::RankOutOfBounds {
+                group_id,
+                user_id,
+                amount,
+            }));
+        }
+        RankBounds::Error => target,
+    };
+
+    let after = ranks.swap_remove(target as usize);
+    if after.id != before.id {
+        set_role(jar, group_id, user_id, after.id).await?;
     }
 
-    Ok(())
+    Ok(RankChange { before, after })
 }
 
-/// Promotes a user in a group.
+/// Moves a user up by x amount of ranks, or down for a negative amount.
+/// Fails when there aren't enough roles to move past, see [`change_rank`] to stop at the
+/// lowest or highest role instead.
 ///
 /// # Error codes
+/// Typed as [`SetRoleError`](crate::util::error_codes::SetRoleError).
 /// - 1: The group is invalid or does not exist.
 /// - 2: The roleset is invalid or does not exist.
 /// - 3: The user is invalid or does not exist.
 /// - 4: You do not have permission to manage this member.
 /// - 18: The operation is temporarily unavailable. Please try again later.
 /// - 23: You cannot change your own role.
-/// - 26: You cannot change the user's role to the same role.
-pub async fn promote(jar: &RequestJar, group_id: i64, user_id: i64) -> Result<(), Box<Error>> {
-    modify_rank_by_amount(jar, group_id, user_id, 1).await?;
-    Ok(())
+///
+/// - UserNotInGroup: The user is not in the group.
+/// - RoleNotAssignable: The user is the group's owner.
+/// - RankOutOfBounds: There are not enough roles above or below the user.
+pub async fn modify_rank_by_amount(
+    jar: &RequestJar,
+    group_id: GroupId,
+    user_id: UserId,
+    amount: i64,
+) -> Result<RankChange, Box<Error>> {
+    change_rank(jar, group_id, user_id, amount, RankBounds::Error).await
 }
 
-/// Demotes a user in a group.
+/// Promotes a user to the next role in a group.
 ///
 /// # Error codes
+/// Typed as [`SetRoleError`](crate::util::error_codes::SetRoleError).
 /// - 1: The group is invalid or does not exist.
 /// - 2: The roleset is invalid or does not exist.
 /// - 3: The user is invalid or does not exist.
 /// - 4: You do not have permission to manage this member.
 /// - 18: The operation is temporarily unavailable. Please try again later.
 /// - 23: You cannot change your own role.
-/// - 26: You cannot change the user's role to the same role.
-pub async fn demote(jar: &RequestJar, group_id: i64, user_id: i64) -> Result<(), Box<Error>> {
-    modify_rank_by_amount(jar, group_id, user_id, 1).await?;
-    Ok(())
+///
+/// - UserNotInGroup: The user is not in the group.
+/// - RoleNotAssignable: The user is the group's owner.
+/// - RankOutOfBounds: The user is already on the highest role.
+pub async fn promote(
+    jar: &RequestJar,
+    group_id: GroupId,
+    user_id: UserId,
+) -> Result<RankChange, Box<Error>> {
+    modify_rank_by_amount(jar, group_id, user_id, 1).await
+}
+
+/// Demotes a user to the previous role in a group.
+///
+/// # Error codes
+/// Typed as [`SetRoleError`](crate::util::error_codes::SetRoleError).
+/// - 1: The group is invalid or does not exist.
+/// - 2: The roleset is invalid or does not exist.
+/// - 3: The user is invalid or does not exist.
+/// - 4: You do not have permission to manage this member.
+/// - 18: The operation is temporarily unavailable. Please try again later.
+/// - 23: You cannot change your own role.
+///
+/// - UserNotInGroup: The user is not in the group.
+/// - RoleNotAssignable: The user is the group's owner.
+/// - RankOutOfBounds: The user is already on the lowest role.
+pub async fn demote(
+    jar: &RequestJar,
+    group_id: GroupId,
+    user_id: UserId,
+) -> Result<RankChange, Box<Error>> {
+    modify_rank_by_amount(jar, group_id, user_id, -1).await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mock::testing::ranked_group_server,
+        util::ids::{GroupId, RoleId, UserId},
+    };
+
+    #[tokio::test]
+    async fn promote_and_demote() {
+        let server = ranked_group_server().await;
+        let jar = server.jar().await;
+
+        let change = crate::groups::promote(&jar, GroupId(1), UserId(2))
+            .await
+            .unwrap();
+        assert_eq!(change.before.id, RoleId(11));
+        assert_eq!(change.after.id, RoleId(12));
+        assert!(change.changed());
+
+        let change = crate::groups::demote(&jar, GroupId(1), UserId(3))
+            .await
+            .unwrap();
+        assert_eq!(change.after.id, RoleId(12));
+
+        let change = crate::groups::modify_rank_by_amount(&jar, GroupId(1), UserId(3), -2)
+            .await
+            .unwrap();
+        assert_eq!(change.after.name, "Member");
+
+        let bodies = server
+            .requests()
+            .into_iter()
+            .filter(|request| request.method == "PATCH")
+            .map(|request| request.body)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bodies,
+            [r#"{"roleId":12}"#, r#"{"roleId":12}"#, r#"{"roleId":11}"#]
+        );
+    }
+
+    #[tokio::test]
+    async fn rank_change_bounds() {
+        use crate::groups::RankBounds;
+        use crate::util::Error;
+
+        let server = ranked_group_server().await;
+        let jar = server.jar().await;
+
+        // Admin is the highest role that can be given, the Owner role is skipped
+        let error = crate::groups::promote(&jar, GroupId(1), UserId(3))
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::RankOutOfBounds { amount: 1, .. }));
+
+        // The Guest role is skipped as well
+        let error = crate::groups::demote(&jar, GroupId(1), UserId(2))
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::RankOutOfBounds { amount: -1, .. }));
+
+        let change = crate::groups::change_rank(&jar, GroupId(1), UserId(2), 10, RankBounds::Clamp)
+            .await
+            .unwrap();
+        assert_eq!(change.after.name, "Admin");
+
+        let change = crate::groups::change_rank(&jar, GroupId(1), UserId(3), 1, RankBounds::Clamp)
+            .await
+            .unwrap();
+        assert!(!change.changed());
+
+        let error = crate::groups::promote(&jar, GroupId(1), UserId(4))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            *error,
+            Error::RoleNotAssignable {
+                role_id: RoleId(14),
+                ..
+            }
+        ));
+
+        let error = crate::groups::promote(&jar, GroupId(1), UserId(5))
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::UserNotInGroup { .. }));
+
+        // Only the clamped promotion of user 2 was sent
+        let requests = server.requests();
+        let patches = requests
+            .iter()
+            .filter(|request| request.method == "PATCH")
+            .collect::<Vec<_>>();
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].body, r#"{"roleId":13}"#);
+    }
+
+    #[tokio::test]
+    async fn set_rank_by_number_and_name() {
+        use super::RoleCache;
+        use crate::util::Error;
+
+        let server = ranked_group_server().await;
+        let jar = server.jar().await;
+        let cache = RoleCache::default();
+        let role_requests = || {
+            server
+                .requests()
+                .iter()
+                .filter(|request| request.path == "/groups/v1/groups/1/roles")
+                .count()
+        };
+
+        let role = crate::groups::set_rank_by_number(&jar, &cache, GroupId(1), UserId(2), 50)
+            .await
+            .unwrap();
+        assert_eq!(role.id, RoleId(12));
+
+        let role = crate::groups::set_role_by_name(&jar, &cache, GroupId(1), UserId(2), " admin ")
+            .await
+            .unwrap();
+        assert_eq!(role.id, RoleId(13));
+        assert_eq!(role_requests(), 1);
+
+        let error = crate::groups::set_rank_by_number(&jar, &cache, GroupId(1), UserId(2), 255)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            *error,
+            Error::RoleNotAssignable {
+                role_id: RoleId(14),
+                ..
+            }
+        ));
+
+        // A role that isn't cached is looked up again before giving up
+        let error =
+            crate::groups::set_role_by_name(&jar, &cache, GroupId(1), UserId(2), "Moderator")
+                .await
+                .unwrap_err();
+        assert!(matches!(*error, Error::RoleNotFound { ref role, .. } if role == "Moderator"));
+        assert_eq!(role_requests(), 2);
+
+        let bodies = server
+            .requests()
+            .into_iter()
+            .filter(|request| request.method == "PATCH")
+            .map(|request| request.body)
+            .collect::<Vec<_>>();
+        assert_eq!(bodies, [r#"{"roleId":12}"#, r#"{"roleId":13}"#]);
+    }
 }
@@ -1,7 +1,7 @@
 use rspc::Type;
 use serde::{Deserialize, Serialize};
 
-use crate::util::{jar::RequestJar, Error};
+use crate::util::{hosts::Domain, jar::RequestJar, Error};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
@@ -51,7 +51,10 @@ pub struct GroupNameChangeConfigurationMetadata {
 
 /// Gets the metadata for group configuration
 pub async fn config_metadata(jar: &RequestJar) -> Result<GroupConfigMetadata, Box<Error>> {
-    let url = "https://groups.roblox.com/v1/groups/configuration/metadata";
+    let url = format!(
+        "{}/v1/groups/configuration/metadata",
+        jar.base_url(Domain::Groups)
+    );
     let response = jar.get_json::<GroupConfigMetadata>(&url).await?;
     Ok(response)
 }
@@ -72,7 +75,7 @@ pub struct GroupMetadata {
 
 /// Gets the metadata for groups
 pub async fn metadata(jar: &RequestJar) -> Result<GroupMetadata, Box<Error>> {
-    let url = "https://groups.roblox.com/v1/groups/metadata";
+    let url = format!("{}/v1/groups/metadata", jar.base_url(Domain::Groups));
     let response = jar.get_json::<GroupMetadata>(&url).await?;
     Ok(response)
 }
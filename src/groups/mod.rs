@@ -1,20 +1,32 @@
+mod audit_log;
+mod audit_log_watcher;
+mod bulk;
+mod events;
+#[allow(clippy::module_inception)]
 mod groups;
-mod join_requests;
+mod join_request_policy;
+pub mod join_requests;
 mod membership;
 mod metadata;
+mod permission_templates;
 mod permissions;
 mod primary;
-mod relationships;
+pub mod relationships;
 mod revenue;
 mod roles;
 mod search;
 mod social_links;
 mod wall;
 
+pub use audit_log::*;
+pub use audit_log_watcher::*;
+pub use bulk::*;
+pub use events::*;
 pub use groups::*;
-pub use join_requests::*;
+pub use join_request_policy::*;
 pub use membership::*;
 pub use metadata::*;
+pub use permission_templates::*;
 pub use permissions::*;
 pub use primary::*;
 pub use relationships::*;
@@ -0,0 +1,458 @@
+//! Role permissions as a template that can be kept in a file and compared to a group.
+//!
+//! A [`PermissionTemplate`] lists the permissions each role should have by role name, so one
+//! template can be checked against many groups. Only the permissions in the template are
+//! checked, so it can be as strict as needed:
+//!
+//! ```no_run
+//! # use rustyroblox::{groups::{self, PermissionTemplate}, util::{ids::GroupId, jar::RequestJar}};
+//! # async fn audit(jar: &RequestJar) -> Result<(), Box<rustyroblox::util::Error>> {
+//! let template: PermissionTemplate = serde_json::from_str(
+//!     r#"{ "roles": { "Member": { "changeRank": false, "removeMembers": false } } }"#,
+//! )
+//! .unwrap();
+//!
+//! let diff = groups::diff_permissions(jar, GroupId(7370273), &template).await?;
+//! for role in &diff.roles {
+//!     for change in &role.changes {
+//!         println!("{}: {} should be {}", role.role_name, change.permission, change.desired);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+
+use rspc::Type;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+use crate::util::{
+    ids::{GroupId, RoleId},
+    jar::RequestJar,
+    Error,
+};
+
+use super::{
+    permissions, update_role_permissions, GroupPermissions, RolePermissions,
+    UpdateRolePermissionsRequestPermissions,
+};
+
+/// A permission that can be changed on a role
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type, Display,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum Permission {
+    ViewWall,
+    PostToWall,
+    DeleteFromWall,
+    ViewStatus,
+    PostToStatus,
+    ChangeRank,
+    InviteMembers,
+    RemoveMembers,
+    ManageRelationships,
+    ManageClan,
+    ViewAuditLogs,
+    SpendGroupFunds,
+    AdvertiseGroup,
+    CreateItems,
+    ManageItems,
+    AddGroupPlaces,
+    ManageGroupGames,
+    /// Read-only, the permission update endpoint can't change it
+    ViewGroupPayouts,
+    ViewAnalytics,
+    UseCloudAuthentication,
+    AdministerCloudAuthentication,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 21] = [
+        Permission::ViewWall,
+        Permission::PostToWall,
+        Permission::DeleteFromWall,
+        Permission::ViewStatus,
+        Permission::PostToStatus,
+        Permission::ChangeRank,
+        Permission::InviteMembers,
+        Permission::RemoveMembers,
+        Permission::ManageRelationships,
+        Permission::ManageClan,
+        Permission::ViewAuditLogs,
+        Permission::SpendGroupFunds,
+        Permission::AdvertiseGroup,
+        Permission::CreateItems,
+        Permission::ManageItems,
+        Permission::AddGroupPlaces,
+        Permission::ManageGroupGames,
+        Permission::ViewGroupPayouts,
+        Permission::ViewAnalytics,
+        Permission::UseCloudAuthentication,
+        Permission::AdministerCloudAuthentication,
+    ];
+
+    /// Whether the permission can be read but not changed
+    pub fn is_read_only(self) -> bool {
+        self == Permission::ViewGroupPayouts
+    }
+}
+
+impl GroupPermissions {
+    /// Whether the role has a permission
+    pub fn get(&self, permission: Permission) -> bool {
+        let posts = &self.group_posts_permissions;
+        let membership = &self.group_membership_permissions;
+        let management = &self.group_management_permissions;
+        let economy = &self.group_economy_permissions;
+        let open_cloud = &self.group_open_cloud_permissions;
+
+        match permission {
+            Permission::ViewWall => posts.view_wall,
+            Permission::PostToWall => posts.post_to_wall,
+            Permission::DeleteFromWall => posts.delete_from_wall,
+            Permission::ViewStatus => posts.view_status,
+            Permission::PostToStatus => posts.post_to_status,
+            Permission::ChangeRank => membership.change_rank,
+            Permission::InviteMembers => membership.invite_members,
+            Permission::RemoveMembers => membership.remove_members,
+            Permission::ManageRelationships => management.manage_relationships,
+            Permission::ManageClan => management.manage_clan,
+            Permission::ViewAuditLogs => management.view_audit_logs,
+            Permission::SpendGroupFunds => economy.spend_group_funds,
+            Permission::AdvertiseGroup => economy.advertise_group,
+            Permission::CreateItems => economy.create_items,
+            Permission::ManageItems => economy.manage_items,
+            Permission::AddGroupPlaces => economy.add_group_places,
+            Permission::ManageGroupGames => economy.manage_group_games,
+            Permission::ViewGroupPayouts => economy.view_group_payouts,
+            Permission::ViewAnalytics => economy.view_analytics,
+            Permission::UseCloudAuthentication => open_cloud.use_cloud_authentication,
+            Permission::AdministerCloudAuthentication => open_cloud.administer_cloud_authentication,
+        }
+    }
+}
+
+impl UpdateRolePermissionsRequestPermissions {
+    /// Sets a permission to be changed, permissions that aren't set are left as they are.
+    /// Read-only permissions are ignored.
+    pub fn set(&mut self, permission: Permission, value: bool) {
+        let field = match permission {
+            Permission::ViewWall => &mut self.view_wall,
+            Permission::PostToWall => &mut self.post_to_wall,
+            Permission::DeleteFromWall => &mut self.delete_from_wall,
+            Permission::ViewStatus => &mut self.view_status,
+            Permission::PostToStatus => &mut self.post_to_status,
+            Permission::ChangeRank => &mut self.change_rank,
+            Permission::InviteMembers => &mut self.invite_members,
+            Permission::RemoveMembers => &mut self.remove_members,
+            Permission::ManageRelationships => &mut self.manage_relationships,
+            Permission::ManageClan => &mut self.manage_clan,
+            Permission::ViewAuditLogs => &mut self.view_audit_logs,
+            Permission::SpendGroupFunds => &mut self.spend_group_funds,
+            Permission::AdvertiseGroup => &mut self.advertise_group,
+            Permission::CreateItems => &mut self.create_items,
+            Permission::ManageItems => &mut self.manage_items,
+            Permission::AddGroupPlaces => &mut self.add_group_places,
+            Permission::ManageGroupGames => &mut self.manage_group_games,
+            Permission::ViewGroupPayouts => return,
+            Permission::ViewAnalytics => &mut self.view_analytics,
+            Permission::UseCloudAuthentication => &mut self.use_cloud_authentication,
+            Permission::AdministerCloudAuthentication => &mut self.administer_cloud_authentication,
+        };
+        *field = Some(value);
+    }
+}
+
+/// The permissions roles should have, by role name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionTemplate {
+    pub roles: BTreeMap<String, BTreeMap<Permission, bool>>,
+}
+
+impl PermissionTemplate {
+    /// A template with every permission of every role, e.g. to save how a group is set up now
+    pub fn from_roles(roles: &[RolePermissions]) -> PermissionTemplate {
+        let roles = roles
+            .iter()
+            .map(|role| {
+                let permissions = Permission::ALL
+                    .iter()
+                    .map(|permission| (*permission, role.permissions.get(*permission)))
+                    .collect();
+                (role.role.name.clone(), permissions)
+            })
+            .collect();
+
+        PermissionTemplate { roles }
+    }
+
+    /// Compares the template to the permissions roles have now
+    pub fn diff(&self, current: &[RolePermissions]) -> PermissionDiff {
+        let mut diff = PermissionDiff::default();
+
+        for (role_name, desired) in &self.roles {
+            let Some(role) = current.iter().find(|role| &role.role.name == role_name) else {
+                diff.missing_roles.push(role_name.clone());
+                continue;
+            };
+
+            let changes = desired
+                .iter()
+                .filter(|(permission, desired)| role.permissions.get(**permission) != **desired)
+                .map(|(permission, desired)| PermissionChange {
+                    permission: *permission,
+                    current: !desired,
+                    desired: *desired,
+                })
+                .collect::<Vec<_>>();
+
+            if !changes.is_empty() {
+                diff.roles.push(RolePermissionDiff {
+                    role_id: role.role.id,
+                    role_name: role_name.clone(),
+                    changes,
+                });
+            }
+        }
+
+        diff
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionChange {
+    pub permission: Permission,
+    pub current: bool,
+    pub desired: bool,
+}
+
+/// The permissions of a role that don't match the template
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RolePermissionDiff {
+    pub role_id: RoleId,
+    pub role_name: String,
+    pub changes: Vec<PermissionChange>,
+}
+
+impl RolePermissionDiff {
+    /// The request that changes only the permissions that don't match
+    pub fn to_update(&self) -> UpdateRolePermissionsRequestPermissions {
+        let mut update = UpdateRolePermissionsRequestPermissions::default();
+        for change in &self.changes {
+            update.set(change.permission, change.desired);
+        }
+        update
+    }
+}
+
+/// How a group's role permissions differ from a template
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionDiff {
+    /// The roles with permissions that don't match, roles that match aren't included
+    pub roles: Vec<RolePermissionDiff>,
+    /// Roles in the template the group doesn't have
+    pub missing_roles: Vec<String>,
+}
+
+impl PermissionDiff {
+    /// Whether every role in the template exists and has the permissions it should
+    pub fn is_empty(&self) -> bool {
+        self.roles.is_empty() && self.missing_roles.is_empty()
+    }
+}
+
+/// Gets the permissions of every role in a group as a template
+///
+/// # Error codes
+/// Typed as [`PermissionsError`](crate::util::error_codes::PermissionsError).
+/// - 1: Group is invalid or does not exist.
+pub async fn snapshot_permissions(
+    jar: &RequestJar,
+    group_id: GroupId,
+) -> Result<PermissionTemplate, Box<Error>> {
+    let roles = permissions(jar, group_id).await?;
+    Ok(PermissionTemplate::from_roles(&roles))
+}
+
+/// Compares a group's role permissions to a template, without changing anything
+///
+/// # Error codes
+/// Typed as [`PermissionsError`](crate::util::error_codes::PermissionsError).
+/// - 1: Group is invalid or does not exist.
+pub async fn diff_permissions(
+    jar: &RequestJar,
+    group_id: GroupId,
+    template: &PermissionTemplate,
+) -> Result<PermissionDiff, Box<Error>> {
+    let roles = permissions(jar, group_id).await?;
+    Ok(template.diff(&roles))
+}
+
+/// Changes the permissions that don't match a template, a request is only sent for roles
+/// with changes and only includes the permissions that differ. Returns the differences it
+/// found, read-only permissions among them are left as they are.
+///
+/// Roles are updated one after another and the first error is returned, the roles before it
+/// have been updated already. Applying the template again continues where it stopped.
+///
+/// # Error codes
+/// Typed as [`PermissionsError`](crate::util::error_codes::PermissionsError).
+/// - 1: Group is invalid or does not exist.
+/// - 2: The roleset is invalid or does not exist.
+/// - 3: You are not authorized to view/edit permissions for this role.
+/// - 4: This role's permissions can not be modified.
+pub async fn apply_permissions(
+    jar: &RequestJar,
+    group_id: GroupId,
+    template: &PermissionTemplate,
+) -> Result<PermissionDiff, Box<Error>> {
+    let diff = diff_permissions(jar, group_id, template).await?;
+
+    for role in &diff.roles {
+        if role
+            .changes
+            .iter()
+            .all(|change| change.permission.is_read_only())
+        {
+            continue;
+        }
+        update_role_permissions(jar, group_id, role.role_id, role.to_update()).await?;
+    }
+
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        apply_permissions, diff_permissions, snapshot_permissions, Permission, PermissionChange,
+        PermissionTemplate,
+    };
+    use crate::{
+        mock::{MockResponse, MockServer},
+        util::{
+            hosts::Domain,
+            ids::{GroupId, RoleId},
+        },
+    };
+
+    /// A mock group 1 where the Member role (11) can only view the wall and the Admin role (13)
+    /// can also rank and exile members. Permission changes are accepted for both.
+    async fn permissions_server() -> MockServer {
+        let role = |id: i64, name: &str, rank: i64, manager: bool| {
+            serde_json::json!({
+                "groupId": 1,
+                "role": { "id": id, "name": name, "rank": rank },
+                "permissions": {
+                    "groupPostsPermissions": { "viewWall": true, "postToWall": false, "deleteFromWall": false, "viewStatus": true, "postToStatus": false },
+                    "groupMembershipPermissions": { "changeRank": manager, "inviteMembers": manager, "removeMembers": manager },
+                    "groupManagementPermissions": { "manageRelationships": false, "manageClan": false, "viewAuditLogs": manager },
+                    "groupEconomyPermissions": { "spendGroupFunds": false, "advertiseGroup": false, "createItems": false, "manageItems": false, "addGroupPlaces": false, "manageGroupGames": false, "viewGroupPayouts": false, "viewAnalytics": false },
+                    "groupOpenCloudPermissions": { "useCloudAuthentication": false, "administerCloudAuthentication": false },
+                },
+            })
+        };
+
+        let server = MockServer::start().await;
+        server.mock(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/roles/permissions",
+            MockResponse::json(
+                &serde_json::json!({
+                    "data": [role(11, "Member", 1, false), role(13, "Admin", 200, true)],
+                })
+                .to_string(),
+            ),
+        );
+        for role_id in [11, 13] {
+            server.mock(
+                Domain::Groups,
+                "PATCH",
+                &format!("/v1/groups/1/roles/{}/permissions", role_id),
+                MockResponse::json("{}"),
+            );
+        }
+        server
+    }
+
+    #[tokio::test]
+    async fn permission_template_diff() {
+        let server = permissions_server().await;
+        let jar = server.jar().await;
+
+        let snapshot = snapshot_permissions(&jar, GroupId(1)).await.unwrap();
+        assert_eq!(snapshot.roles.len(), 2);
+        assert!(snapshot.roles["Admin"][&Permission::ChangeRank]);
+        assert_eq!(snapshot.roles["Member"].len(), Permission::ALL.len());
+
+        // A snapshot matches the group it was taken of
+        let diff = diff_permissions(&jar, GroupId(1), &snapshot).await.unwrap();
+        assert!(diff.is_empty());
+
+        let template: PermissionTemplate = serde_json::from_str(
+            r#"{
+                "roles": {
+                    "Member": { "viewWall": true, "postToWall": true },
+                    "Admin": { "changeRank": true, "viewAuditLogs": false },
+                    "Moderator": { "removeMembers": true }
+                }
+            }"#,
+        )
+        .unwrap();
+        let diff = diff_permissions(&jar, GroupId(1), &template).await.unwrap();
+        assert_eq!(diff.missing_roles, ["Moderator"]);
+        assert_eq!(diff.roles.len(), 2);
+        assert_eq!(diff.roles[0].role_name, "Admin");
+        assert_eq!(
+            diff.roles[0].changes,
+            [PermissionChange {
+                permission: Permission::ViewAuditLogs,
+                current: true,
+                desired: false,
+            }]
+        );
+        assert_eq!(diff.roles[1].role_id, RoleId(11));
+        assert_eq!(diff.roles[1].changes[0].permission, Permission::PostToWall);
+        assert!(server
+            .requests()
+            .iter()
+            .all(|request| request.method == "GET"));
+    }
+
+    #[tokio::test]
+    async fn apply_permission_template() {
+        let server = permissions_server().await;
+        let jar = server.jar().await;
+
+        let template: PermissionTemplate = serde_json::from_str(
+            r#"{ "roles": {
+                "Member": { "viewWall": true, "postToWall": true, "viewAnalytics": false, "viewGroupPayouts": true },
+                "Admin": { "viewGroupPayouts": true }
+            } }"#,
+        )
+        .unwrap();
+        let applied = apply_permissions(&jar, GroupId(1), &template)
+            .await
+            .unwrap();
+        // Read-only permissions are reported but not sent
+        assert_eq!(applied.roles.len(), 2);
+
+        let patches = server
+            .requests()
+            .into_iter()
+            .filter(|request| request.method == "PATCH")
+            .collect::<Vec<_>>();
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].path, "/groups/v1/groups/1/roles/11/permissions");
+        assert_eq!(patches[0].body, r#"{"permissions":{"postToWall":true}}"#);
+    }
+}
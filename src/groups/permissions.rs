@@ -1,7 +1,13 @@
 use rspc::Type;
 use serde::{Deserialize, Serialize};
 
-use crate::util::{jar::RequestJar, responses::DataWrapper, Error};
+use crate::util::{
+    hosts::Domain,
+    ids::{GroupId, RoleId},
+    jar::RequestJar,
+    responses::{DataWrapper, EmptyResponse},
+    Error,
+};
 
 use super::GroupRole;
 
@@ -64,7 +70,7 @@ pub struct GroupOpenCloudPermissions {
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct RolePermissions {
-    pub group_id: i64,
+    pub group_id: GroupId,
     pub role: GroupRole,
     pub permissions: GroupPermissions,
 }
@@ -72,17 +78,20 @@ pub struct RolePermissions {
 /// Gets the permissions for a specific role in a group
 ///
 /// # Error codes
+/// Typed as [`PermissionsError`](crate::util::error_codes::PermissionsError).
 /// - 1: Group is invalid or does not exist.
 /// - 2: The roleset is invalid or does not exist.
 /// - 3: You are not authorized to view/edit permissions for this role.
 pub async fn role_permissions(
     jar: &RequestJar,
-    group_id: i64,
-    role_id: i64,
+    group_id: GroupId,
+    role_id: RoleId,
 ) -> Result<RolePermissions, Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/roles/{}/permissions",
-        group_id, role_id
+        "{}/v1/groups/{}/roles/{}/permissions",
+        jar.base_url(Domain::Groups),
+        group_id,
+        role_id
     );
 
     let response = jar.get_json(&url).await?;
@@ -90,28 +99,49 @@ pub async fn role_permissions(
     Ok(response)
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+/// The permissions to change, the ones that are `None` are left as they are
+#[derive(Debug, Serialize, Deserialize, Clone, Default, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateRolePermissionsRequestPermissions {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub delete_from_wall: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub post_to_wall: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub invite_members: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub post_to_status: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub remove_members: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub view_status: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub view_wall: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub change_rank: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub advertise_group: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub manage_relationships: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub add_group_places: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub view_audit_logs: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub create_items: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub manage_items: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub spend_group_funds: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub manage_clan: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub manage_group_games: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub use_cloud_authentication: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub administer_cloud_authentication: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub view_analytics: Option<bool>,
 }
 
@@ -124,22 +154,25 @@ pub struct UpdateRolePermissionsRequest {
 /// Sets the permissions for a specific role in a group
 ///
 /// # Error codes
+/// Typed as [`PermissionsError`](crate::util::error_codes::PermissionsError).
 /// - 1: Group is invalid or does not exist.
 /// - 2: The roleset is invalid or does not exist.
 /// - 3: You are not authorized to view/edit permissions for this role.
 /// - 4: This role's permissions can not be modified.
 pub async fn update_role_permissions(
     jar: &RequestJar,
-    group_id: i64,
-    role_id: i64,
+    group_id: GroupId,
+    role_id: RoleId,
     permissions: UpdateRolePermissionsRequestPermissions,
 ) -> Result<(), Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/roles/{}/permissions",
-        group_id, role_id
+        "{}/v1/groups/{}/roles/{}/permissions",
+        jar.base_url(Domain::Groups),
+        group_id,
+        role_id
     );
 
-    jar.patch_json(&url, &(UpdateRolePermissionsRequest { permissions }))
+    jar.patch_json::<EmptyResponse, _>(&url, &(UpdateRolePermissionsRequest { permissions }))
         .await?;
 
     Ok(())
@@ -148,15 +181,17 @@ pub async fn update_role_permissions(
 /// Gets the permissions for the group's guest role
 ///
 /// # Error codes
+/// Typed as [`PermissionsError`](crate::util::error_codes::PermissionsError).
 /// - 1: Group is invalid or does not exist.
 /// - 2: The roleset is invalid or does not exist.
 /// - 3: You are not authorized to view/edit permissions for this role.
 pub async fn guest_permissions(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
 ) -> Result<RolePermissions, Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/roles/guest/permissions",
+        "{}/v1/groups/{}/roles/guest/permissions",
+        jar.base_url(Domain::Groups),
         group_id,
     );
 
@@ -168,15 +203,17 @@ pub async fn guest_permissions(
 /// Gets the permissions for all the group's roles
 ///
 /// # Error codes
+/// Typed as [`PermissionsError`](crate::util::error_codes::PermissionsError).
 /// - 1: Group is invalid or does not exist.
 ///
 /// *Note: None were provided in the documentation*
 pub async fn permissions(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
 ) -> Result<Vec<RolePermissions>, Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/roles/permissions",
+        "{}/v1/groups/{}/roles/permissions",
+        jar.base_url(Domain::Groups),
         group_id,
     );
 
@@ -1,7 +1,13 @@
 use rspc::Type;
 use serde::{Deserialize, Serialize};
 
-use crate::util::{jar::RequestJar, Error};
+use crate::util::{
+    hosts::Domain,
+    ids::{GroupId, UserId},
+    jar::RequestJar,
+    responses::EmptyResponse,
+    Error,
+};
 
 use super::{Group, GroupRole};
 
@@ -19,14 +25,15 @@ pub struct PrimaryGroupResponse {
 /// 4: User is invalid or does not exist.
 pub async fn primary_group(
     jar: &RequestJar,
-    user_id: i64,
+    user_id: UserId,
 ) -> Result<PrimaryGroupResponse, Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/users/{}/groups/primary/role",
+        "{}/v1/users/{}/groups/primary/role",
+        jar.base_url(Domain::Groups),
         user_id
     );
 
-    Ok(jar.get_json::<PrimaryGroupResponse>(&url).await?)
+    jar.get_json::<PrimaryGroupResponse>(&url).await
 }
 
 /// Removes the currently authenticated user's primary group.
@@ -34,9 +41,9 @@ pub async fn primary_group(
 /// # Error codes
 /// - 0: Authorization has been denied for this request.
 pub async fn remove_primary_group(jar: &RequestJar) -> Result<(), Box<Error>> {
-    let url = "https://groups.roblox.com/v1/user/groups/primary";
+    let url = format!("{}/v1/user/groups/primary", jar.base_url(Domain::Groups));
 
-    jar.delete(&url, "".to_string()).await?;
+    jar.delete_empty(&url).await?;
 
     Ok(())
 }
@@ -44,7 +51,7 @@ pub async fn remove_primary_group(jar: &RequestJar) -> Result<(), Box<Error>> {
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct SetPrimaryGroupRequest {
-    pub group_id: i64,
+    pub group_id: GroupId,
 }
 
 /// Sets the currently authenticated user's primary group.
@@ -53,13 +60,14 @@ pub struct SetPrimaryGroupRequest {
 /// 0: Authorization has been denied for this request.
 /// 1: Group is invalid or does not exist.
 /// 2: You aren't a member of the group specified.
-pub async fn set_primary_group(jar: &RequestJar, group_id: i64) -> Result<(), Box<Error>> {
+pub async fn set_primary_group(jar: &RequestJar, group_id: GroupId) -> Result<(), Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/user/groups/{}/primary",
+        "{}/v1/user/groups/{}/primary",
+        jar.base_url(Domain::Groups),
         group_id
     );
 
-    jar.post_json(&url, SetPrimaryGroupRequest { group_id })
+    jar.post_json::<EmptyResponse, _>(&url, SetPrimaryGroupRequest { group_id })
         .await?;
 
     Ok(())
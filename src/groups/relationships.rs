@@ -2,19 +2,22 @@ use rspc::Type;
 use serde::{Deserialize, Serialize};
 use strum_macros::Display;
 
-use crate::util::{jar::RequestJar, Error};
+use crate::util::{hosts::Domain, ids::GroupId, jar::RequestJar, Error};
 
 use super::Group;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 pub struct BatchRequest {
-    pub group_ids: Vec<i64>,
+    pub group_ids: Vec<GroupId>,
 }
 
 /// Batch management of group relationships (enemies and allies)
+#[allow(clippy::module_inception)]
 pub mod relationships {
 
-    use crate::util::{jar::RequestJar, Error};
+    use crate::util::{
+        hosts::Domain, ids::GroupId, jar::RequestJar, responses::EmptyResponse, Error,
+    };
 
     use super::{BatchRequest, RelationshipType};
 
@@ -23,8 +26,8 @@ pub mod relationships {
     #[allow(unused)] // It appears to be a bug in the checker, this kinda fixes it.
     pub async fn decline(
         jar: &RequestJar,
-        group_id: i64,
-        group_ids: Vec<i64>,
+        group_id: GroupId,
+        group_ids: Vec<GroupId>,
         relationship_type: RelationshipType,
     ) -> Result<(), Box<Error>> {
         if relationship_type == RelationshipType::All {
@@ -34,13 +37,15 @@ pub mod relationships {
         }
 
         let url = format!(
-            "https://groups.roblox.com/v1/groups/{}/relationships/{}/requests",
+            "{}/v1/groups/{}/relationships/{}/requests",
+            jar.base_url(Domain::Groups),
             group_id,
             relationship_type.get_relationship_type_string()
         );
         let request = BatchRequest { group_ids };
-        let response = jar.delete_json::<(), BatchRequest>(&url, request).await?;
-        Ok(response)
+        jar.delete_json::<EmptyResponse, BatchRequest>(&url, request)
+            .await?;
+        Ok(())
     }
 
     /// Accepts a batch of group relationships for a group.
@@ -48,8 +53,8 @@ pub mod relationships {
     #[allow(unused)] // It appears to be a bug in the checker, this kinda fixes it.
     pub async fn accept(
         jar: &RequestJar,
-        group_id: i64,
-        group_ids: Vec<i64>,
+        group_id: GroupId,
+        group_ids: Vec<GroupId>,
         relationship_type: RelationshipType,
     ) -> Result<(), Box<Error>> {
         if relationship_type == RelationshipType::All {
@@ -59,20 +64,22 @@ pub mod relationships {
         }
 
         let url = format!(
-            "https://groups.roblox.com/v1/groups/{}/relationships/{}/requests",
+            "{}/v1/groups/{}/relationships/{}/requests",
+            jar.base_url(Domain::Groups),
             group_id,
             relationship_type.get_relationship_type_string()
         );
         let request = BatchRequest { group_ids };
-        let response = jar.post_json::<(), BatchRequest>(&url, request).await?;
-        Ok(response)
+        jar.post_json::<EmptyResponse, BatchRequest>(&url, request)
+            .await?;
+        Ok(())
     }
 }
 
 /// Management of 1 group relationship at a time  (enemies and allies)
 pub mod relationship {
 
-    use crate::util::{jar::RequestJar, Error};
+    use crate::util::{hosts::Domain, ids::GroupId, jar::RequestJar, Error};
 
     use super::RelationshipType;
 
@@ -81,18 +88,19 @@ pub mod relationship {
     #[allow(unused)] // It appears to be a bug in the checker, this kinda fixes it.
     pub async fn decline(
         jar: &RequestJar,
-        group_id: i64,
-        relation_group_id: i64,
+        group_id: GroupId,
+        relation_group_id: GroupId,
         relationship_type: RelationshipType,
     ) -> Result<(), Box<Error>> {
         let url = format!(
-            "https://groups.roblox.com/v1/groups/{}/relationships/{}/requests/{}",
+            "{}/v1/groups/{}/relationships/{}/requests/{}",
+            jar.base_url(Domain::Groups),
             group_id,
             relationship_type.get_relationship_type_string(),
             relation_group_id
         );
 
-        jar.delete(&url, "".to_string()).await?;
+        jar.delete_empty(&url).await?;
 
         Ok(())
     }
@@ -102,18 +110,19 @@ pub mod relationship {
     #[allow(unused)] // It appears to be a bug in the checker, this kinda fixes it.
     pub async fn accept(
         jar: &RequestJar,
-        group_id: i64,
-        relation_group_id: i64,
+        group_id: GroupId,
+        relation_group_id: GroupId,
         relationship_type: RelationshipType,
     ) -> Result<(), Box<Error>> {
         let url = format!(
-            "https://groups.roblox.com/v1/groups/{}/relationships/{}/requests/{}",
+            "{}/v1/groups/{}/relationships/{}/requests/{}",
+            jar.base_url(Domain::Groups),
             group_id,
             relationship_type.get_relationship_type_string(),
             relation_group_id
         );
 
-        jar.post(&url, "".to_string()).await?;
+        jar.post_empty(&url).await?;
 
         Ok(())
     }
@@ -142,7 +151,7 @@ impl RelationshipType {
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct GroupRelationships {
-    pub group_id: i64,
+    pub group_id: GroupId,
     pub relationship_type: RelationshipType,
     pub total_group_count: i64,
     #[serde(rename = "relatedGroups")]
@@ -152,13 +161,14 @@ pub struct GroupRelationships {
 /// Retrieves a list of groups that are enemies, allies or both of the specified group.
 ///
 /// # Error codes
+/// Typed as [`RelationshipListError`](crate::util::error_codes::RelationshipListError).
 /// - 1: Group is invalid or does not exist.
 /// - 4: Group relationship type or request type is invalid.
 /// - 8: Invalid or missing pagination parameters
 #[async_recursion::async_recursion]
 pub async fn relationships(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
     relationship_type: RelationshipType,
 ) -> Result<GroupRelationships, Box<Error>> {
     if relationship_type == RelationshipType::All {
@@ -174,37 +184,50 @@ pub async fn relationships(
         });
     }
 
-    let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/relationships/{}?model.startRowIndex=0&model.maxRows=1000",
-        group_id, relationship_type.get_relationship_type_string()
-    );
-    let response = jar.get_json::<GroupRelationships>(&url).await?;
+    let url = jar
+        .url(
+            Domain::Groups,
+            &format!(
+                "/v1/groups/{}/relationships/{}",
+                group_id,
+                relationship_type.get_relationship_type_string()
+            ),
+        )
+        .query("model.startRowIndex", 0)
+        .query("model.maxRows", 1000);
+    let response = jar.get_json::<GroupRelationships>(&url.build()).await?;
     Ok(response)
 }
 
 /// Retrieves a list of groups that are enemies of the specified group.
 ///
 /// # Error codes
+/// Typed as [`RelationshipListError`](crate::util::error_codes::RelationshipListError).
 /// - 1: Group is invalid or does not exist.
 /// - 4: Group relationship type or request type is invalid.
 /// - 8: Invalid or missing pagination parameters
-pub async fn enemies(jar: &RequestJar, group_id: i64) -> Result<GroupRelationships, Box<Error>> {
+pub async fn enemies(
+    jar: &RequestJar,
+    group_id: GroupId,
+) -> Result<GroupRelationships, Box<Error>> {
     relationships(jar, group_id, RelationshipType::Enemy).await
 }
 
 /// Retrieves a list of groups that are allies of the specified group.
 ///
 /// # Error codes
+/// Typed as [`RelationshipListError`](crate::util::error_codes::RelationshipListError).
 /// - 1: Group is invalid or does not exist.
 /// - 4: Group relationship type or request type is invalid.
 /// - 8: Invalid or missing pagination parameters
-pub async fn allies(jar: &RequestJar, group_id: i64) -> Result<GroupRelationships, Box<Error>> {
+pub async fn allies(jar: &RequestJar, group_id: GroupId) -> Result<GroupRelationships, Box<Error>> {
     relationships(jar, group_id, RelationshipType::Ally).await
 }
 
 /// Retrieves a list of enemy, ally or both relationship requests for the specified group.
 ///
 /// # Error codes
+/// Typed as [`RelationshipListError`](crate::util::error_codes::RelationshipListError).
 /// - 1: Group is invalid or does not exist.
 /// - 4: Group relationship type or request type is invalid.
 /// - 5: You don't have permission to manage this group's relationships.
@@ -212,7 +235,7 @@ pub async fn allies(jar: &RequestJar, group_id: i64) -> Result<GroupRelationship
 #[async_recursion::async_recursion]
 pub async fn relationship_requests(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
     relationship_type: RelationshipType,
 ) -> Result<GroupRelationships, Box<Error>> {
     if relationship_type == RelationshipType::All {
@@ -228,24 +251,32 @@ pub async fn relationship_requests(
         });
     }
 
-    let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/relationships/{}?model.startRowIndex=0&model.maxRows=1000",
-        group_id, relationship_type.get_relationship_type_string()
-    );
-    let response = jar.get_json::<GroupRelationships>(&url).await?;
+    let url = jar
+        .url(
+            Domain::Groups,
+            &format!(
+                "/v1/groups/{}/relationships/{}",
+                group_id,
+                relationship_type.get_relationship_type_string()
+            ),
+        )
+        .query("model.startRowIndex", 0)
+        .query("model.maxRows", 1000);
+    let response = jar.get_json::<GroupRelationships>(&url.build()).await?;
     Ok(response)
 }
 
 /// Retrieves a list of groups that are enemies of the specified group.
 ///
 /// # Error codes
+/// Typed as [`RelationshipListError`](crate::util::error_codes::RelationshipListError).
 /// - 1: Group is invalid or does not exist.
 /// - 4: Group relationship type or request type is invalid.
 /// - 5: You don't have permission to manage this group's relationships.
 /// - 8: Invalid or missing pagination parameters
 pub async fn enemy_requests(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
 ) -> Result<GroupRelationships, Box<Error>> {
     relationship_requests(jar, group_id, RelationshipType::Enemy).await
 }
@@ -253,13 +284,14 @@ pub async fn enemy_requests(
 /// Retrieves a list of groups that are allies of the specified group.
 ///
 /// # Error codes
+/// Typed as [`RelationshipListError`](crate::util::error_codes::RelationshipListError).
 /// - 1: Group is invalid or does not exist.
 /// - 4: Group relationship type or request type is invalid.
 /// - 5: You don't have permission to manage this group's relationships.
 /// - 8: Invalid or missing pagination parameters
 pub async fn ally_requests(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
 ) -> Result<GroupRelationships, Box<Error>> {
     relationship_requests(jar, group_id, RelationshipType::Ally).await
 }
@@ -267,14 +299,15 @@ pub async fn ally_requests(
 /// Removes the specified group from the specified group's relationship list.
 ///
 /// # Error codes
+/// Typed as [`RelationshipError`](crate::util::error_codes::RelationshipError).
 /// - 2: Invalid group.
 /// - 3: Target group is invalid or does not exist.
 /// - 8: You are blocked from communicating with this user.
 /// - 11: Relationship does not exist.
 pub async fn remove(
     jar: &RequestJar,
-    group_id: i64,
-    target_group_id: i64,
+    group_id: GroupId,
+    target_group_id: GroupId,
     relationship_type: RelationshipType,
 ) -> Result<(), Box<Error>> {
     if relationship_type == RelationshipType::All {
@@ -282,26 +315,28 @@ pub async fn remove(
     }
 
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/relationships/{}/{}",
+        "{}/v1/groups/{}/relationships/{}/{}",
+        jar.base_url(Domain::Groups),
         group_id,
         relationship_type.get_relationship_type_string(),
         target_group_id
     );
-    jar.delete(&url, "".to_string()).await?;
+    jar.delete_empty(&url).await?;
     Ok(())
 }
 
 /// Removes the specified group from the specified group's enemies list.
 ///
 /// # Error codes
+/// Typed as [`RelationshipError`](crate::util::error_codes::RelationshipError).
 /// - 2: Invalid group.
 /// - 3: Target group is invalid or does not exist.
 /// - 8: You are blocked from communicating with this user.
 /// - 11: Relationship does not exist.
 pub async fn remove_enemy(
     jar: &RequestJar,
-    group_id: i64,
-    target_group_id: i64,
+    group_id: GroupId,
+    target_group_id: GroupId,
 ) -> Result<(), Box<Error>> {
     remove(jar, group_id, target_group_id, RelationshipType::Enemy).await?;
     Ok(())
@@ -310,14 +345,15 @@ pub async fn remove_enemy(
 /// Removes the specified group from the specified group's allies list.
 ///
 /// # Error codes
+/// Typed as [`RelationshipError`](crate::util::error_codes::RelationshipError).
 /// - 2: Invalid group.
 /// - 3: Target group is invalid or does not exist.
 /// - 8: You are blocked from communicating with this user.
 /// - 11: Relationship does not exist.
 pub async fn remove_ally(
     jar: &RequestJar,
-    group_id: i64,
-    target_group_id: i64,
+    group_id: GroupId,
+    target_group_id: GroupId,
 ) -> Result<(), Box<Error>> {
     remove(jar, group_id, target_group_id, RelationshipType::Ally).await?;
     Ok(())
@@ -326,6 +362,7 @@ pub async fn remove_ally(
 /// Sends a relationship request to the specified group.
 ///
 /// # Error codes
+/// Typed as [`RelationshipError`](crate::util::error_codes::RelationshipError).
 /// - 1: Group relationship type or request type is invalid.
 /// - 2: Invalid group.
 /// - 3: Target group is invalid or does not exist.
@@ -337,8 +374,8 @@ pub async fn remove_ally(
 /// - 9: Insufficient permissions.
 pub async fn send_request(
     jar: &RequestJar,
-    group_id: i64,
-    target_group_id: i64,
+    group_id: GroupId,
+    target_group_id: GroupId,
     relationship_type: RelationshipType,
 ) -> Result<(), Box<Error>> {
     if relationship_type == RelationshipType::All {
@@ -346,18 +383,20 @@ pub async fn send_request(
     }
 
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/relationships/{}/{}",
+        "{}/v1/groups/{}/relationships/{}/{}",
+        jar.base_url(Domain::Groups),
         group_id,
         relationship_type.get_relationship_type_string(),
         target_group_id
     );
-    jar.delete(&url, "".to_string()).await?;
+    jar.delete_empty(&url).await?;
     Ok(())
 }
 
 /// Sends an enemy relationship request to the specified group.
 ///
 /// # Error codes
+/// Typed as [`RelationshipError`](crate::util::error_codes::RelationshipError).
 /// - 1: Group relationship type or request type is invalid.
 /// - 2: Invalid group.
 /// - 3: Target group is invalid or does not exist.
@@ -369,8 +408,8 @@ pub async fn send_request(
 /// - 9: Insufficient permissions.
 pub async fn send_enemy_request(
     jar: &RequestJar,
-    group_id: i64,
-    target_group_id: i64,
+    group_id: GroupId,
+    target_group_id: GroupId,
 ) -> Result<(), Box<Error>> {
     send_request(jar, group_id, target_group_id, RelationshipType::Enemy).await?;
     Ok(())
@@ -379,6 +418,7 @@ pub async fn send_enemy_request(
 /// Sends an ally relationship request to the specified group.
 ///
 /// # Error codes
+/// Typed as [`RelationshipError`](crate::util::error_codes::RelationshipError).
 /// - 1: Group relationship type or request type is invalid.
 /// - 2: Invalid group.
 /// - 3: Target group is invalid or does not exist.
@@ -390,8 +430,8 @@ pub async fn send_enemy_request(
 /// - 9: Insufficient permissions.
 pub async fn send_ally_request(
     jar: &RequestJar,
-    group_id: i64,
-    target_group_id: i64,
+    group_id: GroupId,
+    target_group_id: GroupId,
 ) -> Result<(), Box<Error>> {
     send_request(jar, group_id, target_group_id, RelationshipType::Ally).await?;
     Ok(())
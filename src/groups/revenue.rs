@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     users::MinimalGroupUser,
-    util::{jar::RequestJar, responses::DataWrapper, Error},
+    util::{hosts::Domain, ids::GroupId, jar::RequestJar, responses::DataWrapper, Error},
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
@@ -16,14 +16,16 @@ pub struct PayoutRestrictions {
 /// Retrieves the payout restrictions for a group.
 ///
 /// # Error codes
+/// Typed as [`PayoutError`](crate::util::error_codes::PayoutError).
 /// - 1: Group is invalid or does not exist.
 /// - 9: You don't have permission to view this group's payouts.
 pub async fn get_payout_restrictions(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
 ) -> Result<PayoutRestrictions, Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/payout-restrictions",
+        "{}/v1/groups/{}/payout-restrictions",
+        jar.base_url(Domain::Groups),
         group_id
     );
     let response = jar.get_json::<PayoutRestrictions>(&url).await?;
@@ -40,13 +42,18 @@ pub struct RecurringPayout {
 /// Retrieves a list of recurring payouts for a group.
 ///
 /// # Error codes
+/// Typed as [`PayoutError`](crate::util::error_codes::PayoutError).
 /// - 1: Group is invalid or does not exist.
 /// - 9: You don't have permission to view this group's payouts.
 pub async fn get_recurring_payouts(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
 ) -> Result<Vec<RecurringPayout>, Box<Error>> {
-    let url = format!("https://groups.roblox.com/v1/groups/{}/payouts", group_id);
+    let url = format!(
+        "{}/v1/groups/{}/payouts",
+        jar.base_url(Domain::Groups),
+        group_id
+    );
     let response = jar
         .get_json::<DataWrapper<Vec<RecurringPayout>>>(&url)
         .await?;
@@ -1,22 +1,29 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
 use rspc::Type;
 use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
 
 use crate::{
     users::MinimalGroupUser,
     util::{
+        hosts::Domain,
+        ids::{GroupId, RoleId, UserId},
         jar::RequestJar,
         paging::{get_page, PageLimit, SortOrder},
-        responses::{DataWrapper, RobloxError},
-        Error,
+        responses::DataWrapper,
+        Error, RoleUpdateError,
     },
 };
 
-use super::user_memberships;
+use super::{
+    config_metadata, user_memberships, GroupRoleConfigurationMetadata, GUEST_RANK, OWNER_RANK,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct GroupRole {
-    pub id: i64,
+    pub id: RoleId,
     pub name: String,
     pub description: Option<String>,
     pub rank: i64,
@@ -26,54 +33,244 @@ pub struct GroupRole {
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct GroupRoleResponse {
-    pub group_id: i64,
+    pub group_id: GroupId,
     pub roles: Vec<GroupRole>,
 }
 
 /// Gets a group's roles.
 ///
 /// # Error codes
+/// Typed as [`RoleError`](crate::util::error_codes::RoleError).
 /// - 1: The group is invalid or does not exist.
-pub async fn roles(jar: &RequestJar, group_id: i64) -> Result<Vec<GroupRole>, Box<Error>> {
-    let url = format!("https://groups.roblox.com/v1/groups/{}/roles", group_id);
+pub async fn roles(jar: &RequestJar, group_id: GroupId) -> Result<Vec<GroupRole>, Box<Error>> {
+    let url = format!(
+        "{}/v1/groups/{}/roles",
+        jar.base_url(Domain::Groups),
+        group_id
+    );
     let response = jar.get_json::<GroupRoleResponse>(&url).await?;
     Ok(response.roles)
 }
 
+/// How long a group's roles are kept by default
+pub const DEFAULT_ROLE_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug)]
+struct CachedRoles {
+    roles: Vec<GroupRole>,
+    fetched: Instant,
+}
+
+/// Keeps the roles of every group they were looked up for, so ranking by rank number or role
+/// name doesn't request them each time.
+///
+/// Roles are requested again once they're older than the ttl, a ttl of zero disables the cache.
+#[derive(Debug)]
+pub struct RoleCache {
+    ttl: Duration,
+    groups: Mutex<HashMap<GroupId, CachedRoles>>,
+}
+
+impl Default for RoleCache {
+    fn default() -> Self {
+        RoleCache::new(DEFAULT_ROLE_CACHE_TTL)
+    }
+}
+
+impl RoleCache {
+    pub fn new(ttl: Duration) -> RoleCache {
+        RoleCache {
+            ttl,
+            groups: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// A cache that never keeps anything
+    pub fn disabled() -> RoleCache {
+        RoleCache::new(Duration::ZERO)
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// The roles of a group, if they were stored less than the ttl ago
+    pub fn get(&self, group_id: GroupId) -> Option<Vec<GroupRole>> {
+        let mut groups = self
+            .groups
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        match groups.get(&group_id) {
+            Some(cached) if cached.fetched.elapsed() < self.ttl => Some(cached.roles.clone()),
+            Some(_) => {
+                groups.remove(&group_id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Stores the roles of a group, replacing the ones stored before
+    pub fn insert(&self, group_id: GroupId, roles: Vec<GroupRole>) {
+        if self.ttl.is_zero() {
+            return;
+        }
+
+        self.groups
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(
+                group_id,
+                CachedRoles {
+                    roles,
+                    fetched: Instant::now(),
+                },
+            );
+    }
+
+    /// Forgets the roles of a group, e.g. after one of them has been changed
+    pub fn invalidate(&self, group_id: GroupId) {
+        self.groups
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&group_id);
+    }
+
+    /// Forgets the roles of every group
+    pub fn clear(&self) {
+        self.groups
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+    }
+}
+
+/// Gets a group's roles from a [`RoleCache`], requesting them when they aren't cached or
+/// have expired.
+///
+/// # Error codes
+/// Typed as [`RoleError`](crate::util::error_codes::RoleError).
+/// - 1: The group is invalid or does not exist.
+pub async fn cached_roles(
+    jar: &RequestJar,
+    cache: &RoleCache,
+    group_id: GroupId,
+) -> Result<Vec<GroupRole>, Box<Error>> {
+    if let Some(roles) = cache.get(group_id) {
+        return Ok(roles);
+    }
+
+    let roles = roles(jar, group_id).await?;
+    cache.insert(group_id, roles.clone());
+    Ok(roles)
+}
+
+/// Finds a role in the cached roles. When it isn't there the roles are requested again,
+/// in case it was added after they were cached.
+async fn find_role(
+    jar: &RequestJar,
+    cache: &RoleCache,
+    group_id: GroupId,
+    role: String,
+    matches: impl Fn(&[GroupRole]) -> Option<&GroupRole>,
+) -> Result<GroupRole, Box<Error>> {
+    if let Some(found) = cache
+        .get(group_id)
+        .and_then(|roles| matches(&roles).cloned())
+    {
+        return Ok(found);
+    }
+
+    let roles = roles(jar, group_id).await?;
+    cache.insert(group_id, roles.clone());
+    matches(&roles)
+        .cloned()
+        .ok_or_else(|| Box::new(Error::RoleNotFound { group_id, role }))
+}
+
+/// Gets the role with a rank (0-255) in a group, using the cached roles.
+///
+/// # Error codes
+/// Typed as [`RoleError`](crate::util::error_codes::RoleError).
+/// - 1: The group is invalid or does not exist.
+///
+/// - RoleNotFound: No role has the rank.
+pub async fn role_by_rank(
+    jar: &RequestJar,
+    cache: &RoleCache,
+    group_id: GroupId,
+    rank: i64,
+) -> Result<GroupRole, Box<Error>> {
+    find_role(jar, cache, group_id, rank.to_string(), |roles| {
+        roles.iter().find(|role| role.rank == rank)
+    })
+    .await
+}
+
+/// Gets the role with a name in a group, using the cached roles.
+/// An exact match is preferred, otherwise the case and surrounding whitespace are ignored.
+///
+/// # Error codes
+/// Typed as [`RoleError`](crate::util::error_codes::RoleError).
+/// - 1: The group is invalid or does not exist.
+///
+/// - RoleNotFound: No role has the name.
+pub async fn role_by_name(
+    jar: &RequestJar,
+    cache: &RoleCache,
+    group_id: GroupId,
+    name: &str,
+) -> Result<GroupRole, Box<Error>> {
+    let trimmed = name.trim();
+    find_role(jar, cache, group_id, name.to_string(), |roles| {
+        roles.iter().find(|role| role.name == name).or_else(|| {
+            roles
+                .iter()
+                .find(|role| role.name.trim().eq_ignore_ascii_case(trimmed))
+        })
+    })
+    .await
+}
+
 /// Gets users on a group's role.
 ///
 /// # Error codes
+/// Typed as [`RoleError`](crate::util::error_codes::RoleError).
 /// - 1: The group is invalid or does not exist.
 /// - 2: The roleset is invalid or does not exist.
 pub async fn users_on_role(
     jar: &RequestJar,
-    group_id: i64,
-    role_id: i64,
+    group_id: GroupId,
+    role_id: RoleId,
     limit: PageLimit,
     sort_order: Option<SortOrder>,
 ) -> Result<Vec<MinimalGroupUser>, Box<Error>> {
-    let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/roles/{}/users?sortOrder={}",
-        group_id,
-        role_id,
-        sort_order.unwrap_or(SortOrder::Asc).get_sort_order_string()
-    );
-    //let response = jar.get_json::<GroupRoleResponse>(&url).await?;
-    let response = get_page(jar, url.as_str(), limit, None).await?;
+    let url = jar
+        .url(
+            Domain::Groups,
+            &format!("/v1/groups/{}/roles/{}/users", group_id, role_id),
+        )
+        .query(
+            "sortOrder",
+            sort_order.unwrap_or(SortOrder::Asc).get_sort_order_string(),
+        );
+    let response = get_page(jar, url, limit, None).await?;
     Ok(response)
 }
 
 /// Gets a user's role in a group.
 ///
 /// # Error codes
+/// Typed as [`RoleError`](crate::util::error_codes::RoleError).
 /// - 1: The group is invalid or does not exist.
 /// - 3: The user is invalid or does not exist.
 ///
-/// - 200: The user is not in the group.
+/// - UserNotInGroup: The user is not in the group.
 pub async fn user_role(
     jar: &RequestJar,
-    group_id: i64,
-    user_id: i64,
+    group_id: GroupId,
+    user_id: UserId,
 ) -> Result<GroupRole, Box<Error>> {
     let roles = user_memberships(jar, user_id).await?;
 
@@ -83,14 +280,10 @@ pub async fn user_role(
         .filter(|group| group.group.id == group_id)
         .collect::<Vec<_>>();
 
-    if group_roles.len() == 0 {
-        return Err(Box::new(Error::RobloxError(RobloxError {
-            code: 200,
-            message: "The user is not in the group.".to_string(),
-            user_facing_message: Some("The user is not in the group.".to_string()),
-        })));
+    if group_roles.is_empty() {
+        Err(Box::new(Error::UserNotInGroup { group_id, user_id }))
     } else {
-        return Ok(group_roles[0].role.clone());
+        Ok(group_roles[0].role.clone())
     }
 }
 
@@ -101,19 +294,307 @@ pub async fn user_role(
 /// - 2: Too many ids in request.
 pub async fn roles_by_id(
     jar: &RequestJar,
-    role_ids: Vec<i64>,
+    role_ids: Vec<RoleId>,
 ) -> Result<Vec<GroupRole>, Box<Error>> {
-    let string_ids = role_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
-    let url = format!(
-        "https://groups.roblox.com/v1/roles?ids={}",
-        string_ids.join(",")
-    );
+    let url = jar
+        .url(Domain::Groups, "/v1/roles")
+        .query_list("ids", &role_ids);
 
     Ok(jar
-        .get_json::<DataWrapper<Vec<GroupRole>>>(&url)
+        .get_json::<DataWrapper<Vec<GroupRole>>>(&url.build())
         .await?
         .data)
 }
 
-// TODO: Add updating roles
+/// Gets a role of a group by its id. The roles are always requested, so they're up to date.
+///
+/// # Error codes
+/// Typed as [`RoleError`](crate::util::error_codes::RoleError).
+/// - 1: The group is invalid or does not exist.
+///
+/// - RoleNotFound: The group has no role with the id.
+pub async fn role_by_id(
+    jar: &RequestJar,
+    group_id: GroupId,
+    role_id: RoleId,
+) -> Result<GroupRole, Box<Error>> {
+    roles(jar, group_id)
+        .await?
+        .into_iter()
+        .find(|role| role.id == role_id)
+        .ok_or_else(|| {
+            Box::new(Error::RoleNotFound {
+                group_id,
+                role: role_id.to_string(),
+            })
+        })
+}
+
+/// The changes to make to a role, fields that are `None` keep their current value
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoleUpdate {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub rank: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRoleRequest {
+    pub name: String,
+    pub description: String,
+    pub rank: i64,
+}
+
+impl UpdateRoleRequest {
+    /// Applies an update to a role's current values
+    pub fn new(role: &GroupRole, update: RoleUpdate) -> UpdateRoleRequest {
+        UpdateRoleRequest {
+            name: update.name.unwrap_or_else(|| role.name.clone()),
+            description: update
+                .description
+                .or_else(|| role.description.clone())
+                .unwrap_or_default(),
+            rank: update.rank.unwrap_or(role.rank),
+        }
+    }
+
+    /// Checks the request against the limits Roblox puts on roles. The Guest and Owner ranks
+    /// are outside of the range as well, they can't be given to or taken from another role.
+    pub fn validate(&self, config: &GroupRoleConfigurationMetadata) -> Result<(), RoleUpdateError> {
+        if self.name.trim().is_empty() {
+            return Err(RoleUpdateError::EmptyName);
+        }
+        if self.name.chars().count() as i64 > config.name_max_length {
+            return Err(RoleUpdateError::NameTooLong(config.name_max_length));
+        }
+        if self.description.chars().count() as i64 > config.description_max_length {
+            return Err(RoleUpdateError::DescriptionTooLong(
+                config.description_max_length,
+            ));
+        }
+
+        let min_rank = config.min_rank.max(GUEST_RANK + 1);
+        let max_rank = config.max_rank.min(OWNER_RANK - 1);
+        if !(min_rank..=max_rank).contains(&self.rank) {
+            return Err(RoleUpdateError::RankOutOfRange(self.rank));
+        }
+
+        Ok(())
+    }
+}
+
+/// Changes a role's name, description or rank.
+///
+/// The update is checked against the limits from [`config_metadata`] before it's sent, and
+/// the Guest and Owner roles can't be changed. A [`RoleCache`] holding the group's roles
+/// should be [invalidated](RoleCache::invalidate) afterwards.
+///
+/// # Error codes
+/// Typed as [`RoleError`](crate::util::error_codes::RoleError).
+/// - 1: The group is invalid or does not exist.
+/// - 2: The roleset is invalid or does not exist.
+///
+/// - RoleNotFound: The group has no role with the id.
+/// - RoleNotAssignable: The role is the Guest or Owner role.
+/// - InvalidRoleUpdate: The update is outside of Roblox's limits, it wasn't sent.
+pub async fn update_role(
+    jar: &RequestJar,
+    group_id: GroupId,
+    role_id: RoleId,
+    update: RoleUpdate,
+) -> Result<GroupRole, Box<Error>> {
+    let role = role_by_id(jar, group_id, role_id).await?;
+    if role.rank == GUEST_RANK || role.rank == OWNER_RANK {
+        return Err(Box::new(Error::RoleNotAssignable { group_id, role_id }));
+    }
+
+    let request = UpdateRoleRequest::new(&role, update);
+    let config = config_metadata(jar).await?.role_configuration;
+    request
+        .validate(&config)
+        .map_err(|error| Box::new(Error::InvalidRoleUpdate(error)))?;
+
+    let url = format!(
+        "{}/v1/groups/{}/rolesets/{}",
+        jar.base_url(Domain::Groups),
+        group_id,
+        role_id
+    );
+    jar.patch_json::<GroupRole, _>(&url, request).await
+}
+
 // Creating and deleting roles is dangerous bc it costs robux and will NOT be added
+
+#[cfg(test)]
+mod tests {
+    use super::{role_by_id, role_by_rank, RoleCache, RoleUpdate};
+    use crate::{
+        mock::{
+            testing::{mock_jar, ranked_group_server},
+            MockResponse,
+        },
+        util::{
+            hosts::Domain,
+            ids::{GroupId, RoleId, UserId},
+            Error, RoleUpdateError,
+        },
+        Roblox,
+    };
+
+    #[tokio::test]
+    async fn user_not_in_group() {
+        let (server, jar) = mock_jar().await;
+        server.mock(
+            Domain::Groups,
+            "GET",
+            "/v2/users/1/groups/roles",
+            MockResponse::json(r#"{"data":[]}"#),
+        );
+
+        let error = super::user_role(&jar, GroupId(7370273), UserId(1))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            *error,
+            Error::UserNotInGroup {
+                group_id: GroupId(7370273),
+                user_id: UserId(1)
+            }
+        ));
+        assert_eq!(error.to_string(), "User 1 is not in group 7370273");
+    }
+
+    #[tokio::test]
+    async fn role_cache_expires() {
+        let server = ranked_group_server().await;
+        let jar = server.jar().await;
+        let role_requests = || {
+            server
+                .requests()
+                .iter()
+                .filter(|request| request.path == "/groups/v1/groups/1/roles")
+                .count()
+        };
+
+        let cache = RoleCache::disabled();
+        for _ in 0..2 {
+            role_by_rank(&jar, &cache, GroupId(1), 1).await.unwrap();
+        }
+        assert_eq!(role_requests(), 2);
+
+        let cache = RoleCache::new(std::time::Duration::from_millis(100));
+        role_by_rank(&jar, &cache, GroupId(1), 1).await.unwrap();
+        role_by_rank(&jar, &cache, GroupId(1), 200).await.unwrap();
+        assert_eq!(role_requests(), 3);
+
+        tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+        role_by_rank(&jar, &cache, GroupId(1), 1).await.unwrap();
+        assert_eq!(role_requests(), 4);
+
+        cache.invalidate(GroupId(1));
+        assert!(cache.get(GroupId(1)).is_none());
+    }
+
+    #[tokio::test]
+    async fn update_role() {
+        let server = ranked_group_server().await;
+        server.mock(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/configuration/metadata",
+            MockResponse::json(
+                &serde_json::json!({
+                    "groupConfiguration": { "nameMaxLength": 50, "descriptionMaxLength": 1000, "iconMaxFileSizeMb": 20, "cost": 100, "isUsingTwoStepWebviewComponent": true },
+                    "recurringPayoutsConfiguration": { "maxPayoutPartners": 10 },
+                    "roleConfiguration": { "nameMaxLength": 10, "descriptionMaxLength": 1000, "limit": 40, "cost": 25, "minRank": 0, "maxRank": 255 },
+                    "groupNameChangeConfiguration": { "cost": 100, "cooldownInDays": 90, "ownershipCooldownInDays": 90 },
+                    "isPremiumPayoutsEnabled": true,
+                    "isDefaultEmblemPolicyEnabled": true,
+                })
+                .to_string(),
+            ),
+        );
+        server.mock(
+            Domain::Groups,
+            "PATCH",
+            "/v1/groups/1/rolesets/12",
+            MockResponse::json(
+                r#"{"id":12,"name":"Moderator","description":"","rank":60,"memberCount":3}"#,
+            ),
+        );
+        let client = Roblox::from_jar(server.jar().await);
+        let jar = client.jar();
+        let group = client.group(GroupId(1));
+
+        let role = role_by_id(jar, GroupId(1), RoleId(12)).await.unwrap();
+        assert_eq!(role.name, "Trusted");
+        group.role_by_rank(50).await.unwrap();
+        assert!(client.role_cache().get(GroupId(1)).is_some());
+
+        let update = RoleUpdate {
+            name: Some("Moderator".to_string()),
+            rank: Some(60),
+            ..Default::default()
+        };
+        let role = group.update_role(RoleId(12), update).await.unwrap();
+        assert_eq!(role.rank, 60);
+        // The roles changed, so the client doesn't keep them anymore
+        assert!(client.role_cache().get(GroupId(1)).is_none());
+
+        let patch = server
+            .requests()
+            .into_iter()
+            .find(|request| request.method == "PATCH")
+            .unwrap();
+        assert_eq!(
+            patch.body,
+            r#"{"name":"Moderator","description":"","rank":60}"#
+        );
+
+        for (update, expected) in [
+            (
+                RoleUpdate {
+                    name: Some("Senior Moderator".to_string()),
+                    ..Default::default()
+                },
+                RoleUpdateError::NameTooLong(10),
+            ),
+            (
+                RoleUpdate {
+                    name: Some("  ".to_string()),
+                    ..Default::default()
+                },
+                RoleUpdateError::EmptyName,
+            ),
+            (
+                RoleUpdate {
+                    rank: Some(255),
+                    ..Default::default()
+                },
+                RoleUpdateError::RankOutOfRange(255),
+            ),
+        ] {
+            let error = super::update_role(jar, GroupId(1), RoleId(12), update)
+                .await
+                .unwrap_err();
+            assert!(matches!(*error, Error::InvalidRoleUpdate(ref error) if *error == expected));
+        }
+
+        let error = super::update_role(jar, GroupId(1), RoleId(14), RoleUpdate::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, Error::RoleNotAssignable { .. }));
+
+        let error = role_by_id(jar, GroupId(1), RoleId(99)).await.unwrap_err();
+        assert!(matches!(*error, Error::RoleNotFound { .. }));
+
+        // Only the valid update was sent
+        let patches = server
+            .requests()
+            .iter()
+            .filter(|request| request.method == "PATCH")
+            .count();
+        assert_eq!(patches, 1);
+    }
+}
@@ -1,26 +1,29 @@
 use rspc::Type;
 use serde::{Deserialize, Serialize};
 
-use crate::util::{jar::RequestJar, paging::PageLimit, responses::DataWrapper, Error};
+use crate::util::{
+    hosts::Domain, ids::GroupId, jar::RequestJar, paging::PageLimit, responses::DataWrapper,
+    timestamp::Timestamp, Error,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchGroup {
-    pub id: i64,
+    pub id: GroupId,
     pub name: String,
     pub description: String,
     pub member_count: i64,
     pub previous_name: Option<String>,
     pub public_entry_allowed: bool,
-    pub created: String,
-    pub updated: String,
+    pub created: Timestamp,
+    pub updated: Timestamp,
     pub has_verified_badge: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct MinimalSearchGroup {
-    pub id: i64,
+    pub id: GroupId,
     pub name: String,
     pub member_count: i64,
     pub has_verified_badge: bool,
@@ -46,6 +49,7 @@ pub struct GroupSearchResponse {
 /// Search for a group with a keyword
 ///
 /// # Error codes
+/// Typed as [`SearchError`](crate::util::error_codes::SearchError).
 /// - 2: Search term not appropriate for Roblox.
 /// - 3: Search term was left empty.
 /// - 4: Search terms can be 2 to 50 characters long.
@@ -54,34 +58,24 @@ pub async fn search(
     keyword: String,
     props: Option<GroupSearchProps>,
 ) -> Result<GroupSearchResponse, Box<Error>> {
-    let mut url = format!(
-        "https://groups.roblox.com/v1/groups/search?keyword={}",
-        keyword
-    );
+    let mut url = jar
+        .url(Domain::Groups, "/v1/groups/search")
+        .query("keyword", keyword);
 
-    match props {
-        Some(props) => {
-            if let Some(prioritize_exact_match) = props.prioritize_exact_match {
-                url = format!("{}&prioritizeExactMatch={}", url, prioritize_exact_match);
-            }
-
-            if let Some(limit) = props.limit {
-                url = format!("{}&limit={}", url, limit.get_limit());
-            }
-
-            if let Some(cursor) = props.cursor {
-                url = format!("{}&cursor={}", url, cursor);
-            }
-        }
-        None => {}
+    if let Some(props) = props {
+        url = url
+            .query_opt("prioritizeExactMatch", props.prioritize_exact_match)
+            .query_opt("limit", props.limit.map(|limit| limit.get_limit()))
+            .query_opt("cursor", props.cursor);
     }
 
-    Ok(jar.get_json::<GroupSearchResponse>(&url).await?)
+    jar.get_json::<GroupSearchResponse>(&url.build()).await
 }
 
 /// Search for a group with a keyword, prioritizing exact matches.
 ///
 /// # Error codes
+/// Typed as [`SearchError`](crate::util::error_codes::SearchError).
 /// - 2: Search term not appropriate for Roblox.
 /// - 3: Search term was left empty.
 /// - 4: Search terms can be 2 to 50 characters long.
@@ -89,13 +83,12 @@ pub async fn exact_search(
     jar: &RequestJar,
     group_name: String,
 ) -> Result<Vec<MinimalSearchGroup>, Box<Error>> {
-    let url = format!(
-        "https://groups.roblox.com/v1/groups/search/lookup?groupName={}",
-        group_name
-    );
+    let url = jar
+        .url(Domain::Groups, "/v1/groups/search/lookup")
+        .query("groupName", group_name);
 
     Ok(jar
-        .get_json::<DataWrapper<Vec<MinimalSearchGroup>>>(&url)
+        .get_json::<DataWrapper<Vec<MinimalSearchGroup>>>(&url.build())
         .await?
         .data)
 }
@@ -110,9 +103,10 @@ pub struct GroupSearchMetadata {
 /// Get group search metadata
 ///
 /// # Error codes
+/// Typed as [`SearchError`](crate::util::error_codes::SearchError).
 /// - 5: No Localized Version of group search category exists
 pub async fn search_metadata(jar: &RequestJar) -> Result<GroupSearchMetadata, Box<Error>> {
-    let url = "https://groups.roblox.com/v1/groups/search/metadata";
+    let url = format!("{}/v1/groups/search/metadata", jar.base_url(Domain::Groups));
 
-    Ok(jar.get_json::<GroupSearchMetadata>(&url).await?)
+    jar.get_json::<GroupSearchMetadata>(&url).await
 }
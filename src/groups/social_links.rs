@@ -2,7 +2,13 @@ use rspc::Type;
 use serde::{Deserialize, Serialize};
 use strum_macros::Display;
 
-use crate::util::{jar::RequestJar, responses::DataWrapper, Error};
+use crate::util::{
+    hosts::Domain,
+    ids::{GroupId, SocialLinkId},
+    jar::RequestJar,
+    responses::{DataWrapper, EmptyResponse},
+    Error,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Display, Type)]
 pub enum SocialLinkType {
@@ -31,7 +37,7 @@ pub enum SocialLinkType {
 #[serde(rename_all = "camelCase")]
 pub struct SocialLink {
     /// Only present when retrieving social links. Do not provide when adding a social link.
-    pub id: Option<i64>,
+    pub id: Option<SocialLinkId>,
     #[serde(rename = "type")] // Rust doesn't like "type" as a field name
     pub link_type: SocialLinkType,
     pub url: String,
@@ -41,12 +47,17 @@ pub struct SocialLink {
 /// Gets a group's social links.
 ///
 /// # Error codes
+/// Typed as [`SocialLinkError`](crate::util::error_codes::SocialLinkError).
 /// - 1: The group is invalid or does not exist.
 /// - 11: Social links cannot be processed as this time.
 /// - 13: Only users who are over thirteen years of age may view social links.
-pub async fn social_links(jar: &RequestJar, group_id: i64) -> Result<Vec<SocialLink>, Box<Error>> {
+pub async fn social_links(
+    jar: &RequestJar,
+    group_id: GroupId,
+) -> Result<Vec<SocialLink>, Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/social-links",
+        "{}/v1/groups/{}/social-links",
+        jar.base_url(Domain::Groups),
         group_id
     );
     let response = jar.get_json::<DataWrapper<Vec<SocialLink>>>(&url).await?;
@@ -56,6 +67,7 @@ pub async fn social_links(jar: &RequestJar, group_id: i64) -> Result<Vec<SocialL
 /// Adds a social link to a group.
 ///
 /// # Error codes
+/// Typed as [`SocialLinkError`](crate::util::error_codes::SocialLinkError).
 /// - 1: The group is invalid or does not exist.
 /// - 2: You do not have permission to configure this social link.
 /// - 3: The social link title is too long.
@@ -70,21 +82,24 @@ pub async fn social_links(jar: &RequestJar, group_id: i64) -> Result<Vec<SocialL
 /// - 16: A social link with this type already exists on this group.
 pub async fn add_social_link(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
     social_link: SocialLink,
 ) -> Result<(), Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/social-links",
+        "{}/v1/groups/{}/social-links",
+        jar.base_url(Domain::Groups),
         group_id
     );
 
-    jar.post_json(&url, &social_link).await?;
+    jar.post_json::<EmptyResponse, _>(&url, &social_link)
+        .await?;
     Ok(())
 }
 
 /// Deletes a social link from a group.
 ///
 /// # Error codes
+/// Typed as [`SocialLinkError`](crate::util::error_codes::SocialLinkError).
 /// - 1: Group is invalid or does not exist.
 /// - 2: You do not have permission to configure this social link.
 /// - 10: The social link is not for a group.
@@ -93,21 +108,24 @@ pub async fn add_social_link(
 /// - 15: The social link id doesn't match the group id.
 pub async fn delete_social_link(
     jar: &RequestJar,
-    group_id: i64,
-    social_link_id: i64,
+    group_id: GroupId,
+    social_link_id: SocialLinkId,
 ) -> Result<(), Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/social-links/{}",
-        group_id, social_link_id
+        "{}/v1/groups/{}/social-links/{}",
+        jar.base_url(Domain::Groups),
+        group_id,
+        social_link_id
     );
 
-    jar.delete(&url, "".to_string()).await?;
+    jar.delete_empty(&url).await?;
     Ok(())
 }
 
 /// Updates a group's social link.
 ///
 /// # Error codes
+/// Typed as [`SocialLinkError`](crate::util::error_codes::SocialLinkError).
 /// - 1: Group is invalid or does not exist.
 /// - 2: You do not have permission to configure this social link.
 /// - 3: The social link title is too long.
@@ -123,15 +141,17 @@ pub async fn delete_social_link(
 /// - 16: A social link with this type already exists on this group.
 pub async fn update_social_link(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
     social_link: SocialLink,
 ) -> Result<(), Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/social-links/{}",
+        "{}/v1/groups/{}/social-links/{}",
+        jar.base_url(Domain::Groups),
         group_id,
         social_link.id.unwrap()
     );
 
-    jar.patch_json(&url, &social_link).await?;
+    jar.patch_json::<EmptyResponse, _>(&url, &social_link)
+        .await?;
     Ok(())
 }
@@ -4,8 +4,13 @@ use serde::{Deserialize, Serialize};
 use crate::{
     users::MinimalGroupUser,
     util::{
+        hosts::Domain,
+        ids::{GroupId, UserId, WallPostId},
         jar::RequestJar,
-        paging::{get_page, PageLimit, SortOrder},
+        paging::{get_page, PageLimit, Paginator, SortOrder},
+        responses::EmptyResponse,
+        timestamp::Timestamp,
+        url::UrlBuilder,
         Error,
     },
 };
@@ -13,33 +18,51 @@ use crate::{
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct WallPost {
-    pub id: i64,
+    pub id: WallPostId,
     pub poster: MinimalGroupUser,
     pub body: String,
-    pub created: String,
-    pub updated: String,
+    pub created: Timestamp,
+    pub updated: Timestamp,
 }
 
 /// Gets a group's wall posts.
 ///
 /// # Error codes
+/// Typed as [`WallError`](crate::util::error_codes::WallError).
 /// - 1: The group is invalid or does not exist.
 /// - 2: You do not have permission to access this group wall.
 pub async fn wall(
     jar: &RequestJar,
-    group_id: i64,
+    group_id: GroupId,
     limit: PageLimit,
     sort_order: Option<SortOrder>,
+    cursor: Option<String>,
 ) -> Result<Vec<WallPost>, Box<Error>> {
-    let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/wall/posts?sortOrder={}",
-        group_id,
-        sort_order.unwrap_or(SortOrder::Asc).get_sort_order_string()
-    );
-    let response = get_page::<WallPost>(jar, &url, limit, None).await?; // TODO: Add cursor support
+    let url = wall_url(jar, group_id, sort_order);
+    let response = get_page::<WallPost>(jar, url, limit, cursor).await?;
     Ok(response)
 }
 
+/// Goes through all of a group's wall posts, see [`wall`] for the error codes
+pub fn wall_stream(
+    jar: &RequestJar,
+    group_id: GroupId,
+    sort_order: Option<SortOrder>,
+) -> Paginator<'_, WallPost> {
+    Paginator::new(jar, wall_url(jar, group_id, sort_order))
+}
+
+fn wall_url(jar: &RequestJar, group_id: GroupId, sort_order: Option<SortOrder>) -> UrlBuilder {
+    jar.url(
+        Domain::Groups,
+        &format!("/v1/groups/{}/wall/posts", group_id),
+    )
+    .query(
+        "sortOrder",
+        sort_order.unwrap_or(SortOrder::Asc).get_sort_order_string(),
+    )
+}
+
 // Probs shouldnt implement posting to the group wall
 
 // TODO: Figure out what /v1/groups/{groupId}/wall/subscribe does
@@ -47,37 +70,45 @@ pub async fn wall(
 /// Deletes a group's wall post.
 ///
 /// # Error codes
+/// Typed as [`WallError`](crate::util::error_codes::WallError).
 /// - 1: The group is invalid or does not exist.
 /// - 2: You do not have permission to access this group wall
 /// - 3: The group wall post id is invalid or does not exist.
 pub async fn delete_wall_post(
     jar: &RequestJar,
-    group_id: i64,
-    post_id: i64,
+    group_id: GroupId,
+    post_id: WallPostId,
 ) -> Result<(), Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/wall/posts/{}",
-        group_id, post_id
+        "{}/v1/groups/{}/wall/posts/{}",
+        jar.base_url(Domain::Groups),
+        group_id,
+        post_id
     );
-    jar.delete_json(&url, "".to_string()).await?;
+    jar.delete_json::<EmptyResponse, _>(&url, "".to_string())
+        .await?;
     Ok(())
 }
 
 /// Delete all posts by a user on a group's wall.
 ///
 /// # Error codes
+/// Typed as [`WallError`](crate::util::error_codes::WallError).
 /// - 1: The group is invalid or does not exist.
 /// - 2: You do not have permission to access this group wall.
 /// - 6: The user specified is invalid or does not exist.
 pub async fn delete_wall_posts_by_user(
     jar: &RequestJar,
-    group_id: i64,
-    user_id: i64,
+    group_id: GroupId,
+    user_id: UserId,
 ) -> Result<(), Box<Error>> {
     let url = format!(
-        "https://groups.roblox.com/v1/groups/{}/wall/users/{}/posts",
-        group_id, user_id
+        "{}/v1/groups/{}/wall/users/{}/posts",
+        jar.base_url(Domain::Groups),
+        group_id,
+        user_id
     );
-    jar.delete_json(&url, "".to_string()).await?;
+    jar.delete_json::<EmptyResponse, _>(&url, "".to_string())
+        .await?;
     Ok(())
 }
@@ -1,57 +1,40 @@
 pub mod groups;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod roblox;
 pub mod thumbnails;
 pub mod users;
 pub mod util;
 
+pub use roblox::Roblox;
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
-
-    use crate::{groups::GroupSearchProps, util::paging::PageLimit};
+    use crate::{
+        groups::GroupSearchProps,
+        mock::{testing::mock_jar, MockResponse},
+        util::{
+            hosts::Domain,
+            ids::{AssetId, BadgeId, GroupId, RoleId, UserId},
+            paging::PageLimit,
+        },
+    };
 
     use super::*;
 
-    async fn authenticated_jar() -> util::jar::RequestJar {
-        // Read credentials from file
-        let contents = fs::read_to_string("roblosecurity.txt").unwrap();
-        let roblosecurity = contents.trim().to_string();
-        let mut jar = util::jar::RequestJar::new().await;
-        jar.set_roblosecurity(roblosecurity).await.unwrap();
-
-        // If the file proxy.txt exists, use it as a proxy
-        if fs::metadata("proxy.txt").is_ok() {
-            let contents = fs::read_to_string("proxy.txt").unwrap();
-            let proxy = contents.trim().to_string();
-            jar.set_proxy(proxy);
-        }
-
-        jar
-    }
-
-    async fn unauthenticated_jar() -> util::jar::RequestJar {
-        let mut jar = util::jar::RequestJar::new().await;
-
-        // If the file proxy.txt exists, use it as a proxy
-        if fs::metadata("proxy.txt").is_ok() {
-            let contents = fs::read_to_string("proxy.txt").unwrap();
-            let proxy = contents.trim().to_string();
-            jar.set_proxy(proxy);
-        }
-
-        jar
-    }
-
     #[tokio::test]
     async fn whoami() {
-        let mut jar = unauthenticated_jar().await;
-        let user = crate::users::whoami(&mut jar).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let user = crate::users::whoami(&jar).await.unwrap();
         println!("{:#?}", user);
     }
 
     #[tokio::test]
     async fn user_by_id() {
-        let mut jar = unauthenticated_jar().await;
-        let user = crate::users::user_by_id(&mut jar, 375760054).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let user = crate::users::user_by_id(&jar, UserId(375760054))
+            .await
+            .unwrap();
         println!("{:#?}", user);
     }
 
@@ -59,71 +42,62 @@ mod tests {
 
     #[tokio::test]
     async fn validate_display_name_fail() {
-        let mut jar = unauthenticated_jar().await;
-        let display_name = crate::users::validate_display_name(&mut jar, "shit".to_string())
+        let (_server, jar) = mock_jar().await;
+        let display_name = crate::users::validate_display_name(&jar, "shit".to_string())
             .await
             .unwrap();
 
         // Panic if it's valid
-        match display_name {
-            crate::users::ValidateDisplayNameResponseEnum::Success(_) => {
-                panic!("Display name is valid when it shouldn't be")
-            }
-            _ => {}
+        if let crate::users::ValidateDisplayNameResponseEnum::Success(_) = display_name {
+            panic!("Display name is valid when it shouldn't be")
         }
     }
 
     #[tokio::test]
     async fn validate_display_name_success() {
-        let mut jar = unauthenticated_jar().await;
-        let display_name = crate::users::validate_display_name(&mut jar, "test".to_string())
+        let (_server, jar) = mock_jar().await;
+        let display_name = crate::users::validate_display_name(&jar, "test".to_string())
             .await
             .unwrap();
 
         // Panic if it's valid
-        match display_name {
-            crate::users::ValidateDisplayNameResponseEnum::Failed(_) => {
-                panic!("Display name is invalid when it shouldn't be")
-            }
-            _ => {}
+        if let crate::users::ValidateDisplayNameResponseEnum::Failed(_) = display_name {
+            panic!("Display name is invalid when it shouldn't be")
         }
     }
 
     #[tokio::test]
     async fn validate_display_name_for_user() {
-        let mut jar = unauthenticated_jar().await;
-        let user_id = crate::users::whoami(&mut jar).await.unwrap().id;
+        let (_server, jar) = mock_jar().await;
+        let user_id = crate::users::whoami(&jar).await.unwrap().id;
 
-        if user_id != 4205503041 {
+        if user_id != UserId(4205503041) {
             return; // This account gets used for CI, we only want to run this test on that account bc ratelimits
         }
 
         let display_name =
-            crate::users::validate_display_name_for_user(&mut jar, "test".to_string(), user_id)
+            crate::users::validate_display_name_for_user(&jar, "test".to_string(), user_id)
                 .await
                 .unwrap();
 
         // Panic if it's valid
-        match display_name {
-            crate::users::ValidateDisplayNameResponseEnum::Failed(_) => {
-                panic!("Display name is invalid when it shouldn't be")
-            }
-            _ => {}
+        if let crate::users::ValidateDisplayNameResponseEnum::Failed(_) = display_name {
+            panic!("Display name is invalid when it shouldn't be")
         }
     }
 
     //#[tokio::test]
     //async fn set_display_name() {
-    //    let mut jar = authenticated_jar().await;
-    //    crate::users::set_display_name(&mut jar, "notest".to_string())
+    //    let jar = authenticated_jar().await;
+    //    crate::users::set_display_name(&jar, "notest".to_string())
     //        .await
     //        .unwrap();
-    //    let whoami1 = crate::users::whoami(&mut jar).await.unwrap();
-    //    crate::users::set_display_name(&mut jar, "test".to_string())
+    //    let whoami1 = crate::users::whoami(&jar).await.unwrap();
+    //    crate::users::set_display_name(&jar, "test".to_string())
     //        .await
     //        .unwrap();
     //
-    //    let whoami2 = crate::users::whoami(&mut jar).await.unwrap();
+    //    let whoami2 = crate::users::whoami(&jar).await.unwrap();
     //
     //    assert_ne!(whoami1.display_name, whoami2.display_name);
     //    assert_eq!(whoami2.display_name, "test".to_string());
@@ -132,31 +106,31 @@ mod tests {
 
     #[tokio::test]
     async fn get_age_bracket() {
-        let mut jar = unauthenticated_jar().await;
-        let age_bracket = crate::users::age_bracket(&mut jar).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let age_bracket = crate::users::age_bracket(&jar).await.unwrap();
         assert_eq!(age_bracket.age_bracket, 0);
     }
 
     #[tokio::test]
     async fn get_country_code() {
-        let mut jar = unauthenticated_jar().await;
-        let country_code = crate::users::country_code(&mut jar).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let country_code = crate::users::country_code(&jar).await.unwrap();
         assert_eq!(country_code.country_code, "NL");
     }
 
     #[tokio::test]
     async fn get_roles() {
-        let mut jar = unauthenticated_jar().await;
-        let roles = crate::users::roles(&mut jar).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let roles = crate::users::roles(&jar).await.unwrap();
         let empty_vec: Vec<String> = Vec::new();
         assert_eq!(roles.roles, empty_vec);
     }
 
     #[tokio::test]
     async fn bulk_users_by_username() {
-        let mut jar = unauthenticated_jar().await;
+        let (_server, jar) = mock_jar().await;
         let users = crate::users::bulk_users_by_username(
-            &mut jar,
+            &jar,
             vec!["piano1029".to_string(), "ClannyBot".to_string()],
         )
         .await
@@ -164,15 +138,16 @@ mod tests {
 
         assert_eq!(users.len(), 2);
         assert_eq!(users[0].name, "piano1029".to_string());
-        assert_eq!(users[0].id, 375760054);
+        assert_eq!(users[0].id, UserId(375760054));
     }
 
     #[tokio::test]
     async fn bulk_users_by_id() {
-        let mut jar = unauthenticated_jar().await;
-        let users = crate::users::bulk_users_by_id(&mut jar, vec![375760054, 1444131924])
-            .await
-            .unwrap();
+        let (_server, jar) = mock_jar().await;
+        let users =
+            crate::users::bulk_users_by_id(&jar, vec![UserId(375760054), UserId(1444131924)])
+                .await
+                .unwrap();
 
         println!("{:#?}", users);
 
@@ -182,8 +157,8 @@ mod tests {
 
     #[tokio::test]
     async fn username_history() {
-        let mut jar = unauthenticated_jar().await;
-        let users = crate::users::username_history(&mut jar, 375760054)
+        let (_server, jar) = mock_jar().await;
+        let users = crate::users::username_history(&jar, UserId(375760054))
             .await
             .unwrap();
 
@@ -194,9 +169,9 @@ mod tests {
 
     #[tokio::test]
     async fn username_search() {
-        let mut jar = unauthenticated_jar().await;
+        let (_server, jar) = mock_jar().await;
         let users = crate::users::username_search(
-            &mut jar,
+            &jar,
             "miemper".to_string(),
             util::paging::PageLimit::Limit10,
         )
@@ -208,7 +183,7 @@ mod tests {
                 assert_eq!(users[0].name, "miemper".to_string());
             }
             Err(e) => match *e {
-                crate::util::Error::RateLimited => {
+                crate::util::Error::RateLimited(_) => {
                     // This is fine, it just means we're being rate limited
                 }
                 _ => {
@@ -221,12 +196,14 @@ mod tests {
 
     #[tokio::test]
     async fn get_group() {
-        let mut jar = unauthenticated_jar().await;
-        let group = crate::groups::group_by_id(&mut jar, 7370273).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let group = crate::groups::group_by_id(&jar, GroupId(7370273))
+            .await
+            .unwrap();
 
         println!("{:#?}", group);
 
-        assert_eq!(group.id, 7370273);
+        assert_eq!(group.id, GroupId(7370273));
         assert_eq!(group.name, "Clanny Systems".to_string());
         assert_eq!(group.owner.username, "ClannyBot".to_string())
     }
@@ -235,12 +212,13 @@ mod tests {
 
     #[tokio::test]
     async fn get_group_name_history() {
-        let mut jar = unauthenticated_jar().await;
+        let (_server, jar) = mock_jar().await;
         let group_name_history = crate::groups::name_history(
-            &mut jar,
-            7370273,
+            &jar,
+            GroupId(7370273),
             util::paging::PageLimit::All,
             Some(util::paging::SortOrder::Asc),
+            None,
         )
         .await
         .unwrap();
@@ -253,8 +231,8 @@ mod tests {
     // TODO: Re-enable this test when we got a test group
     //#[tokio::test]
     //async fn get_group_settings() {
-    //    let mut jar = unauthenticated_jar().await;
-    //    let group_settings = crate::groups::settings(&mut jar, 7370273)
+    //    let (_server, jar) = mock_jar().await;
+    //    let group_settings = crate::groups::settings(&jar, 7370273)
     //        .await
     //        .unwrap();
     //
@@ -267,8 +245,8 @@ mod tests {
 
     #[tokio::test]
     async fn group_configuration_metadata() {
-        let mut jar = unauthenticated_jar().await;
-        let group_configuration_metadata = crate::groups::config_metadata(&mut jar).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let group_configuration_metadata = crate::groups::config_metadata(&jar).await.unwrap();
 
         println!("{:#?}", group_configuration_metadata);
 
@@ -277,26 +255,26 @@ mod tests {
 
     #[tokio::test]
     async fn group_metadata() {
-        let mut jar = unauthenticated_jar().await;
-        let group_metadata = crate::groups::metadata(&mut jar).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let group_metadata = crate::groups::metadata(&jar).await.unwrap();
 
         println!("{:#?}", group_metadata);
 
-        assert_eq!(group_metadata.show_previous_group_names, true);
+        assert!(group_metadata.show_previous_group_names);
     }
 
     #[tokio::test]
     async fn group_compliance() {
-        let mut jar = unauthenticated_jar().await;
-        let group_compliance = crate::groups::compliance(&mut jar, vec![7370273])
+        let (_server, jar) = mock_jar().await;
+        let group_compliance = crate::groups::compliance(&jar, vec![GroupId(7370273)])
             .await
             .unwrap();
 
         println!("{:#?}", group_compliance);
 
         assert_eq!(group_compliance.groups.len(), 1);
-        assert_eq!(group_compliance.groups[0].can_view_group, true);
-        assert_eq!(group_compliance.groups[0].group_id, 7370273);
+        assert!(group_compliance.groups[0].can_view_group);
+        assert_eq!(group_compliance.groups[0].group_id, GroupId(7370273));
     }
 
     // TODO: Create test for updating group description, but that requires a group (which requires robux)
@@ -306,20 +284,22 @@ mod tests {
 
     #[tokio::test]
     async fn group_membership() {
-        let mut jar = unauthenticated_jar().await;
-        let group_membership = crate::groups::membership(&mut jar, 7370273).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let group_membership = crate::groups::membership(&jar, GroupId(7370273))
+            .await
+            .unwrap();
 
         println!("{:#?}", group_membership);
 
-        assert_eq!(group_membership.group_id, 7370273);
-        assert_eq!(group_membership.are_group_funds_visible, false);
-        assert_eq!(group_membership.can_configure, false);
+        assert_eq!(group_membership.group_id, GroupId(7370273));
+        assert!(!group_membership.are_group_funds_visible);
+        assert!(!group_membership.can_configure);
     }
 
     #[tokio::test]
     async fn group_roles() {
-        let mut jar = unauthenticated_jar().await;
-        let group_roles = crate::groups::roles(&mut jar, 7370273).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let group_roles = crate::groups::roles(&jar, GroupId(7370273)).await.unwrap();
 
         println!("{:#?}", group_roles);
 
@@ -333,14 +313,14 @@ mod tests {
 
     #[tokio::test]
     async fn group_role_members() {
-        let mut jar = unauthenticated_jar().await;
-        let group_roles = crate::groups::roles(&mut jar, 7370273).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let group_roles = crate::groups::roles(&jar, GroupId(7370273)).await.unwrap();
 
         let clanny_role_id = group_roles[group_roles.len() - 1].id;
 
         let group_role_members = crate::groups::users_on_role(
-            &mut jar,
-            7370273,
+            &jar,
+            GroupId(7370273),
             clanny_role_id,
             util::paging::PageLimit::Limit10,
             None,
@@ -356,12 +336,13 @@ mod tests {
 
     #[tokio::test]
     async fn group_members() {
-        let mut jar = unauthenticated_jar().await;
+        let (_server, jar) = mock_jar().await;
         let group_members = crate::groups::members(
-            &mut jar,
-            7370273,
+            &jar,
+            GroupId(7370273),
             util::paging::PageLimit::Limit10,
             Some(util::paging::SortOrder::Asc),
+            None,
         )
         .await
         .unwrap();
@@ -374,8 +355,8 @@ mod tests {
 
     #[tokio::test]
     async fn pending_requests() {
-        let mut jar = unauthenticated_jar().await;
-        let pending_requests = crate::groups::pending_requests(&mut jar).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let pending_requests = crate::groups::pending_requests(&jar).await.unwrap();
 
         println!("{:#?}", pending_requests);
 
@@ -384,9 +365,9 @@ mod tests {
 
     #[tokio::test]
     async fn friend_groups() {
-        let mut jar = unauthenticated_jar().await;
+        let (_server, jar) = mock_jar().await;
 
-        let friend_groups = crate::groups::friend_groups(&mut jar).await.unwrap();
+        let friend_groups = crate::groups::friend_groups(&jar).await.unwrap();
 
         println!("{:#?}", friend_groups);
 
@@ -395,20 +376,20 @@ mod tests {
 
     #[tokio::test]
     async fn user_group_memberships() {
-        let mut jar = unauthenticated_jar().await;
-        let user_group_memberships = crate::groups::user_memberships(&mut jar, 375760054)
+        let (_server, jar) = mock_jar().await;
+        let user_group_memberships = crate::groups::user_memberships(&jar, UserId(375760054))
             .await
             .unwrap();
 
         // Filter where group_id is 7370273
         let clanny_group_membership = user_group_memberships
             .into_iter()
-            .find(|group_membership| group_membership.group.id == 7370273)
+            .find(|group_membership| group_membership.group.id == GroupId(7370273))
             .unwrap();
 
         println!("{:#?}", clanny_group_membership);
 
-        assert_eq!(clanny_group_membership.group.id, 7370273);
+        assert_eq!(clanny_group_membership.group.id, GroupId(7370273));
         assert_eq!(clanny_group_membership.role.rank, 254);
         // This is no longer provided with v2 of the group membership API ):
         // v1 has been replaced with v2 in this package due to it sending malformed JSON
@@ -418,17 +399,14 @@ mod tests {
 
     // TODO: Add test for change_owner, but that requires a group (which requires robux)
     // TODO: Add test for remove_user, but that requires a group (which requires robux)
-    // TODO: Add test for promote, but that requires a group (which requires robux)
-    // TODO: Add test for demote, but that requires a group (which requires robux)
     // TODO: Add test for set_role, but that requires a group (which requires robux)
     // TODO: Add test for set_rank, but that requires a group (which requires robux)
-    // TODO: Add test for modify_rank_by_amount, but that requires a group (which requires robux)
 
     #[tokio::test]
     async fn user_role() {
-        let mut jar = unauthenticated_jar().await;
-        //let group = crate::groups::group_by_id(&mut jar, 7370273).await.unwrap();
-        let user_role = crate::groups::user_role(&mut jar, 7370273, 1444131924)
+        let (_server, jar) = mock_jar().await;
+        //let group = crate::groups::group_by_id(&jar, 7370273).await.unwrap();
+        let user_role = crate::groups::user_role(&jar, GroupId(7370273), UserId(1444131924))
             .await
             .unwrap();
 
@@ -441,9 +419,9 @@ mod tests {
     #[tokio::test]
     async fn group_relationships() {
         // TODO: Improve this test
-        let mut jar = unauthenticated_jar().await;
+        let (_server, jar) = mock_jar().await;
         let group_relationships =
-            crate::groups::relationships(&mut jar, 7370273, groups::RelationshipType::All)
+            crate::groups::relationships(&jar, GroupId(7370273), groups::RelationshipType::All)
                 .await
                 .unwrap();
 
@@ -455,9 +433,9 @@ mod tests {
     #[tokio::test]
     async fn group_enemies() {
         // TODO: Improve this test
-        let mut jar = unauthenticated_jar().await;
+        let (_server, jar) = mock_jar().await;
         let group_relationships =
-            crate::groups::relationships(&mut jar, 7370273, groups::RelationshipType::Enemy)
+            crate::groups::relationships(&jar, GroupId(7370273), groups::RelationshipType::Enemy)
                 .await
                 .unwrap();
 
@@ -469,8 +447,10 @@ mod tests {
     #[tokio::test]
     async fn group_enemies_easy() {
         // TODO: Improve this test
-        let mut jar = unauthenticated_jar().await;
-        let group_relationships = crate::groups::enemies(&mut jar, 7370273).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let group_relationships = crate::groups::enemies(&jar, GroupId(7370273))
+            .await
+            .unwrap();
 
         println!("{:#?}", group_relationships);
 
@@ -480,9 +460,9 @@ mod tests {
     #[tokio::test]
     async fn group_allies() {
         // TODO: Improve this test
-        let mut jar = unauthenticated_jar().await;
+        let (_server, jar) = mock_jar().await;
         let group_relationships =
-            crate::groups::relationships(&mut jar, 7370273, groups::RelationshipType::Ally)
+            crate::groups::relationships(&jar, GroupId(7370273), groups::RelationshipType::Ally)
                 .await
                 .unwrap();
 
@@ -494,8 +474,8 @@ mod tests {
     #[tokio::test]
     async fn group_allies_easy() {
         // TODO: Improve this test
-        let mut jar = unauthenticated_jar().await;
-        let group_relationships = crate::groups::allies(&mut jar, 7370273).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let group_relationships = crate::groups::allies(&jar, GroupId(7370273)).await.unwrap();
 
         println!("{:#?}", group_relationships);
 
@@ -504,14 +484,12 @@ mod tests {
 
     // TODO: Add tests for group relationships, but that requires a group (which requires robux)
     // TODO: Add test for role_permissions, but that requires a group (which requires robux)
-    // TODO: Add test for update_role_permissions, but that requires a group (which requires robux)
     // TODO: Add test for guest_permissions, but that requires a group (which requires robux)
-    // TODO: Add test for permissions, but that requires a group (which requires robux)
 
     #[tokio::test]
     async fn social_links() {
-        let mut jar = unauthenticated_jar().await;
-        let social_links = crate::groups::social_links(&mut jar, 7370273)
+        let (_server, jar) = mock_jar().await;
+        let social_links = crate::groups::social_links(&jar, GroupId(7370273))
             .await
             .unwrap();
 
@@ -526,16 +504,22 @@ mod tests {
 
     #[tokio::test]
     async fn read_group_wall() {
-        let mut jar = unauthenticated_jar().await;
-        let wall = crate::groups::wall(&mut jar, 7370273, util::paging::PageLimit::All, None)
-            .await
-            .unwrap();
+        let (_server, jar) = mock_jar().await;
+        let wall = crate::groups::wall(
+            &jar,
+            GroupId(7370273),
+            util::paging::PageLimit::All,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
 
         println!("{:#?}", wall);
 
         // Panic if wall does not include a post from piano1029
         wall.iter()
-            .find(|post| post.poster.user_id == 375760054)
+            .find(|post| post.poster.user_id == UserId(375760054))
             .unwrap();
 
         println!("{:#?}", wall);
@@ -547,9 +531,9 @@ mod tests {
 
     #[tokio::test]
     async fn group_search() {
-        let mut jar = unauthenticated_jar().await;
+        let (_server, jar) = mock_jar().await;
         let search_results = crate::groups::search(
-            &mut jar,
+            &jar,
             "Clanny Systems".to_string(),
             Some(GroupSearchProps {
                 prioritize_exact_match: Some(true),
@@ -563,7 +547,7 @@ mod tests {
         println!("{:#?}", search_results);
 
         assert_eq!(search_results.results[0].name, "Clanny Systems");
-        assert_eq!(search_results.results[0].id, 7370273);
+        assert_eq!(search_results.results[0].id, GroupId(7370273));
         assert_eq!(search_results.keyword, "Clanny Systems");
 
         assert_ne!(search_results.results.len(), 0);
@@ -571,28 +555,28 @@ mod tests {
 
     #[tokio::test]
     async fn group_exact_search() {
-        let mut jar = unauthenticated_jar().await;
-        let search_results = crate::groups::exact_search(&mut jar, "Clanny Systems".to_string())
+        let (_server, jar) = mock_jar().await;
+        let search_results = crate::groups::exact_search(&jar, "Clanny Systems".to_string())
             .await
             .unwrap();
 
         println!("{:#?}", search_results);
 
         assert_eq!(search_results[0].name, "Clanny Systems");
-        assert_eq!(search_results[0].id, 7370273);
+        assert_eq!(search_results[0].id, GroupId(7370273));
 
         assert_ne!(search_results.len(), 0);
     }
 
     #[tokio::test]
     async fn group_roles_by_id() {
-        let mut jar = unauthenticated_jar().await;
-        let group_roles = crate::groups::roles(&mut jar, 7370273).await.unwrap();
+        let (_server, jar) = mock_jar().await;
+        let group_roles = crate::groups::roles(&jar, GroupId(7370273)).await.unwrap();
 
         println!("{:#?}", group_roles);
 
         let test_role = &group_roles[0];
-        let role_by_id = crate::groups::roles_by_id(&mut jar, vec![test_role.id])
+        let role_by_id = crate::groups::roles_by_id(&jar, vec![test_role.id])
             .await
             .unwrap();
 
@@ -604,19 +588,143 @@ mod tests {
 
     #[tokio::test]
     async fn primary_group() {
-        let mut jar = unauthenticated_jar().await;
-        let primary_group = crate::groups::primary_group(&mut jar, 375760054)
+        let (_server, jar) = mock_jar().await;
+        let primary_group = crate::groups::primary_group(&jar, UserId(375760054))
             .await
             .unwrap();
 
         println!("{:#?}", primary_group);
 
-        assert_eq!(primary_group.group.id, 7370273);
+        assert_eq!(primary_group.group.id, GroupId(7370273));
         assert_eq!(primary_group.group.name, "Clanny Systems");
         assert_eq!(primary_group.role.name, "Developer");
     }
 
     // TODO: Add test for removing and setting primary group
 
-    // TODO: Add tests for thumbnails!
+    #[tokio::test]
+    async fn asset_thumbnails() {
+        let (_server, jar) = mock_jar().await;
+        let thumbnails =
+            crate::thumbnails::get_thumbnails(&jar, vec![AssetId(1818)], None, None, None, false)
+                .await
+                .unwrap();
+
+        println!("{:#?}", thumbnails);
+
+        assert_eq!(thumbnails.len(), 1);
+        assert_eq!(thumbnails[0].target_id, 1818);
+        assert_eq!(thumbnails[0].state, "Completed");
+    }
+
+    #[tokio::test]
+    async fn asset_3d_thumbnail() {
+        let (_server, jar) = mock_jar().await;
+        let thumbnail = crate::thumbnails::get_3d_thumbnail(&jar, AssetId(1818))
+            .await
+            .unwrap();
+
+        assert_eq!(thumbnail.target_id, 1818);
+    }
+
+    #[tokio::test]
+    async fn badge_thumbnails() {
+        let (server, jar) = mock_jar().await;
+        let thumbnails =
+            crate::thumbnails::get_badge_thumbnails(&jar, vec![BadgeId(2124533401)], false)
+                .await
+                .unwrap();
+
+        assert_eq!(thumbnails[0].target_id, 2124533401);
+        assert_eq!(
+            server.requests()[0].query_param("badgeIds"),
+            Some("2124533401")
+        );
+    }
+
+    #[tokio::test]
+    async fn set_role_request() {
+        let (server, jar) = mock_jar().await;
+        server.mock(
+            Domain::Groups,
+            "PATCH",
+            "/v1/groups/7370273/users/375760054",
+            MockResponse::json("{}"),
+        );
+
+        crate::groups::set_role(&jar, GroupId(7370273), UserId(375760054), RoleId(47932104))
+            .await
+            .unwrap();
+
+        let request = &server.requests()[0];
+        assert_eq!(request.method, "PATCH");
+        assert_eq!(request.body, r#"{"roleId":47932104}"#);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_response() {
+        let (server, mut jar) = mock_jar().await;
+        jar.set_retry_policy(crate::util::retry::RetryPolicy::none());
+        server.script(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/7370273",
+            vec![MockResponse::rate_limited()],
+        );
+
+        let error = crate::groups::group_by_id(&jar, GroupId(7370273))
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, crate::util::Error::RateLimited(_)));
+
+        // The scripted response is used up, so the fixture is served again
+        let group = crate::groups::group_by_id(&jar, GroupId(7370273))
+            .await
+            .unwrap();
+        assert_eq!(group.id, GroupId(7370273));
+    }
+
+    #[tokio::test]
+    async fn roblox_error_response() {
+        let (server, jar) = mock_jar().await;
+        server.script(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1",
+            vec![MockResponse::errors(
+                400,
+                &[(1, "Group is invalid or does not exist.")],
+            )],
+        );
+
+        let error = crate::groups::group_by_id(&jar, GroupId(1))
+            .await
+            .unwrap_err();
+        match *error {
+            crate::util::Error::RobloxError(details) => assert_eq!(details.errors[0].code, 1),
+            error => panic!("Unexpected error: {:?}", error),
+        }
+    }
+
+    #[tokio::test]
+    async fn csrf_token_captured() {
+        let (server, jar) = mock_jar().await;
+        server.script(
+            Domain::Groups,
+            "POST",
+            "/v1/groups/policies",
+            vec![MockResponse::csrf_challenge("mock-token")],
+        );
+
+        let _ = crate::groups::compliance(&jar, vec![GroupId(7370273)]).await;
+        assert_eq!(jar.get_xcsrf(), "mock-token");
+
+        crate::groups::compliance(&jar, vec![GroupId(7370273)])
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        let last = requests.last().unwrap();
+        assert_eq!(last.header("x-csrf-token"), Some("mock-token"));
+    }
 }
@@ -0,0 +1,44 @@
+use serde::Deserialize;
+
+use crate::util::hosts::Domain;
+
+use super::{MockResponse, MockServer};
+
+/// A recorded response from one of the Roblox web APIs
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    method: String,
+    /// Path relative to the domain, may contain query parameters that have to match
+    path: String,
+    #[serde(default = "default_status")]
+    status: u16,
+    body: serde_json::Value,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+const GROUPS: &str = include_str!("fixtures/groups.json");
+const USERS: &str = include_str!("fixtures/users.json");
+const THUMBNAILS: &str = include_str!("fixtures/thumbnails.json");
+
+/// Registers every recorded fixture on the server
+pub(super) fn install(server: &MockServer) {
+    for (domain, fixtures) in [
+        (Domain::Groups, GROUPS),
+        (Domain::Users, USERS),
+        (Domain::Thumbnails, THUMBNAILS),
+    ] {
+        let fixtures = serde_json::from_str::<Vec<Fixture>>(fixtures).unwrap();
+
+        for fixture in fixtures {
+            server.mock(
+                domain,
+                &fixture.method,
+                &fixture.path,
+                MockResponse::json(&fixture.body.to_string()).status(fixture.status),
+            );
+        }
+    }
+}
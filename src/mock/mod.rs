@@ -0,0 +1,438 @@
+//! An offline stand-in for the Roblox web APIs.
+//!
+//! [`MockServer`] is a tiny HTTP server bound to localhost which answers requests with
+//! recorded fixtures or scripted responses, so code using a [`RequestJar`] can be tested
+//! without network access or credentials.
+//!
+//! ```ignore
+//! let server = MockServer::with_fixtures().await;
+//! server.script(Domain::Groups, "GET", "/v1/groups/7370273", vec![MockResponse::rate_limited()]);
+//!
+//! let jar = server.jar().await;
+//! let group = groups::group_by_id(&jar, 7370273).await;
+//! ```
+
+mod fixtures;
+#[cfg(test)]
+pub(crate) mod testing;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+
+use crate::util::{
+    hosts::{Domain, Hosts},
+    jar::RequestJar,
+};
+
+/// A response the mock server sends back
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockResponse {
+    /// A 200 response with a JSON body
+    pub fn json(body: &str) -> MockResponse {
+        MockResponse {
+            status: 200,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: body.to_string(),
+        }
+    }
+
+    /// A 200 response with one page of a cursor paged listing
+    pub fn page(data: Vec<serde_json::Value>, next_cursor: Option<&str>) -> MockResponse {
+        MockResponse::page_between(data, None, next_cursor)
+    }
+
+    /// A page of a cursor paged listing with a cursor back to the page before it
+    pub fn page_between(
+        data: Vec<serde_json::Value>,
+        previous_cursor: Option<&str>,
+        next_cursor: Option<&str>,
+    ) -> MockResponse {
+        MockResponse::json(
+            &serde_json::json!({
+                "previousPageCursor": previous_cursor,
+                "nextPageCursor": next_cursor,
+                "data": data,
+            })
+            .to_string(),
+        )
+    }
+
+    /// A response with a Roblox `errors` array
+    pub fn errors(status: u16, errors: &[(i64, &str)]) -> MockResponse {
+        let errors = errors
+            .iter()
+            .map(|(code, message)| {
+                serde_json::json!({
+                    "code": code,
+                    "message": message,
+                    "userFacingMessage": "Something went wrong",
+                })
+            })
+            .collect::<Vec<_>>();
+
+        MockResponse::json(&serde_json::json!({ "errors": errors }).to_string()).status(status)
+    }
+
+    /// A 429 response, like the ones Roblox sends when you are being rate limited
+    pub fn rate_limited() -> MockResponse {
+        MockResponse::errors(429, &[(0, "TooManyRequests")])
+    }
+
+    /// A 403 response asking the client to retry with a new X-CSRF-TOKEN
+    pub fn csrf_challenge(token: &str) -> MockResponse {
+        MockResponse::errors(403, &[(0, "Token Validation Failed")]).header("x-csrf-token", token)
+    }
+
+    /// Overrides the status code
+    pub fn status(mut self, status: u16) -> MockResponse {
+        self.status = status;
+        self
+    }
+
+    /// Adds a header
+    pub fn header(mut self, name: &str, value: &str) -> MockResponse {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// A user as most group endpoints list them, named `user<id>`
+pub fn mock_user(user_id: i64) -> serde_json::Value {
+    serde_json::json!({
+        "hasVerifiedBadge": false,
+        "userId": user_id,
+        "username": format!("user{}", user_id),
+        "displayName": format!("user{}", user_id),
+    })
+}
+
+/// A request the mock server received
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// The path, including the domain prefix (e.g. `/groups/v1/groups/1`)
+    pub path: String,
+    /// The percent-decoded query parameters
+    pub query: Vec<(String, String)>,
+    /// The headers, with lowercase names
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    /// Gets a header by its (case insensitive) name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(|value| value.as_str())
+    }
+
+    /// Gets a query parameter by its name
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+struct Route {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    scripted: VecDeque<MockResponse>,
+    fallback: Option<MockResponse>,
+}
+
+impl Route {
+    fn matches(&self, request: &RecordedRequest) -> bool {
+        self.method == request.method
+            && self.path == request.path
+            && self.query.iter().all(|param| request.query.contains(param))
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    routes: Vec<Route>,
+    requests: Vec<RecordedRequest>,
+}
+
+impl MockState {
+    fn respond(&mut self, request: RecordedRequest) -> MockResponse {
+        // Prefer routes with scripted responses left, then the most specific query
+        let route = self
+            .routes
+            .iter_mut()
+            .filter(|route| route.matches(&request))
+            .filter(|route| !route.scripted.is_empty() || route.fallback.is_some())
+            .max_by_key(|route| (!route.scripted.is_empty(), route.query.len()));
+
+        let response = match route {
+            Some(route) => match route.scripted.pop_front() {
+                Some(response) => response,
+                None => route.fallback.clone().unwrap(),
+            },
+            None => MockResponse::errors(404, &[(0, "NotFound")]),
+        };
+
+        self.requests.push(request);
+        response
+    }
+
+    fn route(&mut self, domain: Domain, method: &str, path: &str) -> &mut Route {
+        let (path, query) = split_path(&format!("/{}{}", domain.get_subdomain(), path));
+        let method = method.to_ascii_uppercase();
+
+        let index = self
+            .routes
+            .iter()
+            .position(|route| route.method == method && route.path == path && route.query == query);
+
+        match index {
+            Some(index) => &mut self.routes[index],
+            None => {
+                self.routes.push(Route {
+                    method,
+                    path,
+                    query,
+                    scripted: VecDeque::new(),
+                    fallback: None,
+                });
+                self.routes.last_mut().unwrap()
+            }
+        }
+    }
+}
+
+/// A local HTTP server standing in for Roblox
+pub struct MockServer {
+    address: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    handle: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Starts an empty mock server, every request gets a 404 until routes are added
+    pub async fn start() -> MockServer {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(MockState::default()));
+
+        let accept_state = state.clone();
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = accept_state.clone();
+                tokio::spawn(async move {
+                    let _ = handle_connection(stream, state).await;
+                });
+            }
+        });
+
+        MockServer {
+            address,
+            state,
+            handle,
+        }
+    }
+
+    /// Starts a mock server serving the recorded groups, users and thumbnails fixtures
+    pub async fn with_fixtures() -> MockServer {
+        let server = MockServer::start().await;
+        fixtures::install(&server);
+        server
+    }
+
+    /// The base url of the server, e.g. `http://127.0.0.1:1234`
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// A host map pointing every domain at this server
+    pub fn hosts(&self) -> Hosts {
+        Hosts::with_base_url(&self.base_url())
+    }
+
+    /// Creates a request jar talking to this server
+    pub async fn jar(&self) -> RequestJar {
+        let mut jar = RequestJar::new().await;
+        jar.set_hosts(self.hosts());
+        jar
+    }
+
+    /// Always answers requests to a route with the given response.
+    ///
+    /// The path is relative to the domain and may contain query parameters, which then
+    /// all have to be present in a request for it to match.
+    pub fn mock(&self, domain: Domain, method: &str, path: &str, response: MockResponse) {
+        let mut state = self.state.lock().unwrap();
+        state.route(domain, method, path).fallback = Some(response);
+    }
+
+    /// Answers the next requests to a route with the given responses, in order.
+    /// Once they have been used up the route falls back to [`MockServer::mock`]'s response.
+    pub fn script(&self, domain: Domain, method: &str, path: &str, responses: Vec<MockResponse>) {
+        let mut state = self.state.lock().unwrap();
+        state.route(domain, method, path).scripted.extend(responses);
+    }
+
+    /// Every request the server has received so far
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    state: Arc<Mutex<MockState>>,
+) -> std::io::Result<()> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    // Read until the end of the headers
+    let header_end = loop {
+        if let Some(position) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break position;
+        }
+
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or_default().to_string();
+
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect::<HashMap<_, _>>();
+
+    // Read the rest of the body
+    let content_length = headers
+        .get("content-length")
+        .and_then(|length| length.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = buffer[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+
+    let (path, query) = split_path(&target);
+    let request = RecordedRequest {
+        method,
+        path,
+        query,
+        headers,
+        body: String::from_utf8_lossy(&body).to_string(),
+    };
+
+    let response = state.lock().unwrap().respond(request);
+
+    let mut raw = format!(
+        "HTTP/1.1 {} {}\r\n",
+        response.status,
+        reason(response.status)
+    );
+    for (name, value) in &response.headers {
+        raw.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    raw.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.body.len(),
+        response.body
+    ));
+
+    stream.write_all(raw.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Splits a request target into its path and decoded query parameters
+fn split_path(target: &str) -> (String, Vec<(String, String)>) {
+    match target.split_once('?') {
+        Some((path, query)) => (
+            path.to_string(),
+            query
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| match pair.split_once('=') {
+                    Some((key, value)) => (percent_decode(key), percent_decode(value)),
+                    None => (percent_decode(pair), String::new()),
+                })
+                .collect(),
+        ),
+        None => (target.to_string(), Vec::new()),
+    }
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let escaped = match bytes.get(index + 1..index + 3) {
+            Some(hex) if bytes[index] == b'%' => std::str::from_utf8(hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+
+        match (escaped, bytes[index]) {
+            (Some(byte), _) => {
+                decoded.push(byte);
+                index += 3;
+                continue;
+            }
+            (None, b'+') => decoded.push(b' '),
+            (None, byte) => decoded.push(byte),
+        }
+        index += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
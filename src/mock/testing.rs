@@ -0,0 +1,121 @@
+//! Mock servers set up for the tests of more than one module.
+
+use std::path::PathBuf;
+
+use crate::util::{hosts::Domain, jar::RequestJar};
+
+use super::{mock_user, MockResponse, MockServer};
+
+/// Starts a mock server serving the recorded fixtures, and a jar talking to it.
+/// The server shuts down when it's dropped, so keep it around for the whole test.
+pub(crate) async fn mock_jar() -> (MockServer, RequestJar) {
+    let server = MockServer::with_fixtures().await;
+    let jar = server.jar().await;
+    (server, jar)
+}
+
+/// A path in the temporary directory that is unique to the test
+pub(crate) fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rustyroblox-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Serves the members of group 1 as three pages of 2, 2 and 1 members
+pub(crate) async fn paged_members_server() -> MockServer {
+    let page = |user_ids: &[i64], previous: Option<&str>, next: Option<&str>| {
+        let data = user_ids
+            .iter()
+            .map(|user_id| {
+                serde_json::json!({
+                    "user": mock_user(*user_id),
+                    "role": { "id": 1, "name": "Member", "rank": 1 },
+                })
+            })
+            .collect();
+        MockResponse::page_between(data, previous, next)
+    };
+
+    let server = MockServer::start().await;
+    server.mock(
+        Domain::Groups,
+        "GET",
+        "/v1/groups/1/users",
+        page(&[1, 2], None, Some("page2")),
+    );
+    server.mock(
+        Domain::Groups,
+        "GET",
+        "/v1/groups/1/users?cursor=page2",
+        page(&[3, 4], Some("page1"), Some("page3")),
+    );
+    server.mock(
+        Domain::Groups,
+        "GET",
+        "/v1/groups/1/users?cursor=page3",
+        page(&[5], Some("page2"), None),
+    );
+    server
+}
+
+/// A mock group 1 with its roles out of order, where user 2 is a Member, user 3 an Admin,
+/// user 4 the owner and user 5 isn't in the group. Every role change is accepted.
+pub(crate) async fn ranked_group_server() -> MockServer {
+    let server = MockServer::start().await;
+    server.mock(
+        Domain::Groups,
+        "GET",
+        "/v1/groups/1/roles",
+        MockResponse::json(
+            &serde_json::json!({
+                "groupId": 1,
+                "roles": [
+                    { "id": 14, "name": "Owner", "rank": 255 },
+                    { "id": 11, "name": "Member", "rank": 1 },
+                    { "id": 13, "name": "Admin", "rank": 200 },
+                    { "id": 10, "name": "Guest", "rank": 0 },
+                    { "id": 12, "name": "Trusted", "rank": 50 },
+                ],
+            })
+            .to_string(),
+        ),
+    );
+
+    let group = serde_json::json!({ "id": 1, "name": "Ranked", "memberCount": 3, "hasVerifiedBadge": false });
+    for (user_id, role) in [
+        (
+            2,
+            serde_json::json!({ "id": 11, "name": "Member", "rank": 1 }),
+        ),
+        (
+            3,
+            serde_json::json!({ "id": 13, "name": "Admin", "rank": 200 }),
+        ),
+        (
+            4,
+            serde_json::json!({ "id": 14, "name": "Owner", "rank": 255 }),
+        ),
+    ] {
+        server.mock(
+            Domain::Groups,
+            "GET",
+            &format!("/v2/users/{}/groups/roles", user_id),
+            MockResponse::json(
+                &serde_json::json!({ "data": [{ "group": group, "role": role }] }).to_string(),
+            ),
+        );
+        server.mock(
+            Domain::Groups,
+            "PATCH",
+            &format!("/v1/groups/1/users/{}", user_id),
+            MockResponse::json("{}"),
+        );
+    }
+    server.mock(
+        Domain::Groups,
+        "GET",
+        "/v2/users/5/groups/roles",
+        MockResponse::json(r#"{"data":[]}"#),
+    );
+    server
+}
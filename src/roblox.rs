@@ -0,0 +1,948 @@
+//! A client that groups the endpoints by what they act on.
+//!
+//! [`Roblox`] owns the [`RequestJar`] and hands out sub-APIs and handles that pass it to the
+//! functions in [`groups`], [`users`] and [`thumbnails`], so they don't have to be given the
+//! jar and ids on every call:
+//!
+//! ```no_run
+//! # use futures::TryStreamExt;
+//! # use rustyroblox::{Roblox, util::ids::{GroupId, RoleId, UserId}};
+//! # async fn census() -> Result<(), Box<rustyroblox::util::Error>> {
+//! let client = Roblox::new().await;
+//! let me = client.users().whoami().await?;
+//!
+//! let group = client.group(GroupId(7370273));
+//! let members = group.members().stream(None).try_collect::<Vec<_>>().await?;
+//! println!("{} is looking at {} members", me.name, members.len());
+//!
+//! group.member(UserId(375760054)).set_role(RoleId(47932104)).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The free functions keep working, and [`Roblox::jar`] gives access to the jar for anything
+//! the client doesn't wrap. The client also keeps a [`RoleCache`], so looking up roles by rank
+//! or name doesn't request a group's roles every time.
+
+use std::{sync::Arc, time::Duration};
+
+use crate::{
+    groups::{
+        self, join_requests, AuditLogWatcher, BulkOptions, BulkReport, GroupAuditLogEntry,
+        GroupEvents, GroupMembershipUserRole, GroupNameHistoryEntry, GroupRole, GroupSearchProps,
+        JoinRequestPolicy, JoinRequestReview, PermissionDiff, PermissionTemplate, RankBounds,
+        RankChange, RoleCache, RoleUpdate, WallPost,
+    },
+    thumbnails::{self, ThumbnailFormat, ThumbnailResponse, ThumbnailReturnPolicy, ThumbnailSize},
+    users,
+    util::{
+        ids::{
+            AssetId, BadgeId, DeveloperProductId, GamePassId, GroupId, RoleId, SocialLinkId,
+            UniverseId, UserId, WallPostId,
+        },
+        jar::RequestJar,
+        paging::{PageLimit, Paginator, SortOrder},
+        Error,
+    },
+};
+
+/// A Roblox client, cheap to clone and share between tasks
+#[derive(Clone)]
+pub struct Roblox {
+    jar: Arc<RequestJar>,
+    role_cache: Arc<RoleCache>,
+}
+
+impl Roblox {
+    /// Creates a client with a jar using the default settings
+    pub async fn new() -> Roblox {
+        Roblox::from_jar(RequestJar::new().await)
+    }
+
+    /// Creates a client sending its requests through a jar, configured with [`RequestJar::builder`]
+    pub fn from_jar(jar: RequestJar) -> Roblox {
+        Roblox::from(Arc::new(jar))
+    }
+
+    /// How long the roles of a group are kept when looking up roles by rank or name,
+    /// zero to request them every time. Clones made before this keep the old cache.
+    pub fn role_cache_ttl(mut self, ttl: Duration) -> Roblox {
+        self.role_cache = Arc::new(RoleCache::new(ttl));
+        self
+    }
+
+    /// The roles kept for looking up roles by rank or name, shared with the clones of the client
+    pub fn role_cache(&self) -> &RoleCache {
+        &self.role_cache
+    }
+
+    /// The jar the client sends its requests through, for calling the free functions
+    pub fn jar(&self) -> &RequestJar {
+        &self.jar
+    }
+
+    /// The jar as shared with the client, to keep it alive in another task
+    pub fn shared_jar(&self) -> Arc<RequestJar> {
+        self.jar.clone()
+    }
+
+    /// Endpoints that aren't about a single group
+    pub fn groups(&self) -> GroupsApi<'_> {
+        GroupsApi { jar: &self.jar }
+    }
+
+    /// Endpoints that aren't about a single user
+    pub fn users(&self) -> UsersApi<'_> {
+        UsersApi { jar: &self.jar }
+    }
+
+    /// Thumbnails and icons of assets, badges, games and products
+    pub fn thumbnails(&self) -> ThumbnailsApi<'_> {
+        ThumbnailsApi { jar: &self.jar }
+    }
+
+    /// A group, nothing is requested until one of its methods is called
+    pub fn group(&self, group_id: GroupId) -> GroupHandle<'_> {
+        GroupHandle {
+            jar: &self.jar,
+            role_cache: &self.role_cache,
+            group_id,
+        }
+    }
+
+    /// A user, nothing is requested until one of its methods is called
+    pub fn user(&self, user_id: UserId) -> UserHandle<'_> {
+        UserHandle {
+            jar: &self.jar,
+            user_id,
+        }
+    }
+}
+
+impl From<RequestJar> for Roblox {
+    fn from(jar: RequestJar) -> Self {
+        Roblox::from_jar(jar)
+    }
+}
+
+impl From<Arc<RequestJar>> for Roblox {
+    fn from(jar: Arc<RequestJar>) -> Self {
+        Roblox {
+            jar,
+            role_cache: Arc::new(RoleCache::default()),
+        }
+    }
+}
+
+/// Group endpoints that aren't about a single group, see [`groups`]
+#[derive(Clone, Copy)]
+pub struct GroupsApi<'a> {
+    jar: &'a RequestJar,
+}
+
+impl<'a> GroupsApi<'a> {
+    /// See [`groups::group_by_id`]
+    pub async fn get(&self, group_id: GroupId) -> Result<groups::Group, Box<Error>> {
+        groups::group_by_id(self.jar, group_id).await
+    }
+
+    /// See [`groups::search`]
+    pub async fn search(
+        &self,
+        keyword: String,
+        props: Option<GroupSearchProps>,
+    ) -> Result<groups::GroupSearchResponse, Box<Error>> {
+        groups::search(self.jar, keyword, props).await
+    }
+
+    /// See [`groups::exact_search`]
+    pub async fn exact_search(
+        &self,
+        group_name: String,
+    ) -> Result<Vec<groups::MinimalSearchGroup>, Box<Error>> {
+        groups::exact_search(self.jar, group_name).await
+    }
+
+    /// See [`groups::search_metadata`]
+    pub async fn search_metadata(&self) -> Result<groups::GroupSearchMetadata, Box<Error>> {
+        groups::search_metadata(self.jar).await
+    }
+
+    /// See [`groups::metadata`]
+    pub async fn metadata(&self) -> Result<groups::GroupMetadata, Box<Error>> {
+        groups::metadata(self.jar).await
+    }
+
+    /// See [`groups::config_metadata`]
+    pub async fn config_metadata(&self) -> Result<groups::GroupConfigMetadata, Box<Error>> {
+        groups::config_metadata(self.jar).await
+    }
+
+    /// See [`groups::compliance`]
+    pub async fn compliance(
+        &self,
+        group_ids: Vec<GroupId>,
+    ) -> Result<groups::GroupComplianceResponse, Box<Error>> {
+        groups::compliance(self.jar, group_ids).await
+    }
+
+    /// See [`groups::roles_by_id`]
+    pub async fn roles_by_id(&self, role_ids: Vec<RoleId>) -> Result<Vec<GroupRole>, Box<Error>> {
+        groups::roles_by_id(self.jar, role_ids).await
+    }
+
+    /// See [`groups::pending_requests`]
+    pub async fn pending_requests(&self) -> Result<Vec<groups::Group>, Box<Error>> {
+        groups::pending_requests(self.jar).await
+    }
+
+    /// See [`groups::friend_groups`]
+    pub async fn friend_groups(&self) -> Result<Vec<groups::FriendGroupsItem>, Box<Error>> {
+        groups::friend_groups(self.jar).await
+    }
+
+    /// See [`groups::set_primary_group`]
+    pub async fn set_primary_group(&self, group_id: GroupId) -> Result<(), Box<Error>> {
+        groups::set_primary_group(self.jar, group_id).await
+    }
+
+    /// See [`groups::remove_primary_group`]
+    pub async fn remove_primary_group(&self) -> Result<(), Box<Error>> {
+        groups::remove_primary_group(self.jar).await
+    }
+}
+
+/// User endpoints that aren't about a single user, see [`users`]
+#[derive(Clone, Copy)]
+pub struct UsersApi<'a> {
+    jar: &'a RequestJar,
+}
+
+impl<'a> UsersApi<'a> {
+    /// See [`users::user_by_id`]
+    pub async fn get(&self, user_id: UserId) -> Result<users::User, Box<Error>> {
+        users::user_by_id(self.jar, user_id).await
+    }
+
+    /// See [`users::whoami`]
+    pub async fn whoami(&self) -> Result<users::MinimalAuthenticatedUser, Box<Error>> {
+        users::whoami(self.jar).await
+    }
+
+    /// See [`users::bulk_users_by_id`]
+    pub async fn by_ids(
+        &self,
+        user_ids: Vec<UserId>,
+    ) -> Result<Vec<users::MinimalBulkUserById>, Box<Error>> {
+        users::bulk_users_by_id(self.jar, user_ids).await
+    }
+
+    /// See [`users::bulk_users_by_username`]
+    pub async fn by_usernames(
+        &self,
+        usernames: Vec<String>,
+    ) -> Result<Vec<users::MinimalBulkUserByUsername>, Box<Error>> {
+        users::bulk_users_by_username(self.jar, usernames).await
+    }
+
+    /// See [`users::username_search`]
+    pub async fn search(
+        &self,
+        keyword: String,
+        limit: PageLimit,
+    ) -> Result<Vec<users::UsernameSearchEntry>, Box<Error>> {
+        users::username_search(self.jar, keyword, limit).await
+    }
+
+    /// See [`users::validate_display_name`]
+    pub async fn validate_display_name(
+        &self,
+        display_name: String,
+    ) -> Result<users::ValidateDisplayNameResponseEnum, Box<Error>> {
+        users::validate_display_name(self.jar, display_name).await
+    }
+
+    /// See [`users::age_bracket`]
+    pub async fn age_bracket(&self) -> Result<users::AgeBracketResponse, Box<Error>> {
+        users::age_bracket(self.jar).await
+    }
+
+    /// See [`users::country_code`]
+    pub async fn country_code(&self) -> Result<users::CountryCodeResponse, Box<Error>> {
+        users::country_code(self.jar).await
+    }
+
+    /// See [`users::roles`]
+    pub async fn roles(&self) -> Result<users::RolesResponse, Box<Error>> {
+        users::roles(self.jar).await
+    }
+}
+
+/// Thumbnail endpoints, see [`thumbnails`]
+#[derive(Clone, Copy)]
+pub struct ThumbnailsApi<'a> {
+    jar: &'a RequestJar,
+}
+
+impl<'a> ThumbnailsApi<'a> {
+    /// See [`thumbnails::get_thumbnails`]
+    pub async fn assets(
+        &self,
+        asset_ids: Vec<AssetId>,
+        thumbnail_size: Option<ThumbnailSize>,
+        thumbnail_return_policy: Option<ThumbnailReturnPolicy>,
+        thumbnail_format: Option<ThumbnailFormat>,
+        is_circular: bool,
+    ) -> Result<Vec<ThumbnailResponse>, Box<Error>> {
+        thumbnails::get_thumbnails(
+            self.jar,
+            asset_ids,
+            thumbnail_size,
+            thumbnail_return_policy,
+            thumbnail_format,
+            is_circular,
+        )
+        .await
+    }
+
+    /// See [`thumbnails::get_3d_thumbnail`]
+    pub async fn asset_3d(&self, asset_id: AssetId) -> Result<ThumbnailResponse, Box<Error>> {
+        thumbnails::get_3d_thumbnail(self.jar, asset_id).await
+    }
+
+    /// See [`thumbnails::get_animated_thumbnail`]
+    pub async fn asset_animated(&self, asset_id: AssetId) -> Result<ThumbnailResponse, Box<Error>> {
+        thumbnails::get_animated_thumbnail(self.jar, asset_id).await
+    }
+
+    /// See [`thumbnails::get_badge_thumbnails`]
+    pub async fn badges(
+        &self,
+        badge_ids: Vec<BadgeId>,
+        is_circular: bool,
+    ) -> Result<Vec<ThumbnailResponse>, Box<Error>> {
+        thumbnails::get_badge_thumbnails(self.jar, badge_ids, is_circular).await
+    }
+
+    /// See [`thumbnails::get_developer_product_icons`]
+    pub async fn developer_products(
+        &self,
+        developer_product_ids: Vec<DeveloperProductId>,
+        thumbnail_size: Option<ThumbnailSize>,
+        thumbnail_format: Option<ThumbnailFormat>,
+        is_circular: bool,
+    ) -> Result<Vec<ThumbnailResponse>, Box<Error>> {
+        thumbnails::get_developer_product_icons(
+            self.jar,
+            developer_product_ids,
+            thumbnail_size,
+            thumbnail_format,
+            is_circular,
+        )
+        .await
+    }
+
+    /// See [`thumbnails::get_game_pass_icons`]
+    pub async fn game_passes(
+        &self,
+        game_pass_ids: Vec<GamePassId>,
+        thumbnail_size: Option<ThumbnailSize>,
+        thumbnail_format: Option<ThumbnailFormat>,
+        is_circular: bool,
+    ) -> Result<Vec<ThumbnailResponse>, Box<Error>> {
+        thumbnails::get_game_pass_icons(
+            self.jar,
+            game_pass_ids,
+            thumbnail_size,
+            thumbnail_format,
+            is_circular,
+        )
+        .await
+    }
+
+    /// See [`thumbnails::get_game_thumbnails`]
+    pub async fn games(
+        &self,
+        universe_id: UniverseId,
+        thumbnail_ids: Vec<i64>,
+        thumbnail_size: Option<ThumbnailSize>,
+        thumbnail_format: Option<ThumbnailFormat>,
+        is_circular: bool,
+    ) -> Result<Vec<ThumbnailResponse>, Box<Error>> {
+        thumbnails::get_game_thumbnails(
+            self.jar,
+            universe_id,
+            thumbnail_ids,
+            thumbnail_size,
+            thumbnail_format,
+            is_circular,
+        )
+        .await
+    }
+
+    /// See [`thumbnails::get_game_icons`]
+    pub async fn game_icons(
+        &self,
+        universe_ids: Vec<UniverseId>,
+        thumbnail_size: Option<ThumbnailSize>,
+        thumbnail_return_policy: Option<ThumbnailReturnPolicy>,
+        thumbnail_format: Option<ThumbnailFormat>,
+        is_circular: bool,
+    ) -> Result<Vec<ThumbnailResponse>, Box<Error>> {
+        thumbnails::get_game_icons(
+            self.jar,
+            universe_ids,
+            thumbnail_size,
+            thumbnail_return_policy,
+            thumbnail_format,
+            is_circular,
+        )
+        .await
+    }
+}
+
+/// A single group, from [`Roblox::group`]
+#[derive(Clone, Copy)]
+pub struct GroupHandle<'a> {
+    jar: &'a RequestJar,
+    role_cache: &'a RoleCache,
+    group_id: GroupId,
+}
+
+impl<'a> GroupHandle<'a> {
+    pub fn id(&self) -> GroupId {
+        self.group_id
+    }
+
+    /// The group's members
+    pub fn members(&self) -> MembersHandle<'a> {
+        MembersHandle {
+            jar: self.jar,
+            group_id: self.group_id,
+        }
+    }
+
+    /// A user in the group, nothing is requested until one of its methods is called
+    pub fn member(&self, user_id: UserId) -> MemberHandle<'a> {
+        MemberHandle {
+            jar: self.jar,
+            role_cache: self.role_cache,
+            group_id: self.group_id,
+            user_id,
+        }
+    }
+
+    /// See [`groups::group_by_id`]
+    pub async fn get(&self) -> Result<groups::Group, Box<Error>> {
+        groups::group_by_id(self.jar, self.group_id).await
+    }
+
+    /// See [`groups::settings`]
+    pub async fn settings(&self) -> Result<groups::GroupSettings, Box<Error>> {
+        groups::settings(self.jar, self.group_id).await
+    }
+
+    /// See [`groups::update_settings`]
+    pub async fn update_settings(
+        &self,
+        request: groups::GroupSettingsUpdateRequest,
+    ) -> Result<groups::GroupSettingsUpdateResponse, Box<Error>> {
+        groups::update_settings(self.jar, self.group_id, request).await
+    }
+
+    /// See [`groups::update_description`]
+    pub async fn update_description(
+        &self,
+        description: String,
+    ) -> Result<groups::NewDescriptionResponse, Box<Error>> {
+        groups::update_description(self.jar, self.group_id, description).await
+    }
+
+    /// See [`groups::update_name`]
+    pub async fn update_name(&self, name: String) -> Result<groups::NewNameResponse, Box<Error>> {
+        groups::update_name(self.jar, self.group_id, name).await
+    }
+
+    /// See [`groups::membership`]
+    pub async fn membership(&self) -> Result<groups::GroupMembership, Box<Error>> {
+        groups::membership(self.jar, self.group_id).await
+    }
+
+    /// See [`groups::roles`]
+    pub async fn roles(&self) -> Result<Vec<GroupRole>, Box<Error>> {
+        groups::roles(self.jar, self.group_id).await
+    }
+
+    /// See [`groups::role_by_id`]
+    pub async fn role_by_id(&self, role_id: RoleId) -> Result<GroupRole, Box<Error>> {
+        groups::role_by_id(self.jar, self.group_id, role_id).await
+    }
+
+    /// See [`groups::update_role`], the cached roles of the group are dropped once it's changed
+    pub async fn update_role(
+        &self,
+        role_id: RoleId,
+        update: RoleUpdate,
+    ) -> Result<GroupRole, Box<Error>> {
+        let role = groups::update_role(self.jar, self.group_id, role_id, update).await?;
+        self.role_cache.invalidate(self.group_id);
+        Ok(role)
+    }
+
+    /// See [`groups::role_by_rank`]
+    pub async fn role_by_rank(&self, rank: i64) -> Result<GroupRole, Box<Error>> {
+        groups::role_by_rank(self.jar, self.role_cache, self.group_id, rank).await
+    }
+
+    /// See [`groups::role_by_name`]
+    pub async fn role_by_name(&self, name: &str) -> Result<GroupRole, Box<Error>> {
+        groups::role_by_name(self.jar, self.role_cache, self.group_id, name).await
+    }
+
+    /// See [`groups::users_on_role`]
+    pub async fn users_on_role(
+        &self,
+        role_id: RoleId,
+        limit: PageLimit,
+        sort_order: Option<SortOrder>,
+    ) -> Result<Vec<users::MinimalGroupUser>, Box<Error>> {
+        groups::users_on_role(self.jar, self.group_id, role_id, limit, sort_order).await
+    }
+
+    /// See [`groups::permissions`]
+    pub async fn permissions(&self) -> Result<Vec<groups::RolePermissions>, Box<Error>> {
+        groups::permissions(self.jar, self.group_id).await
+    }
+
+    /// See [`groups::guest_permissions`]
+    pub async fn guest_permissions(&self) -> Result<groups::RolePermissions, Box<Error>> {
+        groups::guest_permissions(self.jar, self.group_id).await
+    }
+
+    /// See [`groups::role_permissions`]
+    pub async fn role_permissions(
+        &self,
+        role_id: RoleId,
+    ) -> Result<groups::RolePermissions, Box<Error>> {
+        groups::role_permissions(self.jar, self.group_id, role_id).await
+    }
+
+    /// See [`groups::snapshot_permissions`]
+    pub async fn snapshot_permissions(&self) -> Result<PermissionTemplate, Box<Error>> {
+        groups::snapshot_permissions(self.jar, self.group_id).await
+    }
+
+    /// See [`groups::diff_permissions`]
+    pub async fn diff_permissions(
+        &self,
+        template: &PermissionTemplate,
+    ) -> Result<PermissionDiff, Box<Error>> {
+        groups::diff_permissions(self.jar, self.group_id, template).await
+    }
+
+    /// See [`groups::apply_permissions`]
+    pub async fn apply_permissions(
+        &self,
+        template: &PermissionTemplate,
+    ) -> Result<PermissionDiff, Box<Error>> {
+        groups::apply_permissions(self.jar, self.group_id, template).await
+    }
+
+    /// See [`groups::audit_log`]
+    pub async fn audit_log(
+        &self,
+        limit: PageLimit,
+        user_id: Option<UserId>,
+        sort_order: Option<SortOrder>,
+        cursor: Option<String>,
+    ) -> Result<Vec<GroupAuditLogEntry>, Box<Error>> {
+        groups::audit_log(self.jar, self.group_id, limit, user_id, sort_order, cursor).await
+    }
+
+    /// See [`groups::audit_log_stream`]
+    pub fn audit_log_stream(
+        &self,
+        user_id: Option<UserId>,
+        sort_order: Option<SortOrder>,
+    ) -> Paginator<'a, GroupAuditLogEntry> {
+        groups::audit_log_stream(self.jar, self.group_id, user_id, sort_order)
+    }
+
+    /// See [`groups::AuditLogWatcher`]
+    pub fn audit_log_watcher(&self) -> AuditLogWatcher<'a> {
+        AuditLogWatcher::new(self.jar, self.group_id)
+    }
+
+    /// See [`groups::GroupEvents`]
+    pub fn events(&self) -> GroupEvents<'a> {
+        GroupEvents::new(self.jar, self.group_id)
+    }
+
+    /// See [`groups::name_history`]
+    pub async fn name_history(
+        &self,
+        limit: PageLimit,
+        sort_order: Option<SortOrder>,
+        cursor: Option<String>,
+    ) -> Result<Vec<GroupNameHistoryEntry>, Box<Error>> {
+        groups::name_history(self.jar, self.group_id, limit, sort_order, cursor).await
+    }
+
+    /// See [`groups::wall`]
+    pub async fn wall(
+        &self,
+        limit: PageLimit,
+        sort_order: Option<SortOrder>,
+        cursor: Option<String>,
+    ) -> Result<Vec<WallPost>, Box<Error>> {
+        groups::wall(self.jar, self.group_id, limit, sort_order, cursor).await
+    }
+
+    /// See [`groups::wall_stream`]
+    pub fn wall_stream(&self, sort_order: Option<SortOrder>) -> Paginator<'a, WallPost> {
+        groups::wall_stream(self.jar, self.group_id, sort_order)
+    }
+
+    /// See [`groups::delete_wall_post`]
+    pub async fn delete_wall_post(&self, post_id: WallPostId) -> Result<(), Box<Error>> {
+        groups::delete_wall_post(self.jar, self.group_id, post_id).await
+    }
+
+    /// See [`groups::social_links`]
+    pub async fn social_links(&self) -> Result<Vec<groups::SocialLink>, Box<Error>> {
+        groups::social_links(self.jar, self.group_id).await
+    }
+
+    /// See [`groups::add_social_link`]
+    pub async fn add_social_link(&self, social_link: groups::SocialLink) -> Result<(), Box<Error>> {
+        groups::add_social_link(self.jar, self.group_id, social_link).await
+    }
+
+    /// See [`groups::update_social_link`]
+    pub async fn update_social_link(
+        &self,
+        social_link: groups::SocialLink,
+    ) -> Result<(), Box<Error>> {
+        groups::update_social_link(self.jar, self.group_id, social_link).await
+    }
+
+    /// See [`groups::delete_social_link`]
+    pub async fn delete_social_link(&self, social_link_id: SocialLinkId) -> Result<(), Box<Error>> {
+        groups::delete_social_link(self.jar, self.group_id, social_link_id).await
+    }
+
+    /// See [`groups::allies`]
+    pub async fn allies(&self) -> Result<groups::GroupRelationships, Box<Error>> {
+        groups::allies(self.jar, self.group_id).await
+    }
+
+    /// See [`groups::enemies`]
+    pub async fn enemies(&self) -> Result<groups::GroupRelationships, Box<Error>> {
+        groups::enemies(self.jar, self.group_id).await
+    }
+
+    /// See [`join_requests::all::get`]
+    pub async fn join_requests(
+        &self,
+        limit: PageLimit,
+    ) -> Result<Vec<join_requests::all::JoinRequest>, Box<Error>> {
+        join_requests::all::get(self.jar, self.group_id, limit).await
+    }
+
+    /// See [`groups::bulk_set_role`]
+    pub async fn bulk_set_role(
+        &self,
+        user_ids: Vec<UserId>,
+        role_id: RoleId,
+        options: BulkOptions,
+    ) -> BulkReport {
+        groups::bulk_set_role(self.jar, self.group_id, user_ids, role_id, options).await
+    }
+
+    /// See [`groups::bulk_remove_users`]
+    pub async fn bulk_remove_users(
+        &self,
+        user_ids: Vec<UserId>,
+        options: BulkOptions,
+    ) -> BulkReport {
+        groups::bulk_remove_users(self.jar, self.group_id, user_ids, options).await
+    }
+
+    /// See [`groups::bulk_accept_join_requests`]
+    pub async fn bulk_accept_join_requests(
+        &self,
+        user_ids: Vec<UserId>,
+        options: BulkOptions,
+    ) -> BulkReport {
+        groups::bulk_accept_join_requests(self.jar, self.group_id, user_ids, options).await
+    }
+
+    /// See [`groups::bulk_decline_join_requests`]
+    pub async fn bulk_decline_join_requests(
+        &self,
+        user_ids: Vec<UserId>,
+        options: BulkOptions,
+    ) -> BulkReport {
+        groups::bulk_decline_join_requests(self.jar, self.group_id, user_ids, options).await
+    }
+
+    /// See [`groups::review_join_requests`]
+    pub async fn review_join_requests(
+        &self,
+        policy: &JoinRequestPolicy,
+        limit: PageLimit,
+        options: BulkOptions,
+    ) -> Result<JoinRequestReview, Box<Error>> {
+        groups::review_join_requests(self.jar, self.group_id, policy, limit, options).await
+    }
+
+    /// See [`groups::moderate_join_requests`]
+    pub async fn moderate_join_requests(
+        &self,
+        policy: &JoinRequestPolicy,
+        limit: PageLimit,
+        options: BulkOptions,
+    ) -> Result<JoinRequestReview, Box<Error>> {
+        groups::moderate_join_requests(self.jar, self.group_id, policy, limit, options).await
+    }
+
+    /// See [`groups::get_payout_restrictions`]
+    pub async fn payout_restrictions(&self) -> Result<groups::PayoutRestrictions, Box<Error>> {
+        groups::get_payout_restrictions(self.jar, self.group_id).await
+    }
+
+    /// See [`groups::get_recurring_payouts`]
+    pub async fn recurring_payouts(&self) -> Result<Vec<groups::RecurringPayout>, Box<Error>> {
+        groups::get_recurring_payouts(self.jar, self.group_id).await
+    }
+
+    /// See [`groups::change_owner`]
+    pub async fn change_owner(&self, user_id: UserId) -> Result<(), Box<Error>> {
+        groups::change_owner(self.jar, self.group_id, user_id).await
+    }
+
+    /// See [`groups::claim_ownership`]
+    pub async fn claim_ownership(&self) -> Result<(), Box<Error>> {
+        groups::claim_ownership(self.jar, self.group_id).await
+    }
+}
+
+/// The members of a group, from [`GroupHandle::members`]
+#[derive(Clone, Copy)]
+pub struct MembersHandle<'a> {
+    jar: &'a RequestJar,
+    group_id: GroupId,
+}
+
+impl<'a> MembersHandle<'a> {
+    /// Every member, requested a page at a time while the stream is read.
+    /// See [`groups::members_stream`]
+    pub fn stream(&self, sort_order: Option<SortOrder>) -> Paginator<'a, GroupMembershipUserRole> {
+        groups::members_stream(self.jar, self.group_id, sort_order)
+    }
+
+    /// See [`groups::members`]
+    pub async fn page(
+        &self,
+        limit: PageLimit,
+        sort_order: Option<SortOrder>,
+        cursor: Option<String>,
+    ) -> Result<Vec<GroupMembershipUserRole>, Box<Error>> {
+        groups::members(self.jar, self.group_id, limit, sort_order, cursor).await
+    }
+}
+
+/// A user in a group, from [`GroupHandle::member`]
+#[derive(Clone, Copy)]
+pub struct MemberHandle<'a> {
+    jar: &'a RequestJar,
+    role_cache: &'a RoleCache,
+    group_id: GroupId,
+    user_id: UserId,
+}
+
+impl<'a> MemberHandle<'a> {
+    pub fn group_id(&self) -> GroupId {
+        self.group_id
+    }
+
+    pub fn user_id(&self) -> UserId {
+        self.user_id
+    }
+
+    /// See [`groups::user_role`]
+    pub async fn role(&self) -> Result<GroupRole, Box<Error>> {
+        groups::user_role(self.jar, self.group_id, self.user_id).await
+    }
+
+    /// See [`groups::set_role`]
+    pub async fn set_role(&self, role_id: RoleId) -> Result<(), Box<Error>> {
+        groups::set_role(self.jar, self.group_id, self.user_id, role_id).await
+    }
+
+    /// See [`groups::set_rank_by_number`]
+    pub async fn set_rank_by_number(&self, rank: i64) -> Result<GroupRole, Box<Error>> {
+        groups::set_rank_by_number(self.jar, self.role_cache, self.group_id, self.user_id, rank)
+            .await
+    }
+
+    /// See [`groups::set_role_by_name`]
+    pub async fn set_role_by_name(&self, name: &str) -> Result<GroupRole, Box<Error>> {
+        groups::set_role_by_name(self.jar, self.role_cache, self.group_id, self.user_id, name).await
+    }
+
+    /// See [`groups::change_rank`]
+    pub async fn change_rank(
+        &self,
+        amount: i64,
+        bounds: RankBounds,
+    ) -> Result<RankChange, Box<Error>> {
+        groups::change_rank(self.jar, self.group_id, self.user_id, amount, bounds).await
+    }
+
+    /// See [`groups::promote`]
+    pub async fn promote(&self) -> Result<RankChange, Box<Error>> {
+        groups::promote(self.jar, self.group_id, self.user_id).await
+    }
+
+    /// See [`groups::demote`]
+    pub async fn demote(&self) -> Result<RankChange, Box<Error>> {
+        groups::demote(self.jar, self.group_id, self.user_id).await
+    }
+
+    /// See [`groups::remove_user`]
+    pub async fn remove(&self) -> Result<(), Box<Error>> {
+        groups::remove_user(self.jar, self.group_id, self.user_id).await
+    }
+
+    /// See [`groups::delete_wall_posts_by_user`]
+    pub async fn delete_wall_posts(&self) -> Result<(), Box<Error>> {
+        groups::delete_wall_posts_by_user(self.jar, self.group_id, self.user_id).await
+    }
+
+    /// See [`join_requests::single::get`]
+    pub async fn join_request(
+        &self,
+    ) -> Result<Option<join_requests::single::JoinRequest>, Box<Error>> {
+        join_requests::single::get(self.jar, self.group_id, self.user_id).await
+    }
+
+    /// See [`join_requests::single::accept`]
+    pub async fn accept_join_request(&self) -> Result<(), Box<Error>> {
+        join_requests::single::accept(self.jar, self.group_id, self.user_id).await
+    }
+
+    /// See [`join_requests::single::decline`]
+    pub async fn decline_join_request(&self) -> Result<(), Box<Error>> {
+        join_requests::single::decline(self.jar, self.group_id, self.user_id).await
+    }
+}
+
+/// A single user, from [`Roblox::user`]
+#[derive(Clone, Copy)]
+pub struct UserHandle<'a> {
+    jar: &'a RequestJar,
+    user_id: UserId,
+}
+
+impl<'a> UserHandle<'a> {
+    pub fn id(&self) -> UserId {
+        self.user_id
+    }
+
+    /// See [`users::user_by_id`]
+    pub async fn get(&self) -> Result<users::User, Box<Error>> {
+        users::user_by_id(self.jar, self.user_id).await
+    }
+
+    /// See [`users::username_history`]
+    pub async fn username_history(&self) -> Result<Vec<users::UsernameHistoryEntry>, Box<Error>> {
+        users::username_history(self.jar, self.user_id).await
+    }
+
+    /// See [`users::validate_display_name_for_user`]
+    pub async fn validate_display_name(
+        &self,
+        display_name: String,
+    ) -> Result<users::ValidateDisplayNameResponseEnum, Box<Error>> {
+        users::validate_display_name_for_user(self.jar, display_name, self.user_id).await
+    }
+
+    /// See [`groups::user_memberships`]
+    pub async fn groups(&self) -> Result<Vec<groups::UserMembershipsGroupItem>, Box<Error>> {
+        groups::user_memberships(self.jar, self.user_id).await
+    }
+
+    /// See [`groups::primary_group`]
+    pub async fn primary_group(&self) -> Result<groups::PrimaryGroupResponse, Box<Error>> {
+        groups::primary_group(self.jar, self.user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mock::{
+            testing::{mock_jar, paged_members_server},
+            MockResponse,
+        },
+        util::{
+            hosts::Domain,
+            ids::{GroupId, RoleId, UserId},
+        },
+        Roblox,
+    };
+
+    #[tokio::test]
+    async fn client_facade() {
+        let (_server, jar) = mock_jar().await;
+        let client = Roblox::from_jar(jar);
+
+        let group = client.group(GroupId(7370273)).get().await.unwrap();
+        let free = crate::groups::group_by_id(client.jar(), GroupId(7370273))
+            .await
+            .unwrap();
+        assert_eq!(group.id, free.id);
+        assert_eq!(group.name, free.name);
+
+        let user = client.users().whoami().await.unwrap();
+        assert_eq!(
+            user.id,
+            crate::users::whoami(client.jar()).await.unwrap().id
+        );
+
+        let user = client.user(UserId(375760054)).get().await.unwrap();
+        assert_eq!(user.name, "piano1029");
+    }
+
+    #[tokio::test]
+    async fn client_handles() {
+        use futures::TryStreamExt;
+
+        let server = paged_members_server().await;
+        server.mock(
+            Domain::Groups,
+            "PATCH",
+            "/v1/groups/1/users/4",
+            MockResponse::json("{}"),
+        );
+        let client = Roblox::from_jar(server.jar().await);
+        let group = client.group(GroupId(1));
+
+        let members = group
+            .members()
+            .stream(None)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(members.len(), 5);
+
+        group.member(UserId(4)).set_role(RoleId(12)).await.unwrap();
+
+        let requests = server.requests();
+        let request = requests.last().unwrap();
+        assert_eq!(request.method, "PATCH");
+        assert_eq!(request.path, "/groups/v1/groups/1/users/4");
+        assert_eq!(request.body, r#"{"roleId":12}"#);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::util::{jar::RequestJar, responses::DataWrapper, Error};
+use crate::util::{hosts::Domain, ids::AssetId, jar::RequestJar, responses::DataWrapper, Error};
 
 use super::{ThumbnailFormat, ThumbnailResponse, ThumbnailReturnPolicy, ThumbnailSize};
 
@@ -14,28 +14,34 @@ use super::{ThumbnailFormat, ThumbnailResponse, ThumbnailReturnPolicy, Thumbnail
 /// - 10: Circular thumbnail requests are not allowed
 pub async fn get_thumbnails(
     jar: &RequestJar,
-    asset_ids: Vec<i64>,
+    asset_ids: Vec<AssetId>,
     thumbnail_size: Option<ThumbnailSize>,
     thumbnail_return_policy: Option<ThumbnailReturnPolicy>,
     thumbnail_format: Option<ThumbnailFormat>,
     is_circular: bool,
-) -> Result<ThumbnailResponse, Box<Error>> {
-    let url = format!(
-        "https://thumbnails.roblox.com/v1/assets?assetIds={}&size={}&format={}&returnPolicy={}&isCircular={}",
-        asset_ids
-            .iter()
-            .map(|id| id.to_string())
-            .collect::<Vec<String>>()
-            .join(","),
-        thumbnail_size.unwrap_or(ThumbnailSize::Size30x30).string(),
-        thumbnail_format.unwrap_or(ThumbnailFormat::Png).string(),
-        thumbnail_return_policy
-            .unwrap_or(ThumbnailReturnPolicy::AutoGenerated)
-            .string(),
-            is_circular
-    );
+) -> Result<Vec<ThumbnailResponse>, Box<Error>> {
+    let url = jar
+        .url(Domain::Thumbnails, "/v1/assets")
+        .query_list("assetIds", &asset_ids)
+        .query(
+            "size",
+            thumbnail_size.unwrap_or(ThumbnailSize::Size30x30).string(),
+        )
+        .query(
+            "format",
+            thumbnail_format.unwrap_or(ThumbnailFormat::Png).string(),
+        )
+        .query(
+            "returnPolicy",
+            thumbnail_return_policy
+                .unwrap_or(ThumbnailReturnPolicy::AutoGenerated)
+                .string(),
+        )
+        .query("isCircular", is_circular);
 
-    let response = jar.get_json::<DataWrapper<ThumbnailResponse>>(&url).await?;
+    let response = jar
+        .get_json::<DataWrapper<Vec<ThumbnailResponse>>>(&url.build())
+        .await?;
 
     Ok(response.data)
 }
@@ -46,14 +52,13 @@ pub async fn get_thumbnails(
 /// - 4: The requested Ids are invalid, of an invalid type or missing.
 pub async fn get_3d_thumbnail(
     jar: &RequestJar,
-    asset_id: i64,
+    asset_id: AssetId,
 ) -> Result<ThumbnailResponse, Box<Error>> {
-    let url = format!(
-        "https://thumbnails.roblox.com/v1/asset-thumbnail-3d?assetId={}",
-        asset_id
-    );
+    let url = jar
+        .url(Domain::Thumbnails, "/v1/asset-thumbnail-3d")
+        .query("assetId", asset_id);
 
-    Ok(jar.get_json::<ThumbnailResponse>(&url).await?)
+    jar.get_json::<ThumbnailResponse>(&url.build()).await
 }
 
 /// Retrieves an animated thumbnail for a single asset.
@@ -62,12 +67,11 @@ pub async fn get_3d_thumbnail(
 /// - 4: The requested Ids are invalid, of an invalid type or missing.
 pub async fn get_animated_thumbnail(
     jar: &RequestJar,
-    asset_id: i64,
+    asset_id: AssetId,
 ) -> Result<ThumbnailResponse, Box<Error>> {
-    let url = format!(
-        "https://thumbnails.roblox.com/v1/asset-thumbnail-animated?assetId={}",
-        asset_id
-    );
+    let url = jar
+        .url(Domain::Thumbnails, "/v1/asset-thumbnail-animated")
+        .query("assetId", asset_id);
 
-    Ok(jar.get_json::<ThumbnailResponse>(&url).await?)
+    jar.get_json::<ThumbnailResponse>(&url.build()).await
 }
@@ -1,4 +1,4 @@
-use crate::util::{jar::RequestJar, responses::DataWrapper, Error};
+use crate::util::{hosts::Domain, ids::BadgeId, jar::RequestJar, responses::DataWrapper, Error};
 
 use super::ThumbnailResponse;
 
@@ -12,20 +12,17 @@ use super::ThumbnailResponse;
 /// - 10: Circular thumbnail requests are not allowed
 pub async fn get_badge_thumbnails(
     jar: &RequestJar,
-    badge_ids: Vec<i64>,
+    badge_ids: Vec<BadgeId>,
     is_circular: bool,
-) -> Result<ThumbnailResponse, Box<Error>> {
-    let url = format!(
-        "https://thumbnails.roblox.com/v1/assets?badgeIds={}&isCircular={}",
-        badge_ids
-            .iter()
-            .map(|id| id.to_string())
-            .collect::<Vec<String>>()
-            .join(","),
-        is_circular
-    );
+) -> Result<Vec<ThumbnailResponse>, Box<Error>> {
+    let url = jar
+        .url(Domain::Thumbnails, "/v1/badges/icons")
+        .query_list("badgeIds", &badge_ids)
+        .query("isCircular", is_circular);
 
-    let response = jar.get_json::<DataWrapper<ThumbnailResponse>>(&url).await?;
+    let response = jar
+        .get_json::<DataWrapper<Vec<ThumbnailResponse>>>(&url.build())
+        .await?;
 
     Ok(response.data)
 }
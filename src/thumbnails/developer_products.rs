@@ -1,4 +1,6 @@
-use crate::util::{jar::RequestJar, responses::DataWrapper, Error};
+use crate::util::{
+    hosts::Domain, ids::DeveloperProductId, jar::RequestJar, responses::DataWrapper, Error,
+};
 
 use super::{ThumbnailFormat, ThumbnailResponse, ThumbnailSize};
 
@@ -12,24 +14,27 @@ use super::{ThumbnailFormat, ThumbnailResponse, ThumbnailSize};
 /// - 10: Circular thumbnail requests are not allowed
 pub async fn get_developer_product_icons(
     jar: &RequestJar,
-    developer_product_ids: Vec<i64>,
+    developer_product_ids: Vec<DeveloperProductId>,
     thumbnail_size: Option<ThumbnailSize>,
     thumbnail_format: Option<ThumbnailFormat>,
     is_circular: bool,
-) -> Result<ThumbnailResponse, Box<Error>> {
-    let url = format!(
-        "https://thumbnails.roblox.com/v1/developer-products/icons?developerProductIds={}&size={}&format={}&isCircular={}",
-        developer_product_ids
-            .iter()
-            .map(|id| id.to_string())
-            .collect::<Vec<String>>()
-            .join(","),
-             thumbnail_size.unwrap_or(ThumbnailSize::Size30x30).string(),
-        thumbnail_format.unwrap_or(ThumbnailFormat::Png).string(),
-        is_circular
-    );
+) -> Result<Vec<ThumbnailResponse>, Box<Error>> {
+    let url = jar
+        .url(Domain::Thumbnails, "/v1/developer-products/icons")
+        .query_list("developerProductIds", &developer_product_ids)
+        .query(
+            "size",
+            thumbnail_size.unwrap_or(ThumbnailSize::Size30x30).string(),
+        )
+        .query(
+            "format",
+            thumbnail_format.unwrap_or(ThumbnailFormat::Png).string(),
+        )
+        .query("isCircular", is_circular);
 
-    let response = jar.get_json::<DataWrapper<ThumbnailResponse>>(&url).await?;
+    let response = jar
+        .get_json::<DataWrapper<Vec<ThumbnailResponse>>>(&url.build())
+        .await?;
 
     Ok(response.data)
 }
@@ -1,4 +1,4 @@
-use crate::util::{jar::RequestJar, responses::DataWrapper, Error};
+use crate::util::{hosts::Domain, ids::GamePassId, jar::RequestJar, responses::DataWrapper, Error};
 
 use super::{ThumbnailFormat, ThumbnailResponse, ThumbnailSize};
 
@@ -12,24 +12,27 @@ use super::{ThumbnailFormat, ThumbnailResponse, ThumbnailSize};
 /// - 10: Circular thumbnail requests are not allowed
 pub async fn get_game_pass_icons(
     jar: &RequestJar,
-    game_pass_ids: Vec<i64>,
+    game_pass_ids: Vec<GamePassId>,
     thumbnail_size: Option<ThumbnailSize>,
     thumbnail_format: Option<ThumbnailFormat>,
     is_circular: bool,
-) -> Result<ThumbnailResponse, Box<Error>> {
-    let url = format!(
-        "https://thumbnails.roblox.com/v1/game-passes?gamePassIds={}&size={}&format={}&isCircular={}",
-        game_pass_ids
-            .iter()
-            .map(|id| id.to_string())
-            .collect::<Vec<String>>()
-            .join(","),
-             thumbnail_size.unwrap_or(ThumbnailSize::Size30x30).string(),
-        thumbnail_format.unwrap_or(ThumbnailFormat::Png).string(),
-        is_circular
-    );
+) -> Result<Vec<ThumbnailResponse>, Box<Error>> {
+    let url = jar
+        .url(Domain::Thumbnails, "/v1/game-passes")
+        .query_list("gamePassIds", &game_pass_ids)
+        .query(
+            "size",
+            thumbnail_size.unwrap_or(ThumbnailSize::Size30x30).string(),
+        )
+        .query(
+            "format",
+            thumbnail_format.unwrap_or(ThumbnailFormat::Png).string(),
+        )
+        .query("isCircular", is_circular);
 
-    let response = jar.get_json::<DataWrapper<ThumbnailResponse>>(&url).await?;
+    let response = jar
+        .get_json::<DataWrapper<Vec<ThumbnailResponse>>>(&url.build())
+        .await?;
 
     Ok(response.data)
 }
@@ -1,4 +1,4 @@
-use crate::util::{jar::RequestJar, responses::DataWrapper, Error};
+use crate::util::{hosts::Domain, ids::UniverseId, jar::RequestJar, responses::DataWrapper, Error};
 
 use super::{ThumbnailFormat, ThumbnailResponse, ThumbnailReturnPolicy, ThumbnailSize};
 
@@ -13,26 +13,31 @@ use super::{ThumbnailFormat, ThumbnailResponse, ThumbnailReturnPolicy, Thumbnail
 /// - 10: Circular thumbnail requests are not allowed
 pub async fn get_game_thumbnails(
     jar: &RequestJar,
-    universe_id: i64,
+    universe_id: UniverseId,
     thumbnail_ids: Vec<i64>,
     thumbnail_size: Option<ThumbnailSize>,
     thumbnail_format: Option<ThumbnailFormat>,
     is_circular: bool,
-) -> Result<ThumbnailResponse, Box<Error>> {
-    let url = format!(
-        "https://thumbnails.roblox.com/v1/games/{}/thumbnails?thumbnailIds={}&size={}&format={}&isCircular={}",
-        universe_id,
-        thumbnail_ids
-            .iter()
-            .map(|id| id.to_string())
-            .collect::<Vec<String>>()
-            .join(","),
-             thumbnail_size.unwrap_or(ThumbnailSize::Size30x30).string(),
-        thumbnail_format.unwrap_or(ThumbnailFormat::Png).string(),
-        is_circular
-    );
+) -> Result<Vec<ThumbnailResponse>, Box<Error>> {
+    let url = jar
+        .url(
+            Domain::Thumbnails,
+            &format!("/v1/games/{}/thumbnails", universe_id),
+        )
+        .query_list("thumbnailIds", &thumbnail_ids)
+        .query(
+            "size",
+            thumbnail_size.unwrap_or(ThumbnailSize::Size30x30).string(),
+        )
+        .query(
+            "format",
+            thumbnail_format.unwrap_or(ThumbnailFormat::Png).string(),
+        )
+        .query("isCircular", is_circular);
 
-    let response = jar.get_json::<DataWrapper<ThumbnailResponse>>(&url).await?;
+    let response = jar
+        .get_json::<DataWrapper<Vec<ThumbnailResponse>>>(&url.build())
+        .await?;
 
     Ok(response.data)
 }
@@ -49,28 +54,34 @@ pub async fn get_game_thumbnails(
 /// - 10: Circular thumbnail requests are not allowed
 pub async fn get_game_icons(
     jar: &RequestJar,
-    universe_ids: Vec<i64>,
+    universe_ids: Vec<UniverseId>,
     thumbnail_size: Option<ThumbnailSize>,
     thumbnail_return_policy: Option<ThumbnailReturnPolicy>,
     thumbnail_format: Option<ThumbnailFormat>,
     is_circular: bool,
-) -> Result<ThumbnailResponse, Box<Error>> {
-    let url = format!(
-        "https://thumbnails.roblox.com/v1/games/icons?universeIds={}&size={}&format={}&returnPolicy={}&isCircular={}",
-        universe_ids
-            .iter()
-            .map(|id| id.to_string())
-            .collect::<Vec<String>>()
-            .join(","),
-        thumbnail_size.unwrap_or(ThumbnailSize::Size30x30).string(),
-        thumbnail_format.unwrap_or(ThumbnailFormat::Png).string(),
-        thumbnail_return_policy
-            .unwrap_or(ThumbnailReturnPolicy::AutoGenerated)
-            .string(),
-            is_circular
-    );
+) -> Result<Vec<ThumbnailResponse>, Box<Error>> {
+    let url = jar
+        .url(Domain::Thumbnails, "/v1/games/icons")
+        .query_list("universeIds", &universe_ids)
+        .query(
+            "size",
+            thumbnail_size.unwrap_or(ThumbnailSize::Size30x30).string(),
+        )
+        .query(
+            "format",
+            thumbnail_format.unwrap_or(ThumbnailFormat::Png).string(),
+        )
+        .query(
+            "returnPolicy",
+            thumbnail_return_policy
+                .unwrap_or(ThumbnailReturnPolicy::AutoGenerated)
+                .string(),
+        )
+        .query("isCircular", is_circular);
 
-    let response = jar.get_json::<DataWrapper<ThumbnailResponse>>(&url).await?;
+    let response = jar
+        .get_json::<DataWrapper<Vec<ThumbnailResponse>>>(&url.build())
+        .await?;
 
     Ok(response.data)
 }
@@ -3,8 +3,8 @@ use rspc::Type;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    util::{jar::RequestJar, responses::RobloxResponse},
-    util::{status_codes::status_code_to_error, Error},
+    util::Error,
+    util::{hosts::Domain, ids::UserId, jar::RequestJar, responses::RobloxResponse},
 };
 
 use super::users::whoami;
@@ -34,6 +34,7 @@ pub enum ValidateDisplayNameResponseEnum {
 /// Validates a display name for a new user
 ///
 /// # Error codes
+/// Typed as [`DisplayNameError`](crate::util::error_codes::DisplayNameError).
 ///
 /// - 1: Display name is too short
 /// - 2: Display name is too long
@@ -46,35 +47,29 @@ pub async fn validate_display_name(
     jar: &RequestJar,
     display_name: String,
 ) -> Result<ValidateDisplayNameResponseEnum, Box<Error>> {
-    let url = format!(
-        "https://users.roblox.com/v1/display-names/validate?displayName={}&birthdate=1999-12-31T23:00:00.000Z", // Birthdate is required, but it doesn't really matter what it is.
-        display_name
-    );
-    let response = jar.get(&url).await?;
+    // Birthdate is required, but it doesn't really matter what it is.
+    let url = jar
+        .url(Domain::Users, "/v1/display-names/validate")
+        .query("displayName", display_name)
+        .query("birthdate", "1999-12-31T23:00:00.000Z");
+    let response = jar.get(&url.build()).await?;
     let status = response.status();
 
     match status {
         StatusCode::OK => Ok(ValidateDisplayNameResponseEnum::Success(
-            response
-                .json::<ValidateDisplayNameResponse>()
-                .await
-                .unwrap(),
+            RequestJar::read_json::<ValidateDisplayNameResponse>(response).await?,
         )),
         StatusCode::BAD_REQUEST => Ok(ValidateDisplayNameResponseEnum::Failed(
-            response
-                .json::<ValidateDisplayNameFailedResponse>()
-                .await
-                .unwrap(),
-        )),
-        _ => Err(Box::new(
-            status_code_to_error(status).unwrap_or(Error::Network),
+            RequestJar::read_json::<ValidateDisplayNameFailedResponse>(response).await?,
         )),
+        _ => Err(RequestJar::read_error(response).await),
     }
 }
 
 /// Validates a display name for an existing user
 ///
 /// # Error codes
+/// Typed as [`DisplayNameError`](crate::util::error_codes::DisplayNameError).
 ///
 /// - 1: Display name is too short
 /// - 2: Display name is too long
@@ -83,32 +78,25 @@ pub async fn validate_display_name(
 pub async fn validate_display_name_for_user(
     jar: &RequestJar,
     display_name: String,
-    user_id: i64,
+    user_id: UserId,
 ) -> Result<ValidateDisplayNameResponseEnum, Box<Error>> {
-    let url = format!(
-        "https://users.roblox.com/v1/users/{}/display-names/validate?displayName={}",
-        user_id.to_string(),
-        display_name
-    );
-    let response = jar.get(&url).await?;
+    let url = jar
+        .url(
+            Domain::Users,
+            &format!("/v1/users/{}/display-names/validate", user_id),
+        )
+        .query("displayName", display_name);
+    let response = jar.get(&url.build()).await?;
     let status = response.status();
 
     match status {
         StatusCode::OK => Ok(ValidateDisplayNameResponseEnum::Success(
-            response
-                .json::<ValidateDisplayNameResponse>()
-                .await
-                .unwrap(),
+            RequestJar::read_json::<ValidateDisplayNameResponse>(response).await?,
         )),
         StatusCode::BAD_REQUEST => Ok(ValidateDisplayNameResponseEnum::Failed(
-            response
-                .json::<ValidateDisplayNameFailedResponse>()
-                .await
-                .unwrap(),
-        )),
-        _ => Err(Box::new(
-            status_code_to_error(status).unwrap_or(Error::Network),
+            RequestJar::read_json::<ValidateDisplayNameFailedResponse>(response).await?,
         )),
+        _ => Err(RequestJar::read_error(response).await),
     }
 }
 
@@ -121,6 +109,7 @@ struct SetDisplayNameRequest {
 /// Sets the user's display name
 ///
 /// # Error codes
+/// Typed as [`DisplayNameError`](crate::util::error_codes::DisplayNameError).
 /// - 1: Display name is too short
 /// - 2: Display name is too long
 /// - 3: Display name contains invalid characters
@@ -133,8 +122,9 @@ pub async fn set_display_name(
     let this_user = whoami(jar).await?;
 
     let url = format!(
-        "https://users.roblox.com/v1/users/{}/display-names",
-        this_user.id.to_string()
+        "{}/v1/users/{}/display-names",
+        jar.base_url(Domain::Users),
+        this_user.id
     );
 
     let request = SetDisplayNameRequest { new_display_name };
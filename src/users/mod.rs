@@ -1,6 +1,7 @@
 mod display_names;
 mod username_search;
 mod usernames;
+#[allow(clippy::module_inception)]
 mod users;
 
 pub use display_names::*;
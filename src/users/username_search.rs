@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use crate::{
     util::Error,
     util::{
+        hosts::Domain,
+        ids::UserId,
         jar::RequestJar,
         paging::{get_page, PageLimit},
     },
@@ -14,7 +16,7 @@ use crate::{
 pub struct UsernameSearchEntry {
     pub previous_usernames: Vec<String>,
     pub has_verified_badge: bool,
-    pub id: i64,
+    pub id: UserId,
     pub name: String,
     pub display_name: String,
 }
@@ -29,10 +31,9 @@ pub async fn username_search(
         return Err(Box::new(Error::InvalidPageLimit));
     }
 
-    let url = format!(
-        "https://users.roblox.com/v1/users/search?keyword={}",
-        keyword
-    );
-    let response = get_page::<UsernameSearchEntry>(jar, url.as_str(), limit, None).await?;
+    let url = jar
+        .url(Domain::Users, "/v1/users/search")
+        .query("keyword", keyword);
+    let response = get_page::<UsernameSearchEntry>(jar, url, limit, None).await?;
     Ok(response)
 }
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     util::Error,
-    util::{jar::RequestJar, paging::get_page},
+    util::{hosts::Domain, ids::UserId, jar::RequestJar, paging::get_page},
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
@@ -14,16 +14,17 @@ pub struct UsernameHistoryEntry {
 /// Retrieves the username history for a user
 ///
 /// # Error codes
+/// Typed as [`UserError`](crate::util::error_codes::UserError).
 ///
 /// - 3: The user id is invalid
 pub async fn username_history(
     jar: &RequestJar,
-    user_id: i64,
+    user_id: UserId,
 ) -> Result<Vec<UsernameHistoryEntry>, Box<Error>> {
-    let url = format!(
-        "https://users.roblox.com/v1/users/{}/username-history",
-        user_id
+    let url = jar.url(
+        Domain::Users,
+        &format!("/v1/users/{}/username-history", user_id),
     );
 
-    Ok(get_page(jar, url.as_str(), crate::util::paging::PageLimit::All, None).await?)
+    get_page(jar, url, crate::util::paging::PageLimit::All, None).await
 }
@@ -2,7 +2,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     util::Error,
-    util::{jar::RequestJar, responses::DataWrapper},
+    util::{
+        hosts::Domain, ids::UserId, jar::RequestJar, responses::DataWrapper, timestamp::Timestamp,
+    },
 };
 use rspc::Type;
 
@@ -10,11 +12,11 @@ use rspc::Type;
 #[serde(rename_all = "camelCase")]
 pub struct User {
     pub description: String,
-    pub created: String,
+    pub created: Timestamp,
     pub is_banned: bool,
     pub external_app_display_name: Option<String>,
     pub has_verified_badge: bool,
-    pub id: i64,
+    pub id: UserId,
     pub name: String,
     pub display_name: String,
 }
@@ -23,7 +25,7 @@ pub struct User {
 #[serde(rename_all = "camelCase")]
 pub struct MinimalGroupUser {
     pub has_verified_badge: bool,
-    pub user_id: i64,
+    pub user_id: UserId,
     pub username: String,
     pub display_name: String,
 }
@@ -31,9 +33,10 @@ pub struct MinimalGroupUser {
 /// Gets a user by their user ID
 ///
 /// # Error codes
+/// Typed as [`UserError`](crate::util::error_codes::UserError).
 /// - 3: The user id is invalid
-pub async fn user_by_id(jar: &RequestJar, user_id: i64) -> Result<User, Box<Error>> {
-    let url = format!("https://users.roblox.com/v1/users/{}", user_id);
+pub async fn user_by_id(jar: &RequestJar, user_id: UserId) -> Result<User, Box<Error>> {
+    let url = format!("{}/v1/users/{}", jar.base_url(Domain::Users), user_id);
     let response = jar.get_json::<User>(&url).await?;
     Ok(response)
 }
@@ -41,13 +44,13 @@ pub async fn user_by_id(jar: &RequestJar, user_id: i64) -> Result<User, Box<Erro
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct MinimalAuthenticatedUser {
-    pub id: i64,
+    pub id: UserId,
     pub name: String,
     pub display_name: String,
 }
 
 pub async fn whoami(jar: &RequestJar) -> Result<MinimalAuthenticatedUser, Box<Error>> {
-    let url = "https://users.roblox.com/v1/users/authenticated";
+    let url = format!("{}/v1/users/authenticated", jar.base_url(Domain::Users));
     let response = jar.get_json::<MinimalAuthenticatedUser>(&url).await?;
     Ok(response)
 }
@@ -60,7 +63,10 @@ pub struct AgeBracketResponse {
 
 /// Gets the age bracket of the currently authenticated user
 pub async fn age_bracket(jar: &RequestJar) -> Result<AgeBracketResponse, Box<Error>> {
-    let url = format!("https://users.roblox.com/v1/users/authenticated/age-bracket");
+    let url = format!(
+        "{}/v1/users/authenticated/age-bracket",
+        jar.base_url(Domain::Users)
+    );
     let response = jar.get_json::<AgeBracketResponse>(&url).await?;
     Ok(response)
 }
@@ -73,7 +79,10 @@ pub struct CountryCodeResponse {
 
 /// Gets the country code of the currently authenticated user
 pub async fn country_code(jar: &RequestJar) -> Result<CountryCodeResponse, Box<Error>> {
-    let url = format!("https://users.roblox.com/v1/users/authenticated/country-code");
+    let url = format!(
+        "{}/v1/users/authenticated/country-code",
+        jar.base_url(Domain::Users)
+    );
     let response = jar.get_json::<CountryCodeResponse>(&url).await?;
     Ok(response)
 }
@@ -88,7 +97,10 @@ pub struct RolesResponse {
 /// I have never seen this return anything other than an empty array
 /// Might be useless, don't know
 pub async fn roles(jar: &RequestJar) -> Result<RolesResponse, Box<Error>> {
-    let url = format!("https://users.roblox.com/v1/users/authenticated/roles");
+    let url = format!(
+        "{}/v1/users/authenticated/roles",
+        jar.base_url(Domain::Users)
+    );
     let response = jar.get_json::<RolesResponse>(&url).await?;
     Ok(response)
 }
@@ -98,7 +110,7 @@ pub async fn roles(jar: &RequestJar) -> Result<RolesResponse, Box<Error>> {
 pub struct MinimalBulkUserByUsername {
     pub requested_username: String,
     pub has_verified_badge: bool,
-    pub id: i64,
+    pub id: UserId,
     pub name: String,
     pub display_name: String,
 }
@@ -119,12 +131,13 @@ pub struct BulkUsersByUsernameRequest {
 /// Gets a list of users by their usernames
 ///
 /// # Error codes
+/// Typed as [`UserError`](crate::util::error_codes::UserError).
 /// - 2: Too many usernames
 pub async fn bulk_users_by_username(
     jar: &RequestJar,
     usernames: Vec<String>,
 ) -> Result<Vec<MinimalBulkUserByUsername>, Box<Error>> {
-    let url = format!("https://users.roblox.com/v1/usernames/users");
+    let url = format!("{}/v1/usernames/users", jar.base_url(Domain::Users));
     let request = BulkUsersByUsernameRequest {
         usernames,
         exclude_banned_users: true,
@@ -142,7 +155,7 @@ pub async fn bulk_users_by_username(
 #[serde(rename_all = "camelCase")]
 pub struct MinimalBulkUserById {
     pub has_verified_badge: bool,
-    pub id: i64,
+    pub id: UserId,
     pub name: String,
     pub display_name: String,
 }
@@ -150,7 +163,7 @@ pub struct MinimalBulkUserById {
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct BulkUsersByIdRequest {
-    pub user_ids: Vec<i64>,
+    pub user_ids: Vec<UserId>,
     exclude_banned_users: bool,
 }
 
@@ -163,12 +176,13 @@ pub struct BulkUsersByIdRequest {
 /// Gets a list of users by their ids
 ///
 /// # Error codes
+/// Typed as [`UserError`](crate::util::error_codes::UserError).
 /// - 2: Too many ids
 pub async fn bulk_users_by_id(
     jar: &RequestJar,
-    user_ids: Vec<i64>,
+    user_ids: Vec<UserId>,
 ) -> Result<Vec<MinimalBulkUserById>, Box<Error>> {
-    let url = format!("https://users.roblox.com/v1/users");
+    let url = format!("{}/v1/users", jar.base_url(Domain::Users));
     let request = BulkUsersByIdRequest {
         user_ids,
         exclude_banned_users: true,
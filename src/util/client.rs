@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderValue};
+
+use super::{ConfigError, Error, RequestDetails};
+
+/// The User-Agent requests are sent with unless another one is configured.
+/// Roblox rejects some requests (like fetching a X-CSRF-TOKEN) without a browser User-Agent.
+pub const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/107.0.5304.107 Safari/537.36";
+
+/// Settings for the HTTP client a [`RequestJar`](super::jar::RequestJar) sends requests with.
+///
+/// The client is kept for the lifetime of the jar so connections and TLS sessions are reused,
+/// it's only rebuilt when these settings or the proxy change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientConfig {
+    /// How long connecting to Roblox may take
+    pub connect_timeout: Option<Duration>,
+    /// How long an entire request may take, from connecting until the body is read.
+    /// reqwest has no separate read timeout, so this is what limits slow responses.
+    pub timeout: Option<Duration>,
+    /// How many idle connections are kept open per host
+    pub pool_max_idle_per_host: usize,
+    /// How long idle connections are kept open
+    pub pool_idle_timeout: Option<Duration>,
+    /// Interval of TCP keepalive probes on open connections
+    pub tcp_keepalive: Option<Duration>,
+    /// Talk HTTP/2 right away instead of negotiating it during the TLS handshake
+    pub http2_prior_knowledge: bool,
+    pub user_agent: String,
+    /// Headers sent with every request
+    pub default_headers: HeaderMap,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            connect_timeout: Some(Duration::from_secs(10)),
+            timeout: Some(Duration::from_secs(30)),
+            pool_max_idle_per_host: 32,
+            pool_idle_timeout: Some(Duration::from_secs(90)),
+            tcp_keepalive: Some(Duration::from_secs(60)),
+            http2_prior_knowledge: false,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            default_headers: HeaderMap::new(),
+        }
+    }
+}
+
+/// A proxy requests are sent through, optionally authenticated with basic auth
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyConfig {
+    pub url: String,
+    pub basic_auth: Option<(String, String)>,
+}
+
+impl ProxyConfig {
+    pub fn new(url: &str) -> ProxyConfig {
+        ProxyConfig {
+            url: url.to_string(),
+            basic_auth: None,
+        }
+    }
+
+    pub fn with_basic_auth(mut self, username: &str, password: &str) -> ProxyConfig {
+        self.basic_auth = Some((username.to_string(), password.to_string()));
+        self
+    }
+
+    fn get_reqwest_proxy(&self) -> Result<reqwest::Proxy, Box<Error>> {
+        let invalid = || Box::new(Error::Config(ConfigError::InvalidProxy(self.url.clone())));
+
+        // reqwest accepts almost anything, so check it's an actual url first
+        reqwest::Url::parse(&self.url).map_err(|_| invalid())?;
+        let mut proxy = reqwest::Proxy::all(&self.url).map_err(|_| invalid())?;
+
+        if let Some((username, password)) = &self.basic_auth {
+            proxy = proxy.basic_auth(username, password);
+        }
+
+        Ok(proxy)
+    }
+}
+
+impl ClientConfig {
+    /// Checks the settings without building a client
+    ///
+    /// # Error codes
+    /// - Config: A timeout is zero or the User-Agent isn't a valid header value
+    pub fn validate(&self) -> Result<(), Box<Error>> {
+        for (name, timeout) in [
+            ("connect_timeout", self.connect_timeout),
+            ("timeout", self.timeout),
+        ] {
+            if timeout == Some(Duration::ZERO) {
+                return Err(Box::new(Error::Config(ConfigError::InvalidTimeout(
+                    name.to_string(),
+                ))));
+            }
+        }
+
+        if HeaderValue::from_str(&self.user_agent).is_err() {
+            return Err(Box::new(Error::Config(ConfigError::InvalidUserAgent)));
+        }
+
+        Ok(())
+    }
+
+    /// Builds a client with these settings, sending requests through the proxy if there is one
+    ///
+    /// # Error codes
+    /// - Config: The settings or the proxy are invalid
+    /// - Network: The client couldn't be created, e.g. because TLS couldn't be initialized
+    pub fn build_client(&self, proxy: Option<&ProxyConfig>) -> Result<reqwest::Client, Box<Error>> {
+        self.validate()?;
+
+        let mut builder = reqwest::Client::builder()
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .tcp_keepalive(self.tcp_keepalive)
+            .user_agent(&self.user_agent)
+            .default_headers(self.default_headers.clone());
+
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(proxy.get_reqwest_proxy()?);
+        }
+
+        builder
+            .build()
+            .map_err(|error| Box::new(Error::Network(RequestDetails::from_source(error))))
+    }
+}
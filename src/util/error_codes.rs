@@ -0,0 +1,272 @@
+//! Typed versions of the error codes Roblox responds with.
+//!
+//! Codes are only unique within a family of endpoints (code 2 is an invalid role when ranking,
+//! but an inappropriate search term when searching), so every family has its own enum.
+//! Get them from an error with [`Error::code`](super::Error::code):
+//!
+//! ```no_run
+//! # use rustyroblox::{groups, util::{error_codes::SetRoleError, ids::*, jar::RequestJar}};
+//! # async fn rank(jar: &RequestJar) {
+//! let (group, user, role) = (GroupId(7370273), UserId(375760054), RoleId(47932104));
+//! if let Err(error) = groups::set_role(jar, group, user, role).await {
+//!     match error.code::<SetRoleError>() {
+//!         Some(SetRoleError::CannotChangeOwnRole) => println!("You can't rank yourself"),
+//!         Some(code) => println!("Couldn't rank: {}", code),
+//!         None => println!("Couldn't rank: {}", error),
+//!     }
+//! }
+//! # }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// An enum of the error codes an endpoint family can respond with
+pub trait ErrorCode: Sized {
+    /// Converts a code Roblox responded with, unknown codes are kept as is
+    fn from_code(code: i64) -> Self;
+    /// The code Roblox uses for this error
+    fn code(&self) -> i64;
+    /// Roblox's description of the error, `None` for unknown codes
+    fn message(&self) -> Option<&'static str>;
+}
+
+macro_rules! error_codes {
+    ($(
+        $(#[$meta:meta])*
+        $name:ident {
+            $($variant:ident = $code:literal => $message:literal,)*
+        }
+    )*) => {$(
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        pub enum $name {
+            $(
+                #[doc = $message]
+                $variant,
+            )*
+            /// A code that isn't documented for this endpoint family
+            Unknown(i64),
+        }
+
+        impl ErrorCode for $name {
+            fn from_code(code: i64) -> Self {
+                match code {
+                    $($code => $name::$variant,)*
+                    code => $name::Unknown(code),
+                }
+            }
+
+            fn code(&self) -> i64 {
+                match self {
+                    $($name::$variant => $code,)*
+                    $name::Unknown(code) => *code,
+                }
+            }
+
+            fn message(&self) -> Option<&'static str> {
+                match self {
+                    $($name::$variant => Some($message),)*
+                    $name::Unknown(_) => None,
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self.message() {
+                    Some(message) => write!(f, "{}", message),
+                    None => write!(f, "Unknown error code {}", self.code()),
+                }
+            }
+        }
+    )*};
+}
+
+error_codes! {
+    /// Errors from getting or changing a group's information, settings and audit log
+    GroupError {
+        InvalidGroup = 1 => "Group is invalid or does not exist.",
+        DescriptionTooLong = 18 => "The description is too long.",
+        InsufficientPermissions = 23 => "Insufficient permissions to complete the request.",
+        EmptyDescription = 29 => "Your group description was empty.",
+        ServiceUnavailable = 31 => "Service is currently unavailable.",
+    }
+
+    /// Errors from changing a member's role or removing them from the group
+    SetRoleError {
+        InvalidGroup = 1 => "The group is invalid or does not exist.",
+        InvalidRole = 2 => "The roleset is invalid or does not exist.",
+        InvalidUser = 3 => "The user is invalid or does not exist.",
+        CannotManageMember = 4 => "You do not have permission to manage this member.",
+        TemporarilyUnavailable = 18 => "The operation is temporarily unavailable. Please try again later.",
+        CannotChangeOwnRole = 23 => "You cannot change your own role.",
+        TwoStepVerificationRequired = 25 => "2-Step Verification is required to make further transactions.",
+        SameRole = 26 => "You cannot change the user's role to the same role.",
+    }
+
+    /// Errors from getting a group's roles and the members on them
+    RoleError {
+        InvalidGroup = 1 => "The group is invalid or does not exist.",
+        InvalidRole = 2 => "The roleset is invalid or does not exist.",
+        InvalidUser = 3 => "The user is invalid or does not exist.",
+    }
+
+    /// Errors from getting or changing a role's permissions
+    PermissionsError {
+        InvalidGroup = 1 => "Group is invalid or does not exist.",
+        InvalidRole = 2 => "The roleset is invalid or does not exist.",
+        NotAuthorized = 3 => "You are not authorized to view/edit permissions for this role.",
+        CannotModifyRole = 4 => "This role's permissions can not be modified.",
+    }
+
+    /// Errors from handling join requests
+    JoinRequestError {
+        InvalidGroup = 1 => "The group is invalid or does not exist.",
+        InvalidUser = 3 => "The user is invalid or does not exist.",
+        CannotManageMember = 4 => "You do not have permission to manage this member.",
+        TooManyGroups = 6 => "You are already in the maximum number of groups.",
+        TemporarilyUnavailable = 18 => "The operation is temporarily unavailable. Please try again later.",
+        InsufficientPermissions = 19 => "You have insufficient permissions for this request.",
+        InvalidJoinRequest = 20 => "The group join request is invalid.",
+    }
+
+    /// Errors from listing a group's relationships and relationship requests
+    RelationshipListError {
+        InvalidGroup = 1 => "Group is invalid or does not exist.",
+        InvalidRelationshipType = 4 => "Group relationship type or request type is invalid.",
+        InsufficientPermissions = 5 => "You don't have permission to manage this group's relationships.",
+        InvalidPagination = 8 => "Invalid or missing pagination parameters",
+    }
+
+    /// Errors from sending, accepting, declining or removing group relationships
+    RelationshipError {
+        InvalidRelationshipType = 1 => "Group relationship type or request type is invalid.",
+        InvalidGroup = 2 => "Invalid group.",
+        InvalidTargetGroup = 3 => "Target group is invalid or does not exist.",
+        CannotRelateToSelf = 4 => "Your group cannot establish a relationship with itself.",
+        EnemiesNotAllowed = 5 => "Your group does not allow enemy declarations.",
+        TargetEnemiesNotAllowed = 6 => "Other group does not allow enemy declarations.",
+        AlreadyRelated = 7 => "Your group already has a relationship with the target group.",
+        Blocked = 8 => "You are blocked from communicating with this user.",
+        InsufficientPermissions = 9 => "Insufficient permissions.",
+        RelationshipDoesNotExist = 11 => "Relationship does not exist.",
+    }
+
+    /// Errors from searching groups
+    SearchError {
+        InappropriateTerm = 2 => "Search term not appropriate for Roblox.",
+        EmptyTerm = 3 => "Search term was left empty.",
+        InvalidTermLength = 4 => "Search terms can be 2 to 50 characters long.",
+        NoLocalizedCategory = 5 => "No Localized Version of group search category exists",
+    }
+
+    /// Errors from reading a group's payouts
+    PayoutError {
+        InvalidGroup = 1 => "Group is invalid or does not exist.",
+        InsufficientPermissions = 9 => "You don't have permission to view this group's payouts.",
+    }
+
+    /// Errors from reading or deleting group wall posts
+    WallError {
+        InvalidGroup = 1 => "The group is invalid or does not exist.",
+        InsufficientPermissions = 2 => "You do not have permission to access this group wall.",
+        InvalidPost = 3 => "The group wall post id is invalid or does not exist.",
+        InvalidUser = 6 => "The user specified is invalid or does not exist.",
+    }
+
+    /// Errors from changing or claiming a group's ownership
+    OwnershipError {
+        InvalidGroup = 1 => "The group is invalid or does not exist.",
+        InvalidUser = 3 => "The user is invalid or does not exist.",
+        CannotClaim = 11 => "You are not authorized to claim this group.",
+        AlreadyOwned = 12 => "This group already has an owner.",
+        TooManyClaimAttempts = 13 => "Too many attempts to claim groups. Please try again later.",
+        NotAMember = 15 => "User is not a member of the group.",
+        PremiumRequired = 16 => "The user does not have the necessary level of premium membership.",
+        NotAuthorized = 17 => "You are not authorized to change the owner of this group.",
+        TemporarilyUnavailable = 18 => "The operation is temporarily unavailable. Please try again later.",
+        TwoStepVerificationRequired = 25 => "2-Step Verification is required to make further transactions.",
+    }
+
+    /// Errors from reading or changing a group's social links
+    SocialLinkError {
+        InvalidGroup = 1 => "The group is invalid or does not exist.",
+        InsufficientPermissions = 2 => "You do not have permission to configure this social link.",
+        TitleTooLong = 3 => "The social link title is too long.",
+        EmptyTitle = 4 => "The social link title cannot be empty.",
+        EmptyUrl = 5 => "The social link url cannot be empty.",
+        InvalidUrl = 6 => "The social link url was improperly formatted.",
+        NullRequest = 7 => "The request was null.",
+        NotFound = 8 => "The requested group or social link was not found.",
+        InvalidType = 9 => "The social link type is invalid.",
+        NotForGroup = 10 => "The social link is not for a group.",
+        Unavailable = 11 => "Social links cannot be processed as this time.",
+        TitleModerated = 12 => "The social link title was moderated.",
+        AgeRestricted = 13 => "Only users who are over thirteen years of age may view or edit social links.",
+        GroupMismatch = 15 => "The social link id doesn't match the group id.",
+        AlreadyExists = 16 => "A social link with this type already exists on this group.",
+    }
+
+    /// Errors from validating or changing display names
+    DisplayNameError {
+        TooShort = 1 => "Display name is too short",
+        TooLong = 2 => "Display name is too long",
+        InvalidCharacters = 3 => "Display name contains invalid characters",
+        Moderated = 4 => "Display name has been moderated",
+        Throttled = 5 => "Display name updates for this user have been throttled",
+        BirthdateRequired = 6 => "Request must contain a birthdate",
+    }
+
+    /// Errors from getting users
+    UserError {
+        TooManyUsers = 2 => "Too many ids or usernames in request.",
+        InvalidUser = 3 => "The user id is invalid",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mock::{testing::mock_jar, MockResponse},
+        util::{
+            hosts::Domain,
+            ids::{GroupId, RoleId, UserId},
+        },
+    };
+
+    #[tokio::test]
+    async fn typed_error_codes() {
+        use super::{ErrorCode, SearchError, SetRoleError};
+
+        let (server, jar) = mock_jar().await;
+        server.script(
+            Domain::Groups,
+            "PATCH",
+            "/v1/groups/7370273/users/4205503041",
+            vec![MockResponse::errors(
+                400,
+                &[(23, "You cannot change your own role.")],
+            )],
+        );
+
+        let error =
+            crate::groups::set_role(&jar, GroupId(7370273), UserId(4205503041), RoleId(47932104))
+                .await
+                .unwrap_err();
+        assert_eq!(
+            error.code::<SetRoleError>(),
+            Some(SetRoleError::CannotChangeOwnRole)
+        );
+        assert_eq!(SetRoleError::CannotChangeOwnRole.code(), 23);
+        assert_eq!(
+            SetRoleError::from_code(26).to_string(),
+            "You cannot change the user's role to the same role."
+        );
+
+        // Codes mean different things on different endpoints
+        assert_eq!(SearchError::from_code(2), SearchError::InappropriateTerm);
+        assert_eq!(SetRoleError::from_code(99), SetRoleError::Unknown(99));
+        assert_eq!(SetRoleError::Unknown(99).message(), None);
+    }
+}
@@ -0,0 +1,391 @@
+//! Exports of paged endpoints that are too large to go through in one go.
+//!
+//! An [`Export`] writes every page to a sink, and saves a [`Checkpoint`] with the cursor of
+//! the next page after each one. When the process crashes or the export is stopped, it
+//! continues from the checkpoint the next time it's started:
+//!
+//! ```no_run
+//! # use rustyroblox::{groups, util::{export::{Export, JsonLinesSink}, ids::GroupId, jar::RequestJar}};
+//! # async fn census(jar: &RequestJar) -> Result<(), Box<rustyroblox::util::Error>> {
+//! let mut sink = JsonLinesSink::open("members.jsonl")?;
+//! let checkpoint = Export::new(groups::members_stream(jar, GroupId(7370273), None), "members.checkpoint")?
+//!     .run(&mut sink)
+//!     .await?;
+//! println!("Exported {} members", checkpoint.items);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The checkpoint is saved after the sink has stored a page, so a crash in between writes
+//! that page again when the export continues.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{paging::Paginator, Error, RequestDetails};
+
+/// How far an export got
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    /// The url being exported, so a checkpoint isn't used to continue a different export
+    pub url: String,
+    /// The cursor of the next page, `None` before the first page has been exported
+    pub cursor: Option<String>,
+    /// How many items have been written to the sink, over every run
+    pub items: u64,
+    /// How many pages have been written to the sink, over every run
+    pub pages: u64,
+    /// Whether every page has been exported
+    pub finished: bool,
+}
+
+impl Checkpoint {
+    /// Reads a checkpoint, `None` if there is no file yet
+    ///
+    /// # Error codes
+    /// - Io: The file couldn't be read or isn't a checkpoint
+    pub fn load(path: impl AsRef<Path>) -> Result<Option<Checkpoint>, Box<Error>> {
+        load_json(path.as_ref())
+    }
+
+    /// Writes the checkpoint to a temporary file first and then moves it into place,
+    /// so a crash while saving doesn't leave a broken checkpoint behind
+    ///
+    /// # Error codes
+    /// - Io: The file couldn't be written
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<Error>> {
+        save_json_atomically(path.as_ref(), self)
+    }
+}
+
+/// Where an export writes its items to
+pub trait ExportSink<T> {
+    /// Stores a page of items. The checkpoint is saved as soon as this returns,
+    /// so the items should be stored durably by then.
+    fn write_page(&mut self, items: &[T]) -> Result<(), Box<Error>>;
+}
+
+impl<T: Clone> ExportSink<T> for Vec<T> {
+    fn write_page(&mut self, items: &[T]) -> Result<(), Box<Error>> {
+        self.extend_from_slice(items);
+        Ok(())
+    }
+}
+
+/// Appends every item to a file as a line of JSON
+pub struct JsonLinesSink {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl JsonLinesSink {
+    /// Opens a file to append to, creating it if it doesn't exist yet
+    ///
+    /// # Error codes
+    /// - Io: The file couldn't be opened
+    pub fn open(path: impl AsRef<Path>) -> Result<JsonLinesSink, Box<Error>> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|error| io_error(&path, error))?;
+
+        Ok(JsonLinesSink {
+            path,
+            writer: BufWriter::new(file),
+        })
+    }
+}
+
+impl<T: Serialize> ExportSink<T> for JsonLinesSink {
+    fn write_page(&mut self, items: &[T]) -> Result<(), Box<Error>> {
+        let path = &self.path;
+        for item in items {
+            serde_json::to_writer(&mut self.writer, item)
+                .map_err(|error| io_error(path, error.into()))?;
+            self.writer
+                .write_all(b"\n")
+                .map_err(|error| io_error(path, error))?;
+        }
+
+        self.writer.flush().map_err(|error| io_error(path, error))?;
+        self.writer
+            .get_ref()
+            .sync_data()
+            .map_err(|error| io_error(path, error))
+    }
+}
+
+/// Writes every item of a paged endpoint to a sink, saving a checkpoint after each page
+pub struct Export<'a, T> {
+    paginator: Paginator<'a, T>,
+    path: PathBuf,
+    checkpoint: Checkpoint,
+    max_pages: Option<u64>,
+}
+
+impl<'a, T> Export<'a, T>
+where
+    T: DeserializeOwned + Send + 'a,
+{
+    /// Prepares an export, continuing from the checkpoint if one has been saved before.
+    /// An item cap on the paginator counts the items of every run, so it ends the export
+    /// once that many items have been exported in total.
+    ///
+    /// # Error codes
+    /// - Io: The checkpoint couldn't be read, or it belongs to an export of another url
+    pub fn new(paginator: Paginator<'a, T>, path: impl AsRef<Path>) -> Result<Self, Box<Error>> {
+        let path = path.as_ref().to_path_buf();
+        let url = paginator.url().build();
+
+        let checkpoint = match Checkpoint::load(&path)? {
+            Some(checkpoint) if checkpoint.url != url => {
+                return Err(io_error(
+                    &path,
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("the checkpoint is for an export of {}", checkpoint.url),
+                    ),
+                ));
+            }
+            Some(checkpoint) => checkpoint,
+            None => Checkpoint {
+                url,
+                ..Default::default()
+            },
+        };
+
+        Ok(Export {
+            paginator: paginator
+                .cursor(checkpoint.cursor.clone())
+                .reduce_max_items(checkpoint.items as usize),
+            path,
+            checkpoint,
+            max_pages: None,
+        })
+    }
+
+    /// Stops after this many pages, so a large export can be spread over several runs
+    pub fn max_pages(mut self, max_pages: u64) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// Where the export is, as it was loaded or last saved
+    pub fn checkpoint(&self) -> &Checkpoint {
+        &self.checkpoint
+    }
+
+    /// Exports pages until every page has been exported or `max_pages` is reached.
+    /// When a request fails the error is returned, and the next run continues at the
+    /// page that failed.
+    ///
+    /// # Error codes
+    /// - Io: The checkpoint couldn't be saved
+    /// - Anything the endpoint or the sink returns
+    pub async fn run(mut self, sink: &mut impl ExportSink<T>) -> Result<Checkpoint, Box<Error>> {
+        let mut pages = 0;
+
+        while !self.checkpoint.finished {
+            if self.max_pages.is_some_and(|max_pages| pages >= max_pages) {
+                break;
+            }
+
+            let page = self.paginator.next_page().await?;
+            if let Some(items) = &page {
+                sink.write_page(items)?;
+                self.checkpoint.items += items.len() as u64;
+                self.checkpoint.pages += 1;
+                pages += 1;
+            }
+
+            self.checkpoint.cursor = self.paginator.next_page_cursor().map(str::to_string);
+            self.checkpoint.finished = self.paginator.is_finished();
+            self.checkpoint.save(&self.path)?;
+        }
+
+        Ok(self.checkpoint)
+    }
+}
+
+/// Reads a JSON file, `None` if it doesn't exist
+pub(crate) fn load_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, Box<Error>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(io_error(path, error)),
+    };
+
+    serde_json::from_str(&contents)
+        .map(Some)
+        .map_err(|error| io_error(path, error.into()))
+}
+
+/// Writes a JSON file next to the path first and then moves it into place
+pub(crate) fn save_json_atomically<T: Serialize>(path: &Path, value: &T) -> Result<(), Box<Error>> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = PathBuf::from(temporary);
+
+    let contents = serde_json::to_vec(value).map_err(|error| io_error(path, error.into()))?;
+    fs::write(&temporary, contents).map_err(|error| io_error(&temporary, error))?;
+    fs::rename(&temporary, path).map_err(|error| io_error(path, error))
+}
+
+pub(crate) fn io_error(path: &Path, error: io::Error) -> Box<Error> {
+    let error = io::Error::new(error.kind(), format!("{}: {}", path.display(), error));
+    Box::new(Error::Io(RequestDetails::from_source(error)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Checkpoint, Error, Export, JsonLinesSink};
+    use crate::{
+        mock::{
+            testing::{paged_members_server, temp_path},
+            MockResponse,
+        },
+        util::{
+            hosts::Domain,
+            ids::{GroupId, UserId},
+        },
+    };
+
+    #[tokio::test]
+    async fn export_resumes_from_checkpoint() {
+        let server = paged_members_server().await;
+        let jar = server.jar().await;
+        let path = temp_path("export_resumes_from_checkpoint.checkpoint");
+
+        // The first run stops after a page
+        let mut members = Vec::new();
+        let checkpoint = Export::new(crate::groups::members_stream(&jar, GroupId(1), None), &path)
+            .unwrap()
+            .max_pages(1)
+            .run(&mut members)
+            .await
+            .unwrap();
+        assert_eq!(members.len(), 2);
+        assert_eq!(checkpoint.cursor, Some("page2".to_string()));
+        assert_eq!(checkpoint.items, 2);
+        assert!(!checkpoint.finished);
+        assert_eq!(Checkpoint::load(&path).unwrap(), Some(checkpoint));
+
+        // A page fails, the checkpoint stays at the page before it
+        server.script(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/users?cursor=page3",
+            vec![MockResponse::errors(500, &[(0, "InternalServerError")])],
+        );
+        let error = Export::new(crate::groups::members_stream(&jar, GroupId(1), None), &path)
+            .unwrap()
+            .run(&mut members)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.status(),
+            Some(reqwest::StatusCode::INTERNAL_SERVER_ERROR)
+        );
+        let checkpoint = Checkpoint::load(&path).unwrap().unwrap();
+        assert_eq!(checkpoint.cursor, Some("page3".to_string()));
+        assert_eq!(checkpoint.items, 4);
+
+        // The next run picks up the failed page
+        let checkpoint = Export::new(crate::groups::members_stream(&jar, GroupId(1), None), &path)
+            .unwrap()
+            .run(&mut members)
+            .await
+            .unwrap();
+        let user_ids = members
+            .iter()
+            .map(|member| member.user.user_id)
+            .collect::<Vec<_>>();
+        assert_eq!(user_ids, [1, 2, 3, 4, 5].map(UserId));
+        assert_eq!(checkpoint.items, 5);
+        assert_eq!(checkpoint.pages, 3);
+        assert!(checkpoint.finished);
+
+        // A finished export doesn't send any requests
+        let requests = server.requests().len();
+        Export::new(crate::groups::members_stream(&jar, GroupId(1), None), &path)
+            .unwrap()
+            .run(&mut members)
+            .await
+            .unwrap();
+        assert_eq!(server.requests().len(), requests);
+
+        // And the checkpoint can't be used for another export
+        let error = Export::new(crate::groups::members_stream(&jar, GroupId(2), None), &path)
+            .err()
+            .unwrap();
+        assert!(matches!(*error, Error::Io(_)));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn export_item_cap_spans_runs() {
+        let server = paged_members_server().await;
+        let jar = server.jar().await;
+        let path = temp_path("export_item_cap_spans_runs.checkpoint");
+        let members = || crate::groups::members_stream(&jar, GroupId(1), None).max_items(3);
+
+        let mut exported = Vec::new();
+        let checkpoint = Export::new(members(), &path)
+            .unwrap()
+            .max_pages(1)
+            .run(&mut exported)
+            .await
+            .unwrap();
+        assert_eq!(checkpoint.items, 2);
+        assert!(!checkpoint.finished);
+
+        // The second run only has one item of the cap left
+        let checkpoint = Export::new(members(), &path)
+            .unwrap()
+            .run(&mut exported)
+            .await
+            .unwrap();
+        assert_eq!(exported.len(), 3);
+        assert_eq!(checkpoint.items, 3);
+        assert!(checkpoint.finished);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn export_to_json_lines() {
+        let server = paged_members_server().await;
+        let jar = server.jar().await;
+        let path = temp_path("export_to_json_lines.checkpoint");
+        let output = temp_path("export_to_json_lines.jsonl");
+
+        let mut sink = JsonLinesSink::open(&output).unwrap();
+        Export::new(crate::groups::members_stream(&jar, GroupId(1), None), &path)
+            .unwrap()
+            .run(&mut sink)
+            .await
+            .unwrap();
+
+        let lines = std::fs::read_to_string(&output).unwrap();
+        let members = lines
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<crate::groups::GroupMembershipUserRole>(line).unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(members.len(), 5);
+        assert_eq!(members[4].user.username, "user5");
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&output).unwrap();
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use strum_macros::Display;
+
+/// A Roblox web API domain (e.g. `groups.roblox.com`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display)]
+pub enum Domain {
+    Auth,
+    Groups,
+    Thumbnails,
+    Users,
+    Www,
+}
+
+impl Domain {
+    /// Every known domain
+    pub const ALL: [Domain; 5] = [
+        Domain::Auth,
+        Domain::Groups,
+        Domain::Thumbnails,
+        Domain::Users,
+        Domain::Www,
+    ];
+
+    /// The subdomain of roblox.com this domain lives on
+    pub fn get_subdomain(&self) -> &'static str {
+        match self {
+            Domain::Auth => "auth",
+            Domain::Groups => "groups",
+            Domain::Thumbnails => "thumbnails",
+            Domain::Users => "users",
+            Domain::Www => "www",
+        }
+    }
+
+    /// The base url Roblox serves this domain on
+    pub fn get_default_base_url(&self) -> String {
+        format!("https://{}.roblox.com", self.get_subdomain())
+    }
+}
+
+/// Maps every Roblox domain to the base url requests for it are sent to.
+///
+/// By default every domain points at Roblox itself, but they can be pointed at a
+/// mock server, an egress proxy or a staging mirror instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hosts {
+    base_urls: HashMap<Domain, String>,
+}
+
+impl Default for Hosts {
+    fn default() -> Self {
+        Hosts {
+            base_urls: Domain::ALL
+                .iter()
+                .map(|domain| (*domain, domain.get_default_base_url()))
+                .collect(),
+        }
+    }
+}
+
+impl Hosts {
+    /// Creates a host map pointing at Roblox
+    pub fn new() -> Hosts {
+        Hosts::default()
+    }
+
+    /// Creates a host map serving every domain from a single base url, using the
+    /// subdomain as a path prefix (`{base}/groups`, `{base}/users`, ...).
+    pub fn with_base_url(base_url: &str) -> Hosts {
+        let base_url = base_url.trim_end_matches('/');
+        Hosts {
+            base_urls: Domain::ALL
+                .iter()
+                .map(|domain| (*domain, format!("{}/{}", base_url, domain.get_subdomain())))
+                .collect(),
+        }
+    }
+
+    /// Gets the base url for a domain, without a trailing slash
+    pub fn get(&self, domain: Domain) -> &str {
+        // Every domain is inserted on creation and can't be removed
+        &self.base_urls[&domain]
+    }
+
+    /// Overrides the base url for a domain
+    pub fn set(&mut self, domain: Domain, base_url: &str) {
+        self.base_urls
+            .insert(domain, base_url.trim_end_matches('/').to_string());
+    }
+
+    /// Finds the domain a url belongs to, returning it with the path relative to the domain
+    pub fn resolve<'a>(&self, url: &'a str) -> Option<(Domain, &'a str)> {
+        self.base_urls.iter().find_map(|(domain, base_url)| {
+            let path = url.strip_prefix(base_url.as_str())?;
+            if path.is_empty() || path.starts_with('/') || path.starts_with('?') {
+                Some((*domain, path))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Domain, Hosts};
+
+    #[test]
+    fn hosts_resolve() {
+        let hosts = Hosts::with_base_url("http://127.0.0.1:8080");
+        assert_eq!(
+            hosts.resolve("http://127.0.0.1:8080/groups/v1/groups/7370273"),
+            Some((Domain::Groups, "/v1/groups/7370273"))
+        );
+        assert_eq!(hosts.resolve("http://127.0.0.1:8080/groupsx/v1"), None);
+        assert_eq!(hosts.resolve("https://example.com/v1"), None);
+    }
+}
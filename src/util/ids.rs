@@ -0,0 +1,175 @@
+//! Ids of the different things on Roblox.
+//!
+//! They are all numbers, but each kind has its own type so a role id can't be passed where
+//! a user id is expected, or a rank where a role id is:
+//!
+//! ```no_run
+//! # use rustyroblox::{groups, util::{ids::{GroupId, RoleId, UserId}, jar::RequestJar}};
+//! # async fn rank(jar: &RequestJar) {
+//! groups::set_role(jar, GroupId(7370273), UserId(375760054), RoleId(47932104))
+//!     .await
+//!     .unwrap();
+//! # }
+//! ```
+
+use std::{fmt, num::ParseIntError, str::FromStr};
+
+use rspc::Type;
+use serde::{Deserialize, Serialize};
+
+// The rspc derive clashes with doc comments passed through a macro, so the structs are
+// declared by hand and only their implementations are generated
+macro_rules! impl_id {
+    ($($name:ident),* $(,)?) => {$(
+        impl $name {
+            /// The id as a plain number
+            pub fn get(self) -> i64 {
+                self.0
+            }
+        }
+
+        impl From<i64> for $name {
+            fn from(id: i64) -> Self {
+                $name(id)
+            }
+        }
+
+        impl From<$name> for i64 {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseIntError;
+
+            fn from_str(id: &str) -> Result<Self, Self::Err> {
+                id.parse().map($name)
+            }
+        }
+    )*};
+}
+
+/// The id of a user
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(transparent)]
+pub struct UserId(pub i64);
+
+/// The id of a group
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(transparent)]
+pub struct GroupId(pub i64);
+
+/// The id of a role (roleset) in a group, not to be confused with its rank
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(transparent)]
+pub struct RoleId(pub i64);
+
+/// The id of a post on a group's wall
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(transparent)]
+pub struct WallPostId(pub i64);
+
+/// The id of a group's social link
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(transparent)]
+pub struct SocialLinkId(pub i64);
+
+/// The id of an asset, like a model, decal or piece of clothing
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(transparent)]
+pub struct AssetId(pub i64);
+
+/// The id of a universe (experience), which holds one or more places
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(transparent)]
+pub struct UniverseId(pub i64);
+
+/// The id of a place in a universe
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(transparent)]
+pub struct PlaceId(pub i64);
+
+/// The id of a badge
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(transparent)]
+pub struct BadgeId(pub i64);
+
+/// The id of a game pass
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(transparent)]
+pub struct GamePassId(pub i64);
+
+/// The id of a developer product
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Type,
+)]
+#[serde(transparent)]
+pub struct DeveloperProductId(pub i64);
+
+impl_id!(
+    UserId,
+    GroupId,
+    RoleId,
+    WallPostId,
+    SocialLinkId,
+    AssetId,
+    UniverseId,
+    PlaceId,
+    BadgeId,
+    GamePassId,
+    DeveloperProductId,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{GroupId, UserId};
+
+    #[test]
+    fn id_newtypes() {
+        let group_id = "7370273".parse::<GroupId>().unwrap();
+        assert_eq!(group_id, GroupId(7370273));
+        assert_eq!(group_id.to_string(), "7370273");
+        assert_eq!(i64::from(group_id), 7370273);
+
+        // Ids are sent and received as plain numbers
+        let request = crate::groups::GroupComplianceRequest {
+            group_ids: vec![group_id],
+        };
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"groupIds":[7370273]}"#
+        );
+        let user = serde_json::from_str::<crate::users::MinimalGroupUser>(
+            r#"{"hasVerifiedBadge":false,"userId":375760054,"username":"piano1029","displayName":"piano1029"}"#,
+        )
+        .unwrap();
+        assert_eq!(user.user_id, UserId(375760054));
+    }
+}
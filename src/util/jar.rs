@@ -1,401 +1,452 @@
-use std::sync::{Arc, Mutex};
-use crate::util::Error;
+use crate::util::{ConfigError, Error, RequestDetails};
 use async_recursion::async_recursion;
+use reqwest::{
+    header::{HeaderName, HeaderValue},
+    Method, StatusCode,
+};
+use std::{
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
 
-use super::responses::FailedRobloxResponse;
+use super::{
+    client::{ClientConfig, ProxyConfig},
+    hosts::{Domain, Hosts},
+    rate_limit::{RateLimit, RateLimiter, RouteFamily},
+    responses::{EmptyResponse, FailedRobloxResponse},
+    retry::{get_retry_after, RetryPolicy},
+    url::UrlBuilder,
+};
 
+/// How many times a request is sent again after Roblox rejected its X-CSRF-TOKEN
+pub const MAX_XCSRF_RETRIES: u32 = 2;
+
+/// Sends requests to Roblox and holds the state shared between them.
+///
+/// The credentials and proxy can be changed through a shared reference, so a single
+/// `Arc<RequestJar>` can be used from many tasks while its cookie is being refreshed.
+/// Requests that are already being sent keep using the values they started with.
 pub struct RequestJar {
-    pub roblosecurity: Option<String>,
+    roblosecurity: RwLock<Option<String>>,
     pub xcsrf_token: Arc<Mutex<Option<String>>>,
 
-    pub proxy: Option<String>,
+    proxy: RwLock<Option<ProxyConfig>>,
+    client: RwLock<reqwest::Client>,
+    client_config: ClientConfig,
+
+    pub hosts: Hosts,
+
+    pub retry_policy: RetryPolicy,
+    pub rate_limiter: RateLimiter,
 }
 
 impl RequestJar {
+    /// Creates a jar with the default settings, use [`RequestJar::builder`] to configure it
     pub async fn new() -> RequestJar {
+        // Like `reqwest::Client::new`, this only fails when TLS can't be initialized
+        RequestJarBuilder::new()
+            .build()
+            .expect("the default request jar settings should always build a client")
+    }
+
+    pub fn builder() -> RequestJarBuilder {
+        RequestJarBuilder::new()
+    }
+
+    fn from_builder(
+        builder: RequestJarBuilder,
+        proxy: Option<ProxyConfig>,
+        client: reqwest::Client,
+    ) -> RequestJar {
         RequestJar {
-            roblosecurity: None,
+            roblosecurity: RwLock::new(builder.roblosecurity),
             xcsrf_token: Arc::new(Mutex::new(None)),
 
-            proxy: None,
+            proxy: RwLock::new(proxy),
+            client: RwLock::new(client),
+            client_config: builder.client_config,
+
+            hosts: builder.hosts,
+
+            retry_policy: builder.retry_policy,
+            rate_limiter: builder.rate_limiter,
         }
     }
 
-    pub async fn set_roblosecurity(&self, roblosecurity: String) -> Result<(), Box<Error>> {
-        // TODO: Fix this
-        // self.roblosecurity = Some(roblosecurity);
-        self.get_xcsrf_token(0).await?;
-        Ok(())
+    /// Gets the base url requests for a domain are sent to
+    pub fn base_url(&self, domain: Domain) -> &str {
+        self.hosts.get(domain)
     }
 
-    pub fn set_proxy(&self, proxy: String) {
-        // TODO: Fix this
-        // self.proxy = Some(proxy);
+    /// Starts building the url of an endpoint, the path is relative to the domain
+    /// (e.g. `/v1/groups/7370273/users`)
+    pub fn url(&self, domain: Domain, path: &str) -> UrlBuilder {
+        UrlBuilder::new(&format!("{}{}", self.base_url(domain), path))
     }
 
-    pub fn get_reqwest_client(&self) -> reqwest::Client {
-        let mut client = reqwest::Client::new();
+    /// Points a single domain at a different base url
+    pub fn set_base_url(&mut self, domain: Domain, base_url: &str) {
+        self.hosts.set(domain, base_url);
+    }
 
-        if self.proxy.is_some() {
-            client = reqwest::Client::builder()
-                .proxy(reqwest::Proxy::all(self.proxy.as_ref().unwrap()).unwrap())
-                .build()
-                .unwrap();
-        }
+    /// Replaces the entire host map
+    pub fn set_hosts(&mut self, hosts: Hosts) {
+        self.hosts = hosts;
+    }
 
-        client
+    /// Sets how requests are retried when Roblox rate limits them
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
     }
 
-    pub async fn get(&self, url: &str) -> Result<reqwest::Response, Box<Error>> {
-        let client = self.get_reqwest_client();
+    /// Sets how requests are throttled before they are sent
+    pub fn set_rate_limiter(&mut self, rate_limiter: RateLimiter) {
+        self.rate_limiter = rate_limiter;
+    }
 
-        let response = client
-            .get(url)
-            .header(
-                "Cookie",
-                if self.roblosecurity.is_some() {
-                    format!(".ROBLOSECURITY={};", self.roblosecurity.as_ref().unwrap())
-                } else {
-                    "".to_string()
-                },
-            )
-            .header("Accept", "application/json")
-            .send()
-            .await;
-
-        match response {
-            Ok(res) => {
-                //if res.status() != 200 && !soft_fail {
-                //    let error = status_code_to_error(res.status());
-                //    if error.is_some() {
-                //        return Err(Box::new(error.unwrap_or(Error::Network)));
-                //    };
-                //}
-                Ok(res)
-            }
-            Err(_) => Err(Box::new(Error::Network)),
+    /// Waits until the rate limiter allows a request to be sent
+    async fn throttle(&self, request: &reqwest::Request) {
+        if let Some((domain, path)) = self.hosts.resolve(request.url().as_str()) {
+            let family = RouteFamily::classify(domain, request.method(), path);
+            self.rate_limiter.acquire(domain, family).await;
         }
     }
 
-    pub async fn get_json<T: for<'de> serde::Deserialize<'de>>(
-        &self,
-        url: &str,
-    ) -> Result<T, Box<Error>> {
-        let response = self.get(url).await?;
+    /// Gets the .ROBLOSECURITY cookie requests are authenticated with
+    pub fn get_roblosecurity(&self) -> Option<String> {
+        self.roblosecurity
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
 
-        if response.status() != 200 {
-            if response.status() == 429 {
-                return Err(Box::new(Error::RateLimited));
-            }
+    /// Authenticates the jar with a .ROBLOSECURITY cookie, replacing the previous one,
+    /// and fetches a new X-CSRF-TOKEN for it.
+    ///
+    /// # Error codes
+    /// - Network: Roblox couldn't be reached
+    /// - XcsrfToken: Roblox didn't send a X-CSRF-TOKEN
+    pub async fn set_roblosecurity(&self, roblosecurity: String) -> Result<(), Box<Error>> {
+        *self
+            .roblosecurity
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(roblosecurity);
 
-            let json = response.json::<FailedRobloxResponse>().await.unwrap();
-            return Err(Box::new(Error::RobloxError(json.errors[0].clone())));
-        }
+        // The token belongs to the previous session
+        *self
+            .xcsrf_token
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
 
-        // Need to log raw responses? Uncomment this and comment the json and response stuff
-        // let raw = response.text().await.unwrap();
-        // println!("URL: {}\n\n{}", url, raw);
-        // Err(Box::new(Error::JSON))
+        self.get_xcsrf_token(0).await?;
+        Ok(())
+    }
 
-        let json = response.json::<T>().await;
+    /// Removes the .ROBLOSECURITY cookie, requests are sent unauthenticated afterwards
+    pub fn clear_roblosecurity(&self) {
+        *self
+            .roblosecurity
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+        *self
+            .xcsrf_token
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
 
-        match json {
-            Ok(json) => Ok(json),
-            Err(error) => {
-                println!("Error: {:#?}", error);
-                Err(Box::new(Error::JSON))
-            }
-        }
+    /// Gets the url of the proxy requests are sent through
+    pub fn get_proxy(&self) -> Option<String> {
+        self.get_proxy_config().map(|proxy| proxy.url)
     }
 
-    pub async fn post(&self, url: &str, data: String) -> Result<reqwest::Response, Box<Error>> {
-        let client = self.get_reqwest_client();
+    pub fn get_proxy_config(&self) -> Option<ProxyConfig> {
+        self.proxy
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
 
-        let xcsrf_token = self.get_xcsrf();
+    /// Sends all following requests through a proxy (e.g. `http://127.0.0.1:8080`)
+    ///
+    /// # Error codes
+    /// - Config: The proxy isn't a valid url
+    /// - Network: The client couldn't be created
+    pub fn set_proxy(&self, proxy: String) -> Result<(), Box<Error>> {
+        self.set_proxy_config(ProxyConfig::new(&proxy))
+    }
 
-        let response = client
-            .post(url)
-            .body(data.clone())
-            .header(
-                "Cookie",
-                if self.roblosecurity.is_some() {
-                    format!(".ROBLOSECURITY={};", self.roblosecurity.as_ref().unwrap())
-                } else {
-                    "".to_string()
-                },
-            )
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/json")
-            .header("X-CSRF-TOKEN", xcsrf_token)
-            .send()
-            .await;
+    /// Sends all following requests through a proxy that may require authentication
+    ///
+    /// # Error codes
+    /// - Config: The proxy isn't a valid url
+    /// - Network: The client couldn't be created
+    pub fn set_proxy_config(&self, proxy: ProxyConfig) -> Result<(), Box<Error>> {
+        let mut current = self
+            .proxy
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.set_client(self.client_config.build_client(Some(&proxy))?);
+        *current = Some(proxy);
+        Ok(())
+    }
 
-        // If the response returned a X-Csrf-Token header, update the client's xcsrf token.
-        if response
-            .as_ref()
-            .unwrap()
-            .headers()
-            .contains_key("X-CSRF-TOKEN")
-        {
-            // TODO: Fix this
-            let mut xcsrf_token = self.xcsrf_token.lock().unwrap();
-            *xcsrf_token = Some(
-                response
-                    .as_ref()
-                    .unwrap()
-                    .headers()
-                    .get("X-CSRF-TOKEN")
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string(),
-            );
-        }
+    /// Sends all following requests directly to Roblox again
+    ///
+    /// # Error codes
+    /// - Network: The client couldn't be created
+    pub fn clear_proxy(&self) -> Result<(), Box<Error>> {
+        let mut current = self
+            .proxy
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        self.set_client(self.client_config.build_client(None)?);
+        *current = None;
+        Ok(())
+    }
 
-        match response {
-            Ok(res) => {
-                //if res.status() != 200 && !soft_fail {
-                //    let error = status_code_to_error(res.status());
-                //    if error.is_some() {
-                //        return Err(Box::new(error.unwrap_or(Error::Network)));
-                //    };
-                //}
-                Ok(res)
-            }
-            Err(_) => Err(Box::new(Error::Network)),
-        }
+    pub fn get_client_config(&self) -> &ClientConfig {
+        &self.client_config
     }
 
-    #[async_recursion]
-    pub async fn post_json<
-        T: for<'de> serde::Deserialize<'de>,
-        PD: serde::Serialize + std::marker::Send,
-    >(
-        &self,
-        url: &str,
-        json_data: PD,
-    ) -> Result<T, Box<Error>> {
-        let data = serde_json::to_string(&json_data).unwrap();
-        let response = self.post(url, data).await?;
+    /// Changes the timeouts, pooling, HTTP/2 or header settings, rebuilding the client
+    ///
+    /// # Error codes
+    /// - Config: The settings are invalid
+    /// - Network: The client couldn't be created
+    pub fn set_client_config(&mut self, client_config: ClientConfig) -> Result<(), Box<Error>> {
+        let client = client_config.build_client(self.get_proxy_config().as_ref())?;
+        self.set_client(client);
+        self.client_config = client_config;
+        Ok(())
+    }
 
-        if response.status() != 200 {
-            if response.status() == 429 {
-                return Err(Box::new(Error::RateLimited));
-            }
+    fn set_client(&self, client: reqwest::Client) {
+        *self
+            .client
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = client;
+    }
 
-            let json = response.json::<FailedRobloxResponse>().await.unwrap();
+    /// Gets the client requests are sent with. Clients share their connection pool with
+    /// their clones, so this is cheap.
+    pub fn get_reqwest_client(&self) -> reqwest::Client {
+        self.client
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
 
-            if json.errors[0].clone().message == "Token Validation Failed" {
-                self.get_xcsrf_token(0).await?;
-                //panic!("E");
-                return self.post_json(url, json_data).await;
-            }
+    /// Sends a request once the rate limiter allows it, retrying it according to the retry
+    /// policy while Roblox responds with 429 Too Many Requests. The request is rebuilt for
+    /// every attempt.
+    async fn send(
+        &self,
+        request: impl Fn() -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, Box<Error>> {
+        let mut attempt = 1;
 
-            return Err(Box::new(Error::RobloxError(json.errors[0].clone())));
-        }
+        loop {
+            let (client, request) = request().build_split();
+            let request = request
+                .map_err(|error| Box::new(Error::Network(RequestDetails::from_source(error))))?;
+            self.throttle(&request).await;
 
-        let json = response.json::<T>().await;
+            let method = request.method().clone();
+            let url = request.url().to_string();
+            let response = client.execute(request).await.map_err(|error| {
+                Box::new(Error::Network(RequestDetails {
+                    method: Some(method),
+                    url: Some(url),
+                    source: Some(Box::new(error)),
+                    ..Default::default()
+                }))
+            })?;
 
-        match json {
-            Ok(json) => Ok(json),
-            Err(error) => {
-                println!("Error: {:#?}", error);
-                Err(Box::new(Error::JSON))
+            if response.status() != StatusCode::TOO_MANY_REQUESTS
+                || attempt >= self.retry_policy.max_attempts
+            {
+                return Ok(response);
             }
+
+            let delay = self
+                .retry_policy
+                .get_delay(attempt, get_retry_after(response.headers()));
+            tokio::time::sleep(delay).await;
+            attempt += 1;
         }
     }
 
-    pub async fn patch(&self, url: &str, data: String) -> Result<reqwest::Response, Box<Error>> {
-        let client = self.get_reqwest_client();
-
-        let xcsrf_token = self.get_xcsrf();
+    pub async fn get(&self, url: &str) -> Result<reqwest::Response, Box<Error>> {
+        self.request(Method::GET, url, None).await
+    }
 
-        let response = client
-            .patch(url)
-            .body(data)
-            .header(
-                "Cookie",
-                if self.roblosecurity.is_some() {
-                    format!(".ROBLOSECURITY={};", self.roblosecurity.as_ref().unwrap())
-                } else {
-                    "".to_string()
-                },
-            )
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/json")
-            .header("X-CSRF-TOKEN", xcsrf_token)
-            .send()
-            .await;
+    pub async fn post(&self, url: &str, data: String) -> Result<reqwest::Response, Box<Error>> {
+        self.request(Method::POST, url, Some(data)).await
+    }
 
-        // If the response returned a X-Csrf-Token header, update the client's xcsrf token.
-        if response
-            .as_ref()
-            .unwrap()
-            .headers()
-            .contains_key("X-CSRF-TOKEN")
-        {
-            let mut xcsrf_token = self.xcsrf_token.lock().unwrap();
-            *xcsrf_token = Some(
-                response
-                    .as_ref()
-                    .unwrap()
-                    .headers()
-                    .get("X-CSRF-TOKEN")
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string(),
-            );
-        }
+    pub async fn patch(&self, url: &str, data: String) -> Result<reqwest::Response, Box<Error>> {
+        self.request(Method::PATCH, url, Some(data)).await
+    }
 
-        match response {
-            Ok(res) => {
-                //if res.status() != 200 && !soft_fail {
-                //    let error = status_code_to_error(res.status());
-                //    if error.is_some() {
-                //        return Err(Box::new(error.unwrap_or(Error::Network)));
-                //    };
-                //}
-                Ok(res)
-            }
-            Err(_) => Err(Box::new(Error::Network)),
-        }
+    pub async fn delete(&self, url: &str, data: String) -> Result<reqwest::Response, Box<Error>> {
+        self.request(Method::DELETE, url, Some(data)).await
     }
 
-    #[async_recursion]
-    pub async fn patch_json<
-        T: for<'de> serde::Deserialize<'de>,
-        PD: serde::Serialize + std::marker::Send,
-    >(
+    /// Sends a request with an optional JSON body.
+    ///
+    /// Requests that change data are sent with the X-CSRF-TOKEN. When Roblox rejects the token it
+    /// sends a new one along with a 403, the token is stored and the request is sent again, at most
+    /// [`MAX_XCSRF_RETRIES`] times. An empty body is sent without a Content-Type.
+    ///
+    /// # Error codes
+    /// - Network: Roblox couldn't be reached
+    pub async fn request(
         &self,
+        method: Method,
         url: &str,
-        json_data: PD,
-    ) -> Result<T, Box<Error>> {
-        let data = serde_json::to_string(&json_data).unwrap();
-        let response = self.patch(url, data).await?;
+        data: Option<String>,
+    ) -> Result<reqwest::Response, Box<Error>> {
+        let client = self.get_reqwest_client();
+        let data = data.filter(|data| !data.is_empty());
+        let needs_xcsrf = method != Method::GET && method != Method::HEAD;
+        let mut xcsrf_retries = 0;
 
-        if response.status() != 200 {
-            if response.status() == 429 {
-                return Err(Box::new(Error::RateLimited));
-            }
+        loop {
+            let xcsrf_token = self.get_xcsrf();
 
-            let json = response.json::<FailedRobloxResponse>().await.unwrap();
+            let response = self
+                .send(|| {
+                    let mut request = client
+                        .request(method.clone(), url)
+                        .header("Cookie", self.get_cookie())
+                        .header("Accept", "application/json");
 
-            if json.errors[0].clone().message == "Token Validation Failed" {
-                self.get_xcsrf_token(0).await?;
-                //panic!("E");
-                return self.post_json(url, json_data).await;
-            }
+                    if needs_xcsrf {
+                        request = request.header("X-CSRF-TOKEN", &xcsrf_token);
+                    }
 
-            return Err(Box::new(Error::RobloxError(json.errors[0].clone())));
-        }
+                    match &data {
+                        Some(data) => request
+                            .header("Content-Type", "application/json")
+                            .body(data.clone()),
+                        None if needs_xcsrf => request.header("Content-Length", "0"),
+                        None => request,
+                    }
+                })
+                .await?;
 
-        let json = response.json::<T>().await;
+            let new_token = self.store_xcsrf_token(&response);
 
-        match json {
-            Ok(json) => Ok(json),
-            Err(error) => {
-                println!("Error: {:#?}", error);
-                Err(Box::new(Error::JSON))
+            let is_challenge = response.status() == StatusCode::FORBIDDEN
+                && new_token.is_some_and(|token| token != xcsrf_token);
+            if !needs_xcsrf || !is_challenge || xcsrf_retries >= MAX_XCSRF_RETRIES {
+                return Ok(response);
             }
+
+            xcsrf_retries += 1;
         }
     }
 
-    pub async fn delete(&self, url: &str, data: String) -> Result<reqwest::Response, Box<Error>> {
-        let client = self.get_reqwest_client();
-
-        let xcsrf_token = self.get_xcsrf();
+    /// Sends a request like [`request`](Self::request) and decodes the JSON response.
+    ///
+    /// # Error codes
+    /// - Network: Roblox couldn't be reached
+    /// - RateLimited: Roblox kept rate limiting the request after retrying it
+    /// - RobloxError: Roblox rejected the request
+    /// - JSON: The response couldn't be decoded
+    pub async fn request_json<T: for<'de> serde::Deserialize<'de>>(
+        &self,
+        method: Method,
+        url: &str,
+        data: Option<String>,
+    ) -> Result<T, Box<Error>> {
+        let response = self.request(method.clone(), url, data).await?;
+        read_response(Some(method), response).await
+    }
 
-        let response = client
-            .delete(url)
-            .body(data)
-            .header(
-                "Cookie",
-                if self.roblosecurity.is_some() {
-                    format!(".ROBLOSECURITY={};", self.roblosecurity.as_ref().unwrap())
-                } else {
-                    "".to_string()
-                },
-            )
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/json")
-            .header("X-CSRF-TOKEN", xcsrf_token)
-            .send()
-            .await;
+    /// Decodes the JSON body of a response without looking at its status, for endpoints that
+    /// respond with a different body depending on the status
+    ///
+    /// # Error codes
+    /// - Network: The body couldn't be read
+    /// - JSON: The body couldn't be decoded
+    pub async fn read_json<T: for<'de> serde::Deserialize<'de>>(
+        response: reqwest::Response,
+    ) -> Result<T, Box<Error>> {
+        let (details, text) = read_body(None, response).await?;
+        decode_body(details, text)
+    }
 
-        // If the response returned a X-Csrf-Token header, update the client's xcsrf token.
-        if response
-            .as_ref()
-            .unwrap()
-            .headers()
-            .contains_key("X-CSRF-TOKEN")
-        {
-            // TODO: Fix this
-            let mut xcsrf_token = self.xcsrf_token.lock().unwrap();
-            *xcsrf_token = Some(
-                response
-                    .as_ref()
-                    .unwrap()
-                    .headers()
-                    .get("X-CSRF-TOKEN")
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string(),
-            );
+    /// Turns a response Roblox rejected into an error carrying everything it responded with
+    pub async fn read_error(response: reqwest::Response) -> Box<Error> {
+        match read_body(None, response).await {
+            Ok((details, text)) => error_from_body(details, text),
+            Err(error) => error,
         }
+    }
 
-        match response {
-            Ok(res) => {
-                //if res.status() != 200 && !soft_fail {
-                //    let error = status_code_to_error(res.status());
-                //    if error.is_some() {
-                //        return Err(Box::new(error.unwrap_or(Error::Network)));
-                //    };
-                //}
-                Ok(res)
-            }
-            Err(_) => Err(Box::new(Error::Network)),
-        }
+    pub async fn get_json<T: for<'de> serde::Deserialize<'de>>(
+        &self,
+        url: &str,
+    ) -> Result<T, Box<Error>> {
+        self.request_json(Method::GET, url, None).await
     }
 
-    #[async_recursion]
-    pub async fn delete_json<
-        T: for<'de> serde::Deserialize<'de>,
-        PD: serde::Serialize + std::marker::Send,
-    >(
+    pub async fn post_json<T: for<'de> serde::Deserialize<'de>, PD: serde::Serialize>(
         &self,
         url: &str,
         json_data: PD,
     ) -> Result<T, Box<Error>> {
-        let data = serde_json::to_string(&json_data).unwrap();
-        let response = self.delete(url, data).await?;
+        let data = serialize(Method::POST, url, &json_data)?;
+        self.request_json(Method::POST, url, Some(data)).await
+    }
 
-        if response.status() != 200 {
-            if response.status() == 429 {
-                return Err(Box::new(Error::RateLimited));
-            }
+    pub async fn patch_json<T: for<'de> serde::Deserialize<'de>, PD: serde::Serialize>(
+        &self,
+        url: &str,
+        json_data: PD,
+    ) -> Result<T, Box<Error>> {
+        let data = serialize(Method::PATCH, url, &json_data)?;
+        self.request_json(Method::PATCH, url, Some(data)).await
+    }
 
-            let json = response.json::<FailedRobloxResponse>().await.unwrap();
+    pub async fn delete_json<T: for<'de> serde::Deserialize<'de>, PD: serde::Serialize>(
+        &self,
+        url: &str,
+        json_data: PD,
+    ) -> Result<T, Box<Error>> {
+        let data = serialize(Method::DELETE, url, &json_data)?;
+        self.request_json(Method::DELETE, url, Some(data)).await
+    }
 
-            if json.errors[0].clone().message == "Token Validation Failed" {
-                self.get_xcsrf_token(0).await?;
-                //panic!("E");
-                return self.post_json(url, json_data).await;
-            }
+    /// Sends a POST request without a body, for endpoints that take everything from the url
+    pub async fn post_empty(&self, url: &str) -> Result<(), Box<Error>> {
+        self.request_json::<EmptyResponse>(Method::POST, url, None)
+            .await?;
+        Ok(())
+    }
 
-            return Err(Box::new(Error::RobloxError(json.errors[0].clone())));
-        }
+    /// Sends a DELETE request without a body, for endpoints that take everything from the url
+    pub async fn delete_empty(&self, url: &str) -> Result<(), Box<Error>> {
+        self.request_json::<EmptyResponse>(Method::DELETE, url, None)
+            .await?;
+        Ok(())
+    }
 
-        let json = response.json::<T>().await;
+    /// Stores the X-CSRF-TOKEN if the response came with one, returning it
+    fn store_xcsrf_token(&self, response: &reqwest::Response) -> Option<String> {
+        let token = response
+            .headers()
+            .get("X-CSRF-TOKEN")?
+            .to_str()
+            .ok()?
+            .to_string();
 
-        match json {
-            Ok(json) => Ok(json),
-            Err(error) => {
-                println!("Error: {:#?}", error);
-                Err(Box::new(Error::JSON))
-            }
-        }
+        *self
+            .xcsrf_token
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(token.clone());
+        Some(token)
     }
 
     #[async_recursion]
@@ -403,41 +454,29 @@ impl RequestJar {
         //panic!("Not implemented yet");
         //return Ok(()); // TODO: Implement this? Might not be needed, its in noblox.js but from my very limited research it doesnt appear to be used anymore
         // After more resarch it is very needed on not get requests
-        if self.roblosecurity.is_none() {
+        let Some(roblosecurity) = self.get_roblosecurity() else {
             return Err(Box::new(Error::Authentication));
-        }
+        };
 
         let client = self.get_reqwest_client();
         let response = client
-            .post("https://auth.roblox.com/v2/logout")
-            .header(
-                "Cookie",
-                format!(".ROBLOSECURITY={};", self.roblosecurity.as_ref().unwrap()),
-            )
+            .post(format!("{}/v2/logout", self.base_url(Domain::Auth)))
+            .header("Cookie", format!(".ROBLOSECURITY={};", roblosecurity))
             .header("Content-Length", "0")
-            .header("Origin", "https://www.roblox.com")
-            .header("Referer", "https://www.roblox.com/")
-            .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/107.0.5304.107 Safari/537.36")
+            .header("Origin", self.base_url(Domain::Www))
+            .header("Referer", format!("{}/", self.base_url(Domain::Www)))
             .send()
             .await
-            .unwrap(); // TODO: Handle error
+            .map_err(|error| Box::new(Error::Network(RequestDetails::from_source(error))))?;
 
-        // Get the X-Csrf-Token header
-        let token_header = response.headers().get("X-CSRF-TOKEN");
+        if self.store_xcsrf_token(&response).is_some() {
+            return Ok(());
+        }
 
-        match token_header {
-            Some(token) => {
-                let mut xcsrf_token = self.xcsrf_token.lock().unwrap();
-                *xcsrf_token = Some(token.to_str().unwrap().to_string());
-                return Ok(());
-            }
-            None => {
-                if depth > 3 {
-                    return Err(Box::new(Error::XcsrfToken));
-                }
-                return self.get_xcsrf_token(depth + 1).await;
-            }
+        if depth > 3 {
+            return Err(Box::new(Error::XcsrfToken));
         }
+        self.get_xcsrf_token(depth + 1).await
 
         //self.xcsrf_token = Some(token.to_string());
 
@@ -449,9 +488,550 @@ impl RequestJar {
         //self.xcsrf_token = Some(token.to_string());
     }
 
+    pub fn get_cookie(&self) -> String {
+        match self.get_roblosecurity() {
+            Some(roblosecurity) => format!(".ROBLOSECURITY={};", roblosecurity),
+            None => "".to_string(),
+        }
+    }
+
     pub fn get_xcsrf(&self) -> String {
-        let xcsrf = self.xcsrf_token.lock().unwrap();
-        let xcsrf_token = xcsrf.clone().unwrap_or("".to_string());
-        return xcsrf_token
+        let xcsrf = self
+            .xcsrf_token
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        xcsrf.clone().unwrap_or_default()
+    }
+}
+
+fn serialize<PD: serde::Serialize>(
+    method: Method,
+    url: &str,
+    json_data: &PD,
+) -> Result<String, Box<Error>> {
+    serde_json::to_string(json_data).map_err(|error| {
+        Box::new(Error::JSON(RequestDetails {
+            method: Some(method),
+            url: Some(url.to_string()),
+            source: Some(Box::new(error)),
+            ..Default::default()
+        }))
+    })
+}
+
+async fn read_response<T: for<'de> serde::Deserialize<'de>>(
+    method: Option<Method>,
+    response: reqwest::Response,
+) -> Result<T, Box<Error>> {
+    let (details, text) = read_body(method, response).await?;
+
+    if !details.status.unwrap_or_default().is_success() {
+        return Err(error_from_body(details, text));
+    }
+
+    decode_body(details, text)
+}
+
+async fn read_body(
+    method: Option<Method>,
+    response: reqwest::Response,
+) -> Result<(RequestDetails, String), Box<Error>> {
+    let details = RequestDetails {
+        method,
+        url: Some(response.url().to_string()),
+        status: Some(response.status()),
+        ..Default::default()
+    };
+
+    match response.text().await {
+        Ok(text) => Ok((details, text)),
+        Err(error) => Err(Box::new(Error::Network(RequestDetails {
+            source: Some(Box::new(error)),
+            ..details
+        }))),
+    }
+}
+
+fn error_from_body(details: RequestDetails, text: String) -> Box<Error> {
+    // Roblox usually sends a list of errors, but not when something in front of it failed
+    let errors = serde_json::from_str::<FailedRobloxResponse>(&text)
+        .map(|failed| failed.errors)
+        .unwrap_or_default();
+    let details = RequestDetails {
+        errors,
+        body: Some(text),
+        ..details
+    };
+
+    Box::new(if details.status == Some(StatusCode::TOO_MANY_REQUESTS) {
+        Error::RateLimited(details)
+    } else {
+        Error::RobloxError(details)
+    })
+}
+
+fn decode_body<T: for<'de> serde::Deserialize<'de>>(
+    details: RequestDetails,
+    text: String,
+) -> Result<T, Box<Error>> {
+    // Some endpoints respond with no content at all
+    let body = if text.trim().is_empty() {
+        "null"
+    } else {
+        &text
+    };
+
+    match serde_json::from_str::<T>(body) {
+        Ok(json) => Ok(json),
+        Err(error) => Err(Box::new(Error::JSON(RequestDetails {
+            body: Some(text),
+            source: Some(Box::new(error)),
+            ..details
+        }))),
+    }
+}
+
+/// Configures a [`RequestJar`]. Everything is validated when the jar is built, so a jar
+/// that was built successfully won't fail on its configuration later on.
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use rustyroblox::util::jar::RequestJar;
+/// # fn build() -> Result<RequestJar, Box<rustyroblox::util::Error>> {
+/// let jar = RequestJar::builder()
+///     .roblosecurity("_|WARNING:-DO-NOT-SHARE-THIS...")
+///     .proxy("http://127.0.0.1:8080")
+///     .proxy_auth("user", "password")
+///     .connect_timeout(Duration::from_secs(5))
+///     .user_agent("my-ranking-bot/1.0")
+///     .default_header("X-Bot-Owner", "clanny")
+///     .build()?;
+/// # Ok(jar)
+/// # }
+/// ```
+pub struct RequestJarBuilder {
+    roblosecurity: Option<String>,
+    proxy: Option<String>,
+    proxy_auth: Option<(String, String)>,
+    client_config: ClientConfig,
+    default_headers: Vec<(String, String)>,
+    hosts: Hosts,
+    retry_policy: RetryPolicy,
+    rate_limiter: RateLimiter,
+}
+
+impl Default for RequestJarBuilder {
+    fn default() -> Self {
+        RequestJarBuilder {
+            roblosecurity: None,
+            proxy: None,
+            proxy_auth: None,
+            client_config: ClientConfig::default(),
+            default_headers: Vec::new(),
+            hosts: Hosts::new(),
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: RateLimiter::default(),
+        }
+    }
+}
+
+impl RequestJarBuilder {
+    pub fn new() -> RequestJarBuilder {
+        RequestJarBuilder::default()
+    }
+
+    /// The .ROBLOSECURITY cookie to authenticate with. The X-CSRF-TOKEN is fetched once
+    /// Roblox asks for it.
+    pub fn roblosecurity(mut self, roblosecurity: &str) -> Self {
+        self.roblosecurity = Some(roblosecurity.to_string());
+        self
+    }
+
+    /// Sends requests through a proxy (e.g. `http://127.0.0.1:8080`)
+    pub fn proxy(mut self, url: &str) -> Self {
+        self.proxy = Some(url.to_string());
+        self
+    }
+
+    /// Authenticates with the proxy using basic auth, only used when a proxy is set.
+    /// It can be set before or after the proxy itself.
+    pub fn proxy_auth(mut self, username: &str, password: &str) -> Self {
+        self.proxy_auth = Some((username.to_string(), password.to_string()));
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.client_config.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// How long an entire request may take, including reading the response
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.client_config.timeout = Some(timeout);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.client_config.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Adds a header that is sent with every request
+    pub fn default_header(mut self, name: &str, value: &str) -> Self {
+        self.default_headers
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Replaces the client settings. Headers added with [`default_header`](Self::default_header)
+    /// are still sent.
+    pub fn client_config(mut self, client_config: ClientConfig) -> Self {
+        self.client_config = client_config;
+        self
+    }
+
+    pub fn hosts(mut self, hosts: Hosts) -> Self {
+        self.hosts = hosts;
+        self
+    }
+
+    /// Points a single domain at a different base url
+    pub fn base_url(mut self, domain: Domain, base_url: &str) -> Self {
+        self.hosts.set(domain, base_url);
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Sets the limit for a single route family, keeping the other limits
+    pub fn rate_limit(mut self, domain: Domain, family: RouteFamily, limit: RateLimit) -> Self {
+        self.rate_limiter.set_limit(domain, family, limit);
+        self
+    }
+
+    /// Validates the configuration and creates the jar
+    ///
+    /// # Error codes
+    /// - Config: A setting is invalid, the [`ConfigError`] says which one
+    /// - Network: The client couldn't be created, e.g. because TLS couldn't be initialized
+    pub fn build(mut self) -> Result<RequestJar, Box<Error>> {
+        let config_error = |error: ConfigError| Box::new(Error::Config(error));
+
+        if let Some(roblosecurity) = &self.roblosecurity {
+            HeaderValue::from_str(&format!(".ROBLOSECURITY={};", roblosecurity))
+                .map_err(|_| config_error(ConfigError::InvalidCookie))?;
+        }
+
+        for (name, value) in &self.default_headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| config_error(ConfigError::InvalidHeader(name.clone())))?;
+            let header_value = HeaderValue::from_str(value)
+                .map_err(|_| config_error(ConfigError::InvalidHeader(name.clone())))?;
+            self.client_config
+                .default_headers
+                .insert(header_name, header_value);
+        }
+
+        for domain in Domain::ALL {
+            let base_url = self.hosts.get(domain);
+            if reqwest::Url::parse(base_url).is_err() {
+                return Err(config_error(ConfigError::InvalidBaseUrl(
+                    base_url.to_string(),
+                )));
+            }
+        }
+
+        if !self.retry_policy.is_valid() {
+            return Err(config_error(ConfigError::InvalidRetryPolicy));
+        }
+
+        if !self
+            .rate_limiter
+            .limits()
+            .all(|(_, _, limit)| limit.is_valid())
+        {
+            return Err(config_error(ConfigError::InvalidRateLimit));
+        }
+
+        let proxy = self.proxy.as_ref().map(|url| ProxyConfig {
+            url: url.clone(),
+            basic_auth: self.proxy_auth.clone(),
+        });
+        let client = self.client_config.build_client(proxy.as_ref())?;
+        Ok(RequestJar::from_builder(self, proxy, client))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mock::{testing::mock_jar, MockResponse, MockServer},
+        util::ids::{GroupId, RoleId, UserId},
+    };
+
+    #[tokio::test]
+    async fn set_roblosecurity() {
+        let (server, jar) = mock_jar().await;
+        server.mock(
+            Domain::Auth,
+            "POST",
+            "/v2/logout",
+            MockResponse::csrf_challenge("session-token"),
+        );
+
+        jar.set_roblosecurity("cookie".to_string()).await.unwrap();
+        assert_eq!(jar.get_roblosecurity().as_deref(), Some("cookie"));
+        assert_eq!(jar.get_xcsrf(), "session-token");
+
+        crate::users::whoami(&jar).await.unwrap();
+        let requests = server.requests();
+        let last = requests.last().unwrap();
+        assert_eq!(last.header("cookie"), Some(".ROBLOSECURITY=cookie;"));
+
+        jar.clear_roblosecurity();
+        assert_eq!(jar.get_roblosecurity(), None);
+        assert_eq!(jar.get_xcsrf(), "");
+    }
+
+    #[tokio::test]
+    async fn swap_roblosecurity_on_shared_jar() {
+        let (server, jar) = mock_jar().await;
+        server.mock(
+            Domain::Auth,
+            "POST",
+            "/v2/logout",
+            MockResponse::csrf_challenge("session-token"),
+        );
+        let jar = Arc::new(jar);
+
+        jar.set_roblosecurity("old".to_string()).await.unwrap();
+
+        let refresher = jar.clone();
+        tokio::spawn(async move { refresher.set_roblosecurity("new".to_string()).await })
+            .await
+            .unwrap()
+            .unwrap();
+
+        crate::users::whoami(&jar).await.unwrap();
+        let requests = server.requests();
+        let last = requests.last().unwrap();
+        assert_eq!(last.header("cookie"), Some(".ROBLOSECURITY=new;"));
+    }
+
+    #[tokio::test]
+    async fn set_proxy() {
+        let (_server, jar) = mock_jar().await;
+        assert_eq!(jar.get_proxy(), None);
+
+        jar.set_proxy("http://127.0.0.1:8080".to_string()).unwrap();
+        assert_eq!(jar.get_proxy().as_deref(), Some("http://127.0.0.1:8080"));
+
+        let error = jar.set_proxy("not a proxy".to_string()).unwrap_err();
+        assert!(matches!(
+            *error,
+            Error::Config(ConfigError::InvalidProxy(_))
+        ));
+        assert_eq!(jar.get_proxy().as_deref(), Some("http://127.0.0.1:8080"));
+
+        jar.clear_proxy().unwrap();
+        assert_eq!(jar.get_proxy(), None);
+    }
+
+    #[tokio::test]
+    async fn set_client_config() {
+        let (_server, mut jar) = mock_jar().await;
+        jar.set_client_config(ClientConfig {
+            connect_timeout: Some(Duration::from_secs(1)),
+            timeout: Some(Duration::from_secs(5)),
+            pool_max_idle_per_host: 1,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(jar.get_client_config().pool_max_idle_per_host, 1);
+
+        let group = crate::groups::group_by_id(&jar, GroupId(7370273))
+            .await
+            .unwrap();
+        assert_eq!(group.id, GroupId(7370273));
+    }
+
+    #[tokio::test]
+    async fn request_jar_builder() {
+        let server = MockServer::with_fixtures().await;
+        let jar = RequestJar::builder()
+            .hosts(server.hosts())
+            .roblosecurity("cookie")
+            .user_agent("rustyroblox-tests/1.0")
+            .default_header("X-Bot-Owner", "clanny")
+            .connect_timeout(Duration::from_secs(1))
+            .retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+
+        crate::users::whoami(&jar).await.unwrap();
+
+        let request = &server.requests()[0];
+        assert_eq!(request.header("cookie"), Some(".ROBLOSECURITY=cookie;"));
+        assert_eq!(request.header("user-agent"), Some("rustyroblox-tests/1.0"));
+        assert_eq!(request.header("x-bot-owner"), Some("clanny"));
+    }
+
+    #[test]
+    fn request_jar_builder_validation() {
+        let error_of = |builder: RequestJarBuilder| match builder.build() {
+            Err(error) => match *error {
+                Error::Config(error) => error,
+                error => panic!("Expected a config error, got {:?}", error),
+            },
+            Ok(_) => panic!("Expected the configuration to be rejected"),
+        };
+
+        assert_eq!(
+            error_of(RequestJar::builder().proxy("not a proxy")),
+            ConfigError::InvalidProxy("not a proxy".to_string())
+        );
+        assert_eq!(
+            error_of(RequestJar::builder().user_agent("bad\nagent")),
+            ConfigError::InvalidUserAgent
+        );
+        assert_eq!(
+            error_of(RequestJar::builder().roblosecurity("bad\ncookie")),
+            ConfigError::InvalidCookie
+        );
+        assert_eq!(
+            error_of(RequestJar::builder().default_header("bad header", "value")),
+            ConfigError::InvalidHeader("bad header".to_string())
+        );
+        assert_eq!(
+            error_of(RequestJar::builder().base_url(Domain::Groups, "groups.roblox.com")),
+            ConfigError::InvalidBaseUrl("groups.roblox.com".to_string())
+        );
+        assert_eq!(
+            error_of(RequestJar::builder().timeout(Duration::ZERO)),
+            ConfigError::InvalidTimeout("timeout".to_string())
+        );
+        assert_eq!(
+            error_of(RequestJar::builder().retry_policy(RetryPolicy {
+                max_attempts: 0,
+                ..Default::default()
+            })),
+            ConfigError::InvalidRetryPolicy
+        );
+        assert_eq!(
+            error_of(RequestJar::builder().rate_limit(
+                Domain::Groups,
+                RouteFamily::Read,
+                RateLimit::per_second(0)
+            )),
+            ConfigError::InvalidRateLimit
+        );
+
+        let jar = RequestJar::builder()
+            .proxy("http://127.0.0.1:8080")
+            .proxy_auth("user", "password")
+            .build()
+            .unwrap();
+        assert_eq!(
+            jar.get_proxy_config(),
+            Some(ProxyConfig::new("http://127.0.0.1:8080").with_basic_auth("user", "password"))
+        );
+
+        // The order the proxy and its credentials are set in doesn't matter
+        let jar = RequestJar::builder()
+            .proxy_auth("user", "password")
+            .proxy("http://127.0.0.1:8080")
+            .build()
+            .unwrap();
+        assert_eq!(
+            jar.get_proxy_config().and_then(|proxy| proxy.basic_auth),
+            Some(("user".to_string(), "password".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn csrf_challenge_keeps_verb() {
+        let (server, jar) = mock_jar().await;
+        server.mock(
+            Domain::Groups,
+            "PATCH",
+            "/v1/groups/7370273/users/375760054",
+            MockResponse::json("{}"),
+        );
+        server.script(
+            Domain::Groups,
+            "PATCH",
+            "/v1/groups/7370273/users/375760054",
+            vec![MockResponse::csrf_challenge("patch-token")],
+        );
+
+        crate::groups::set_role(&jar, GroupId(7370273), UserId(375760054), RoleId(47932104))
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].method, "PATCH");
+        assert_eq!(requests[1].header("x-csrf-token"), Some("patch-token"));
+        assert_eq!(requests[1].body, r#"{"roleId":47932104}"#);
+    }
+
+    #[tokio::test]
+    async fn csrf_challenge_empty_body() {
+        let (server, jar) = mock_jar().await;
+        server.mock(
+            Domain::Groups,
+            "DELETE",
+            "/v1/groups/7370273/users/375760054",
+            MockResponse::json("{}"),
+        );
+        server.script(
+            Domain::Groups,
+            "DELETE",
+            "/v1/groups/7370273/users/375760054",
+            vec![MockResponse::csrf_challenge("delete-token")],
+        );
+
+        crate::groups::remove_user(&jar, GroupId(7370273), UserId(375760054))
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].method, "DELETE");
+        assert_eq!(requests[1].header("x-csrf-token"), Some("delete-token"));
+        assert_eq!(requests[1].header("content-type"), None);
+        assert_eq!(requests[1].body, "");
+    }
+
+    #[tokio::test]
+    async fn csrf_challenge_retries_bounded() {
+        let (server, jar) = mock_jar().await;
+        server.script(
+            Domain::Groups,
+            "DELETE",
+            "/v1/groups/7370273/users/375760054",
+            (0..5)
+                .map(|attempt| MockResponse::csrf_challenge(&format!("token-{}", attempt)))
+                .collect(),
+        );
+
+        let error = crate::groups::remove_user(&jar, GroupId(7370273), UserId(375760054))
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, crate::util::Error::RobloxError(_)));
+        assert_eq!(
+            server.requests().len(),
+            1 + super::MAX_XCSRF_RETRIES as usize
+        );
     }
 }
@@ -1,38 +1,403 @@
-use self::responses::RobloxError;
+use std::fmt;
 
+use reqwest::{Method, StatusCode};
+
+use self::{error_codes::ErrorCode, responses::RobloxError};
+
+pub mod client;
+pub mod error_codes;
+pub mod export;
+pub mod hosts;
+pub mod ids;
 pub mod jar;
 pub mod paging;
+pub mod rate_limit;
 pub mod responses;
+pub mod retry;
 pub mod status_codes;
+pub mod timestamp;
+pub mod url;
 
 // Create Error type
 #[derive(Debug)]
 pub enum Error {
-    Network,
-    JSON,
+    /// The request couldn't be sent or the response couldn't be read
+    Network(RequestDetails),
+    /// The response (or the request body) wasn't the JSON that was expected
+    JSON(RequestDetails),
     Authentication,
     Throttled,
-    RateLimited,
+    /// Roblox kept rate limiting the request after it was retried
+    RateLimited(RequestDetails),
     InvalidPageLimit,
     InvalidRelationshipType,
-    RobloxError(RobloxError),
+    /// Roblox rejected the request, every error it responded with is in the details
+    RobloxError(RequestDetails),
     XcsrfToken,
+    Config(ConfigError),
+    /// A file couldn't be read or written, like the checkpoint of an export
+    Io(RequestDetails),
+    /// The user isn't a member of the group, so they don't have a role in it
+    UserNotInGroup {
+        group_id: ids::GroupId,
+        user_id: ids::UserId,
+    },
+    /// The role can't be given to or taken from anyone, like a group's Guest and Owner roles
+    RoleNotAssignable {
+        group_id: ids::GroupId,
+        role_id: ids::RoleId,
+    },
+    /// The group has no role with this rank or name
+    RoleNotFound {
+        group_id: ids::GroupId,
+        role: String,
+    },
+    /// A change to a role is outside of Roblox's limits, so it wasn't sent
+    InvalidRoleUpdate(RoleUpdateError),
+    /// Moving the user by this many roles would go past the lowest or highest role
+    RankOutOfBounds {
+        group_id: ids::GroupId,
+        user_id: ids::UserId,
+        amount: i64,
+    },
+}
+
+/// Everything known about a request that failed
+#[derive(Debug, Default)]
+pub struct RequestDetails {
+    pub method: Option<Method>,
+    pub url: Option<String>,
+    pub status: Option<StatusCode>,
+    /// Every entry of the `errors` array Roblox responded with
+    pub errors: Vec<RobloxError>,
+    /// The raw response body, kept when it couldn't be decoded
+    pub body: Option<String>,
+    pub source: Option<Box<dyn std::error::Error + Send + Sync>>,
+}
+
+impl RequestDetails {
+    /// Details for an error that didn't come from a request, like the checks done before one
+    pub fn from_roblox_error(error: RobloxError) -> RequestDetails {
+        RequestDetails {
+            errors: vec![error],
+            ..Default::default()
+        }
+    }
+
+    pub fn from_source(source: impl std::error::Error + Send + Sync + 'static) -> RequestDetails {
+        RequestDetails {
+            source: Some(Box::new(source)),
+            ..Default::default()
+        }
+    }
+}
+
+impl fmt::Display for RequestDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+
+        match (&self.method, &self.url) {
+            (Some(method), Some(url)) => parts.push(format!("{} {}", method, url)),
+            (None, Some(url)) => parts.push(url.clone()),
+            _ => {}
+        }
+
+        if let Some(status) = self.status {
+            parts.push(format!("status {}", status));
+        }
+
+        if parts.is_empty() {
+            Ok(())
+        } else {
+            write!(f, " ({})", parts.join(", "))
+        }
+    }
+}
+
+impl Error {
+    /// Details about the request, for errors that came from one
+    pub fn details(&self) -> Option<&RequestDetails> {
+        match self {
+            Error::Network(details)
+            | Error::JSON(details)
+            | Error::RateLimited(details)
+            | Error::RobloxError(details)
+            | Error::Io(details) => Some(details),
+            _ => None,
+        }
+    }
+
+    /// The status code Roblox responded with
+    pub fn status(&self) -> Option<StatusCode> {
+        self.details()?.status
+    }
+
+    /// Every error Roblox responded with
+    pub fn roblox_errors(&self) -> &[RobloxError] {
+        match self.details() {
+            Some(details) => &details.errors,
+            None => &[],
+        }
+    }
+
+    /// The first error Roblox responded with, this is usually the only one
+    pub fn roblox_error(&self) -> Option<&RobloxError> {
+        self.roblox_errors().first()
+    }
+
+    /// The first error code Roblox responded with, as the enum of the endpoint family the
+    /// request was sent to (e.g. [`SetRoleError`](error_codes::SetRoleError) for `set_role`).
+    pub fn code<C: ErrorCode>(&self) -> Option<C> {
+        self.roblox_error().map(|error| C::from_code(error.code))
+    }
+
+    /// Whether sending the same request again later might succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Network(_) | Error::Throttled | Error::RateLimited(_) => true,
+            Error::RobloxError(details) | Error::JSON(details) => details
+                .status
+                .is_some_and(|status| status.is_server_error()),
+            _ => false,
+        }
+    }
+
+    /// Whether the error is caused by missing or invalid credentials
+    pub fn is_auth(&self) -> bool {
+        match self {
+            Error::Authentication | Error::XcsrfToken => true,
+            _ => self.status() == Some(StatusCode::UNAUTHORIZED),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Network(details) => write!(f, "Network error{}", details)?,
+            Error::JSON(details) => write!(f, "JSON error{}", details)?,
+            Error::RateLimited(details) => write!(f, "Throttled, too many requests{}", details)?,
+            Error::RobloxError(details) => {
+                let messages = details
+                    .errors
+                    .iter()
+                    .map(|error| format!("{} (code {})", error.message, error.code))
+                    .collect::<Vec<_>>();
+
+                if messages.is_empty() {
+                    write!(f, "Roblox error{}", details)?;
+                } else {
+                    write!(f, "Roblox error: {}{}", messages.join(", "), details)?;
+                }
+            }
+            Error::Config(error) => write!(f, "Invalid configuration: {:?}", error)?,
+            Error::Io(details) => write!(f, "IO error{}", details)?,
+            Error::Authentication => write!(f, "Authentication error")?,
+            Error::Throttled => write!(f, "Throttled, too many requests")?,
+            Error::InvalidPageLimit => write!(f, "Invalid page limit")?,
+            Error::InvalidRelationshipType => write!(f, "Invalid group relationship type")?,
+            Error::XcsrfToken => write!(f, "Xcsrf token error")?,
+            Error::UserNotInGroup { group_id, user_id } => {
+                write!(f, "User {} is not in group {}", user_id, group_id)?
+            }
+            Error::RoleNotAssignable { group_id, role_id } => write!(
+                f,
+                "Role {} in group {} can't be assigned",
+                role_id, group_id
+            )?,
+            Error::RoleNotFound { group_id, role } => {
+                write!(f, "Group {} has no role {}", group_id, role)?
+            }
+            Error::InvalidRoleUpdate(error) => write!(f, "Invalid role update: {:?}", error)?,
+            Error::RankOutOfBounds {
+                group_id,
+                user_id,
+                amount,
+            } => write!(
+                f,
+                "User {} can't be moved {} roles in group {}",
+                user_id, amount, group_id
+            )?,
+        }
+
+        Ok(())
+    }
+}
+
+/// The cause is only returned by `source`, not written by `Display`, so error reporters that
+/// walk the chain don't print it twice
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        let source = self.details()?.source.as_ref()?;
+        Some(source.as_ref() as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// A setting that was rejected while configuring a [`RequestJar`](jar::RequestJar)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    InvalidProxy(String),
+    InvalidUserAgent,
+    InvalidCookie,
+    InvalidHeader(String),
+    InvalidBaseUrl(String),
+    /// The name of the timeout that was zero
+    InvalidTimeout(String),
+    InvalidRetryPolicy,
+    InvalidRateLimit,
+}
+
+/// A change to a role that was rejected before it was sent, by the limits in the group
+/// configuration metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoleUpdateError {
+    EmptyName,
+    /// The maximum length of a name
+    NameTooLong(i64),
+    /// The maximum length of a description
+    DescriptionTooLong(i64),
+    /// The rank that isn't allowed, the Guest and Owner ranks can't be used either
+    RankOutOfRange(i64),
 }
 
 pub fn error_to_user_message(err: Error) -> String {
     match err {
-        Error::Network => "Network error",
-        Error::JSON => "JSON error",
+        Error::Network(_) => "Network error",
+        Error::JSON(_) => "JSON error",
         Error::Authentication => "Authentication error",
         Error::Throttled => "Throttled, too many requests",
-        Error::RateLimited => "Throttled, too many requests",
+        Error::RateLimited(_) => "Throttled, too many requests",
         Error::InvalidPageLimit => "Invalid page limit",
         Error::InvalidRelationshipType => "Invalid group relationship type",
-        Error::RobloxError(e) => {
-            let msg = format!("Roblox error: {}", e.message);
+        Error::RobloxError(details) => {
+            let msg = match details.errors.first() {
+                Some(e) => format!("Roblox error: {}", e.message),
+                None => "Roblox error".to_string(),
+            };
             return msg;
         }
         Error::XcsrfToken => "Xcsrf token error",
+        Error::Config(e) => {
+            let msg = format!("Invalid configuration: {:?}", e);
+            return msg;
+        }
+        Error::Io(_) => "IO error",
+        Error::UserNotInGroup { .. } => "The user is not in the group",
+        Error::RoleNotAssignable { .. } => "That role can't be assigned",
+        Error::RoleNotFound { .. } => "The group has no such role",
+        Error::InvalidRoleUpdate(e) => {
+            let msg = format!("Invalid role update: {:?}", e);
+            return msg;
+        }
+        Error::RankOutOfBounds { .. } => "There is no role to move the user to",
     }
     .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mock::{testing::mock_jar, MockResponse},
+        util::{hosts::Domain, ids::GroupId},
+    };
+
+    #[tokio::test]
+    async fn roblox_error_details() {
+        let (server, jar) = mock_jar().await;
+        server.script(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1",
+            vec![MockResponse::errors(
+                400,
+                &[
+                    (1, "Group is invalid or does not exist."),
+                    (0, "Something else went wrong."),
+                ],
+            )],
+        );
+
+        let error = crate::groups::group_by_id(&jar, GroupId(1))
+            .await
+            .unwrap_err();
+        let details = error.details().unwrap();
+        assert_eq!(details.method, Some(reqwest::Method::GET));
+        assert!(details
+            .url
+            .as_ref()
+            .unwrap()
+            .ends_with("/groups/v1/groups/1"));
+        assert_eq!(error.status(), Some(reqwest::StatusCode::BAD_REQUEST));
+        assert_eq!(error.roblox_errors().len(), 2);
+        assert_eq!(error.roblox_error().unwrap().code, 1);
+        assert!(!error.is_retryable());
+        assert!(!error.is_auth());
+
+        let message = error.to_string();
+        assert!(message.contains("Group is invalid or does not exist. (code 1)"));
+        assert!(message.contains("Something else went wrong. (code 0)"));
+        assert!(message.contains("status 400"));
+    }
+
+    #[tokio::test]
+    async fn html_error_response() {
+        let (server, jar) = mock_jar().await;
+        server.script(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/7370273",
+            vec![MockResponse::json("<html>Bad Gateway</html>").status(502)],
+        );
+
+        let error = crate::groups::group_by_id(&jar, GroupId(7370273))
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, super::Error::RobloxError(_)));
+        assert!(error.roblox_errors().is_empty());
+        assert_eq!(
+            error.details().unwrap().body.as_deref(),
+            Some("<html>Bad Gateway</html>")
+        );
+        assert!(error.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn invalid_json_response() {
+        let (server, jar) = mock_jar().await;
+        server.script(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/7370273",
+            vec![MockResponse::json(r#"{"id":"not a number"}"#)],
+        );
+
+        let error = crate::groups::group_by_id(&jar, GroupId(7370273))
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, super::Error::JSON(_)));
+        assert_eq!(
+            error.details().unwrap().body.as_deref(),
+            Some(r#"{"id":"not a number"}"#)
+        );
+        let source = std::error::Error::source(&*error).unwrap().to_string();
+        assert!(!error.to_string().contains(&source));
+    }
+
+    #[tokio::test]
+    async fn unauthorized_response() {
+        let (server, jar) = mock_jar().await;
+        server.script(
+            Domain::Users,
+            "GET",
+            "/v1/users/authenticated",
+            vec![MockResponse::errors(
+                401,
+                &[(0, "Authorization has been denied for this request.")],
+            )],
+        );
+
+        let error = crate::users::whoami(&jar).await.unwrap_err();
+        assert!(error.is_auth());
+        assert!(!error.is_retryable());
+    }
+}
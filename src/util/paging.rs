@@ -1,8 +1,15 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::{future::BoxFuture, Stream, TryStreamExt};
 use rspc::Type;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use strum_macros::Display;
 
-use crate::{util::jar::RequestJar, util::Error};
+use crate::util::{jar::RequestJar, url::UrlBuilder, Error};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 #[serde(rename_all = "camelCase")]
@@ -14,7 +21,8 @@ pub struct PagedResponse<T> {
 
 #[derive(PartialEq, Display, Debug, Clone, Copy)]
 pub enum PageLimit {
-    /// Retrieves all pages
+    /// Retrieves all pages, up to [`get_limit`](Self::get_limit) (20000) items. Use a
+    /// [`Paginator`] to go through larger listings.
     All,
     /// Retrieves 10 items
     Limit10,
@@ -26,6 +34,9 @@ pub enum PageLimit {
     Limit100,
 }
 
+/// The most items Roblox returns in a single page
+pub const MAX_PAGE_SIZE: i64 = 100;
+
 // Implement a trait for PageLimit to get the limit as a number
 impl PageLimit {
     pub fn get_limit(&self) -> i64 {
@@ -58,63 +69,427 @@ impl SortOrder {
     }
 }
 
-/// Retrieves all pages of a paged response
-async fn get_all_pages<T>(jar: &RequestJar, url: &str) -> Result<Vec<T>, Box<Error>>
+/// Retrieves a single page, including the cursors to the pages around it.
+/// [`PageLimit::All`] gets a page of 100 items, use a [`Paginator`] to go through every page.
+pub async fn get_paged_response<T>(
+    jar: &RequestJar,
+    url: &UrlBuilder,
+    limit: PageLimit,
+    cursor: Option<&str>,
+) -> Result<PagedResponse<T>, Box<Error>>
 where
-    T: for<'de> Deserialize<'de>,
+    T: DeserializeOwned,
 {
-    let mut data = Vec::new();
-    let mut next_page_cursor = Some(String::new());
-
-    while let Some(cursor) = next_page_cursor {
-        let url = if cursor.is_empty() {
-            url.to_string()
-        } else {
-            format!("{}?cursor={}", url, cursor)
-        };
+    let url = url
+        .clone()
+        .query_opt("cursor", cursor.filter(|cursor| !cursor.is_empty()))
+        .query("limit", limit.get_limit().min(MAX_PAGE_SIZE));
 
-        // Extend the url with the limit
-        let url = format!(
-            "{}{}limit=100",
-            url,
-            if url.contains("?") { "&" } else { "?" }
-        );
-
-        let response = jar.get_json::<PagedResponse<T>>(&url).await?;
-        data.extend(response.data);
-        next_page_cursor = response.next_page_cursor;
-    }
-
-    Ok(data)
+    jar.get_json::<PagedResponse<T>>(&url.build()).await
 }
 
-/// Retrieves a paged response
+/// Retrieves a paged response. [`PageLimit::All`] keeps every page in memory and stops at
+/// 20000 items, a [`Paginator`] goes through any number of them a page at a time.
 pub async fn get_page<T>(
     jar: &RequestJar,
-    url: &str,
+    url: UrlBuilder,
     limit: PageLimit,
     cursor: Option<String>,
 ) -> Result<Vec<T>, Box<Error>>
 where
-    T: for<'de> Deserialize<'de>,
+    T: DeserializeOwned + Send,
 {
-    if limit.get_limit() > 100 {
-        return get_all_pages(jar, url).await;
+    if limit == PageLimit::All {
+        return Paginator::new(jar, url)
+            .cursor(cursor)
+            .max_items(limit.get_limit() as usize)
+            .try_collect()
+            .await;
+    }
+
+    let response = get_paged_response(jar, &url, limit, cursor.as_deref()).await?;
+    Ok(response.data)
+}
+
+/// Goes through a paged endpoint lazily, only requesting the next page once every item of the
+/// current one has been used. Items are read with [`Stream`] (e.g. [`StreamExt::next`](futures::StreamExt::next))
+/// or a page at a time with [`Paginator::next_page`].
+///
+/// Dropping the paginator stops it, and the cursors can be saved to continue later on:
+///
+/// ```no_run
+/// # use futures::StreamExt;
+/// # use rustyroblox::{groups, util::{ids::GroupId, jar::RequestJar}};
+/// # async fn export(jar: &RequestJar, cursor: Option<String>) -> Option<String> {
+/// let mut members = groups::members_stream(jar, GroupId(7370273), None).cursor(cursor);
+/// while let Some(page) = members.next_page().await.unwrap() {
+///     println!("{} members", page.len());
+/// }
+/// members.next_page_cursor().map(|cursor| cursor.to_string())
+/// # }
+/// ```
+pub struct Paginator<'a, T> {
+    jar: &'a RequestJar,
+    url: UrlBuilder,
+    page_size: PageLimit,
+    max_items: Option<usize>,
+    yielded: usize,
+    /// The cursor the next page is requested with
+    cursor: Option<String>,
+    page_cursor: Option<String>,
+    next_page_cursor: Option<String>,
+    previous_page_cursor: Option<String>,
+    items: VecDeque<T>,
+    finished: bool,
+    request: Option<BoxFuture<'a, Result<PagedResponse<T>, Box<Error>>>>,
+}
+
+// The items are never pinned, only the request is and that's boxed
+impl<T> Unpin for Paginator<'_, T> {}
+
+impl<'a, T> Paginator<'a, T>
+where
+    T: DeserializeOwned + Send + 'a,
+{
+    /// Creates a paginator for a paged endpoint, the url shouldn't contain a cursor or limit
+    pub fn new(jar: &'a RequestJar, url: UrlBuilder) -> Paginator<'a, T> {
+        Paginator {
+            jar,
+            url,
+            page_size: PageLimit::Limit100,
+            max_items: None,
+            yielded: 0,
+            cursor: None,
+            page_cursor: None,
+            next_page_cursor: None,
+            previous_page_cursor: None,
+            items: VecDeque::new(),
+            finished: false,
+            request: None,
+        }
+    }
+
+    /// How many items are requested per page, [`PageLimit::All`] requests 100
+    pub fn page_size(mut self, page_size: PageLimit) -> Paginator<'a, T> {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Starts at a cursor from an earlier run instead of the first page
+    pub fn cursor(mut self, cursor: Option<String>) -> Paginator<'a, T> {
+        self.cursor = cursor.filter(|cursor| !cursor.is_empty());
+        self
+    }
+
+    /// Stops after this many items, even if there are more pages
+    pub fn max_items(mut self, max_items: usize) -> Paginator<'a, T> {
+        self.max_items = Some(max_items);
+        self
+    }
+
+    /// Lowers the item cap by the items an earlier run already read, for paginators that
+    /// continue from a saved cursor
+    pub(crate) fn reduce_max_items(mut self, items_read_before: usize) -> Paginator<'a, T> {
+        self.max_items = self
+            .max_items
+            .map(|max_items| max_items.saturating_sub(items_read_before));
+        self
+    }
+
+    /// The url of the endpoint, without the cursor and limit
+    pub fn url(&self) -> &UrlBuilder {
+        &self.url
+    }
+
+    /// The cursor the current page was requested with, `None` for the first page.
+    /// Resuming from it repeats the items of the current page that were already read.
+    pub fn page_cursor(&self) -> Option<&str> {
+        self.page_cursor.as_deref()
+    }
+
+    /// The cursor of the page after the current one, `None` before the first page has been
+    /// requested and once the last page has been reached
+    pub fn next_page_cursor(&self) -> Option<&str> {
+        self.next_page_cursor.as_deref()
+    }
+
+    /// The cursor of the page before the current one
+    pub fn previous_page_cursor(&self) -> Option<&str> {
+        self.previous_page_cursor.as_deref()
+    }
+
+    /// How many items have been read so far
+    pub fn items_read(&self) -> usize {
+        self.yielded
+    }
+
+    /// Checks whether every page has been read or the item cap has been reached
+    pub fn is_finished(&self) -> bool {
+        self.is_capped() || (self.finished && self.items.is_empty())
+    }
+
+    /// Gets the rest of the current page, or the next page if every item of the current one
+    /// has been read. Empty pages that still point at a next page are skipped. Returns `None`
+    /// once there are no items left.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<T>>, Box<Error>> {
+        while self.items.is_empty() && !self.is_finished() {
+            let request = match self.request.take() {
+                Some(request) => request,
+                None => self.request_next_page(),
+            };
+            match request.await {
+                Ok(response) => self.receive_page(response),
+                Err(error) => {
+                    self.finished = true;
+                    return Err(error);
+                }
+            }
+        }
+
+        let mut count = self.items.len();
+        if let Some(max_items) = self.max_items {
+            count = count.min(max_items.saturating_sub(self.yielded));
+        }
+        if count == 0 {
+            return Ok(None);
+        }
+
+        self.yielded += count;
+        Ok(Some(self.items.drain(..count).collect()))
     }
 
-    let mut url = if let Some(cursor) = cursor {
-        format!("{}?cursor={}", url, cursor)
-    } else {
-        url.to_string()
+    fn is_capped(&self) -> bool {
+        self.max_items
+            .map(|max_items| self.yielded >= max_items)
+            .unwrap_or(false)
+    }
+
+    fn request_next_page(&mut self) -> BoxFuture<'a, Result<PagedResponse<T>, Box<Error>>> {
+        self.page_cursor = self.cursor.clone();
+
+        let jar = self.jar;
+        let url = self.url.clone();
+        let page_size = self.page_size;
+        let cursor = self.cursor.clone();
+        Box::pin(async move { get_paged_response(jar, &url, page_size, cursor.as_deref()).await })
+    }
+
+    fn receive_page(&mut self, response: PagedResponse<T>) {
+        self.next_page_cursor = response
+            .next_page_cursor
+            .filter(|cursor| !cursor.is_empty());
+        self.previous_page_cursor = response
+            .previous_page_cursor
+            .filter(|cursor| !cursor.is_empty());
+        self.cursor = self.next_page_cursor.clone();
+        self.finished = self.cursor.is_none();
+        self.items.extend(response.data);
+    }
+}
+
+impl<'a, T> Stream for Paginator<'a, T>
+where
+    T: DeserializeOwned + Send + 'a,
+{
+    type Item = Result<T, Box<Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.is_capped() {
+                return Poll::Ready(None);
+            }
+
+            if let Some(item) = this.items.pop_front() {
+                this.yielded += 1;
+                return Poll::Ready(Some(Ok(item)));
+            }
+
+            if this.finished {
+                return Poll::Ready(None);
+            }
+
+            let mut request = this
+                .request
+                .take()
+                .unwrap_or_else(|| this.request_next_page());
+            let Poll::Ready(response) = request.as_mut().poll(cx) else {
+                this.request = Some(request);
+                return Poll::Pending;
+            };
+
+            match response {
+                Ok(response) => this.receive_page(response),
+                Err(error) => {
+                    this.finished = true;
+                    return Poll::Ready(Some(Err(error)));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PageLimit;
+    use crate::{
+        mock::{mock_user, testing::paged_members_server, MockResponse, MockServer},
+        util::{
+            hosts::Domain,
+            ids::{GroupId, UserId},
+        },
     };
 
-    url = format!(
-        "{}{}limit={}",
-        url,
-        if url.contains("?") { "&" } else { "?" },
-        limit.get_limit()
-    );
+    #[tokio::test]
+    async fn paginator_streams_pages() {
+        use futures::TryStreamExt;
 
-    let response = jar.get_json::<PagedResponse<T>>(&url).await?;
-    Ok(response.data)
+        let server = paged_members_server().await;
+        let jar = server.jar().await;
+
+        let mut members = crate::groups::members_stream(&jar, GroupId(1), None);
+        assert_eq!(members.next_page_cursor(), None);
+
+        let user_ids = (&mut members)
+            .map_ok(|member| member.user.user_id)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(user_ids, [1, 2, 3, 4, 5].map(UserId));
+        assert!(members.is_finished());
+        assert_eq!(members.items_read(), 5);
+        assert_eq!(members.page_cursor(), Some("page3"));
+        assert_eq!(members.previous_page_cursor(), Some("page2"));
+        assert_eq!(members.next_page_cursor(), None);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].query_param("cursor"), None);
+        assert_eq!(requests[0].query_param("sortOrder"), Some("Asc"));
+        assert_eq!(requests[1].query_param("cursor"), Some("page2"));
+        assert_eq!(requests[1].query_param("limit"), Some("100"));
+
+        // PageLimit::All goes through the same pages
+        let members = crate::groups::members(&jar, GroupId(1), PageLimit::All, None, None)
+            .await
+            .unwrap();
+        assert_eq!(members.len(), 5);
+
+        // A single page can be requested from a cursor
+        let members = crate::groups::members(
+            &jar,
+            GroupId(1),
+            PageLimit::Limit10,
+            None,
+            Some("page3".to_string()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].user.user_id, UserId(5));
+    }
+
+    #[tokio::test]
+    async fn paginator_stops_early() {
+        use futures::StreamExt;
+
+        let server = paged_members_server().await;
+        let jar = server.jar().await;
+
+        // Only the pages needed for the first item are requested
+        let first = crate::groups::members_stream(&jar, GroupId(1), None)
+            .next()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.user.user_id, UserId(1));
+        assert_eq!(server.requests().len(), 1);
+
+        // The cap can end in the middle of a page
+        let mut members = crate::groups::members_stream(&jar, GroupId(1), None).max_items(3);
+        assert_eq!(members.next_page().await.unwrap().unwrap().len(), 2);
+        assert_eq!(members.next_page().await.unwrap().unwrap().len(), 1);
+        assert!(members.next_page().await.unwrap().is_none());
+        assert!(members.is_finished());
+        assert_eq!(members.next_page_cursor(), Some("page3"));
+        assert_eq!(server.requests().len(), 3);
+
+        // And a new paginator can resume from the saved cursor
+        let resumed = crate::groups::members_stream(&jar, GroupId(1), None)
+            .cursor(members.next_page_cursor().map(|cursor| cursor.to_string()))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(resumed.len(), 1);
+        assert_eq!(resumed[0].as_ref().unwrap().user.user_id, UserId(5));
+    }
+
+    #[tokio::test]
+    async fn paginator_returns_errors() {
+        use futures::StreamExt;
+
+        let server = MockServer::start().await;
+        let jar = server.jar().await;
+        server.mock(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/users",
+            MockResponse::errors(400, &[(1, "The group is invalid or does not exist.")]),
+        );
+
+        let results = crate::groups::members_stream(&jar, GroupId(1), None)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0]
+                .as_ref()
+                .unwrap_err()
+                .code::<crate::util::error_codes::GroupError>(),
+            Some(crate::util::error_codes::GroupError::InvalidGroup)
+        );
+    }
+
+    #[tokio::test]
+    async fn paginator_skips_empty_pages() {
+        let member = |user_id: i64| {
+            serde_json::json!({
+                "user": mock_user(user_id),
+                "role": { "id": 1, "name": "Member", "rank": 1 },
+            })
+        };
+
+        let server = MockServer::start().await;
+        let jar = server.jar().await;
+        server.mock(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/users",
+            MockResponse::page(vec![member(1)], Some("page2")),
+        );
+        server.mock(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/users?cursor=page2",
+            MockResponse::page(Vec::new(), Some("page3")),
+        );
+        server.mock(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/users?cursor=page3",
+            MockResponse::page(vec![member(2)], None),
+        );
+
+        let mut members = crate::groups::members_stream(&jar, GroupId(1), None);
+        let first = members.next_page().await.unwrap().unwrap();
+        assert_eq!(first[0].user.user_id, UserId(1));
+
+        // The empty page doesn't end the listing
+        let second = members.next_page().await.unwrap().unwrap();
+        assert_eq!(second[0].user.user_id, UserId(2));
+        assert_eq!(members.page_cursor(), Some("page3"));
+
+        assert!(members.next_page().await.unwrap().is_none());
+        assert_eq!(server.requests().len(), 3);
+    }
 }
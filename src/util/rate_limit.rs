@@ -0,0 +1,366 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use reqwest::Method;
+use strum_macros::Display;
+use tokio::time::Instant;
+
+use super::hosts::Domain;
+
+/// A group of endpoints on a domain that share a rate limit on Roblox's side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display)]
+pub enum RouteFamily {
+    /// Reading data, e.g. getting a group or a user
+    Read,
+    /// Changing data that isn't covered by a more specific family
+    Write,
+    /// Changing group memberships: ranking, exiling and handling join requests
+    MembershipWrite,
+    /// Searching for users or groups
+    Search,
+    /// Looking up many items in a single request, e.g. thumbnails or users by id
+    Batch,
+}
+
+impl RouteFamily {
+    /// Works out which family a request belongs to from its domain, method and path.
+    /// The path is relative to the domain, e.g. `/v1/groups/7370273/users/375760054`.
+    pub fn classify(domain: Domain, method: &Method, path: &str) -> RouteFamily {
+        let path = path.split('?').next().unwrap_or_default();
+        let is_read = method == Method::GET;
+
+        match domain {
+            Domain::Thumbnails => RouteFamily::Batch,
+            Domain::Users if path.starts_with("/v1/users/search") => RouteFamily::Search,
+            Domain::Users if path == "/v1/users" || path == "/v1/usernames/users" => {
+                RouteFamily::Batch
+            }
+            Domain::Groups if path.starts_with("/v1/groups/search") => RouteFamily::Search,
+            Domain::Groups
+                if !is_read && (path.contains("/users/") || path.contains("/join-requests")) =>
+            {
+                RouteFamily::MembershipWrite
+            }
+            _ if is_read => RouteFamily::Read,
+            _ => RouteFamily::Write,
+        }
+    }
+}
+
+/// How many requests may be sent within a time window.
+/// The full amount can be sent in a burst, after which requests are spread evenly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+impl RateLimit {
+    pub fn new(requests: u32, per: Duration) -> RateLimit {
+        RateLimit { requests, per }
+    }
+
+    pub fn per_second(requests: u32) -> RateLimit {
+        RateLimit::new(requests, Duration::from_secs(1))
+    }
+
+    pub fn per_minute(requests: u32) -> RateLimit {
+        RateLimit::new(requests, Duration::from_secs(60))
+    }
+
+    /// Checks that at least one request is allowed in a window longer than zero
+    pub fn is_valid(&self) -> bool {
+        self.requests > 0 && !self.per.is_zero()
+    }
+
+    fn get_capacity(&self) -> f64 {
+        self.requests.max(1) as f64
+    }
+
+    fn get_refill_rate(&self) -> f64 {
+        self.get_capacity() / self.per.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Can go negative, every waiting request has reserved a token it will get later
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Throttles requests before they are sent using a token bucket per domain and route family,
+/// so bursts don't get the account temporarily blocked by Roblox.
+///
+/// Requests without a configured limit are never throttled.
+#[derive(Debug)]
+pub struct RateLimiter {
+    limits: HashMap<(Domain, RouteFamily), RateLimit>,
+    buckets: Mutex<HashMap<(Domain, RouteFamily), Bucket>>,
+}
+
+impl Default for RateLimiter {
+    /// Cautious limits for the route families that bursts usually hit. Roblox doesn't publish
+    /// its limits, so these are a starting point to adjust with [`set_limit`](Self::set_limit).
+    fn default() -> Self {
+        let mut rate_limiter = RateLimiter::unlimited();
+        rate_limiter.set_limit(
+            Domain::Groups,
+            RouteFamily::MembershipWrite,
+            RateLimit::per_minute(60),
+        );
+        rate_limiter.set_limit(
+            Domain::Groups,
+            RouteFamily::Search,
+            RateLimit::per_minute(60),
+        );
+        rate_limiter.set_limit(
+            Domain::Users,
+            RouteFamily::Search,
+            RateLimit::per_minute(60),
+        );
+        rate_limiter.set_limit(Domain::Users, RouteFamily::Batch, RateLimit::per_second(10));
+        rate_limiter.set_limit(
+            Domain::Thumbnails,
+            RouteFamily::Batch,
+            RateLimit::per_second(20),
+        );
+        rate_limiter
+    }
+}
+
+impl RateLimiter {
+    pub fn new() -> RateLimiter {
+        RateLimiter::default()
+    }
+
+    /// A rate limiter without any limits
+    pub fn unlimited() -> RateLimiter {
+        RateLimiter {
+            limits: HashMap::new(),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the limit for a route family on a domain, replacing the previous one
+    pub fn set_limit(&mut self, domain: Domain, family: RouteFamily, limit: RateLimit) {
+        self.limits.insert((domain, family), limit);
+        self.buckets
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&(domain, family));
+    }
+
+    /// Stops throttling a route family on a domain
+    pub fn remove_limit(&mut self, domain: Domain, family: RouteFamily) {
+        self.limits.remove(&(domain, family));
+        self.buckets
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&(domain, family));
+    }
+
+    /// Every configured limit
+    pub fn limits(&self) -> impl Iterator<Item = (Domain, RouteFamily, RateLimit)> + '_ {
+        self.limits
+            .iter()
+            .map(|((domain, family), limit)| (*domain, *family, *limit))
+    }
+
+    pub fn get_limit(&self, domain: Domain, family: RouteFamily) -> Option<RateLimit> {
+        self.limits.get(&(domain, family)).copied()
+    }
+
+    /// Waits until a request for the route family may be sent.
+    /// Callers are let through in the order they started waiting.
+    pub async fn acquire(&self, domain: Domain, family: RouteFamily) {
+        let Some(limit) = self.get_limit(domain, family) else {
+            return;
+        };
+
+        let wait = {
+            let mut buckets = self
+                .buckets
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            let now = Instant::now();
+            let bucket = buckets.entry((domain, family)).or_insert(Bucket {
+                tokens: limit.get_capacity(),
+                updated: now,
+            });
+
+            let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+            bucket.tokens =
+                (bucket.tokens + elapsed * limit.get_refill_rate()).min(limit.get_capacity());
+            bucket.updated = now;
+            bucket.tokens -= 1.0;
+
+            if bucket.tokens >= 0.0 {
+                return;
+            }
+
+            Duration::from_secs_f64(-bucket.tokens / limit.get_refill_rate())
+        };
+
+        // Gives the token back when the request is dropped while it's still waiting
+        let reservation = Reservation {
+            rate_limiter: self,
+            key: (domain, family),
+            capacity: limit.get_capacity(),
+        };
+        tokio::time::sleep(wait).await;
+        std::mem::forget(reservation);
+    }
+}
+
+/// A token taken from a bucket by a request that is waiting for it
+struct Reservation<'a> {
+    rate_limiter: &'a RateLimiter,
+    key: (Domain, RouteFamily),
+    capacity: f64,
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        let mut buckets = self
+            .rate_limiter
+            .buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(bucket) = buckets.get_mut(&self.key) {
+            bucket.tokens = (bucket.tokens + 1.0).min(self.capacity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RateLimit, RateLimiter, RouteFamily};
+    use crate::{
+        mock::{testing::mock_jar, MockResponse},
+        util::{
+            hosts::Domain,
+            ids::{GroupId, RoleId, UserId},
+        },
+    };
+
+    #[test]
+    fn route_families() {
+        use reqwest::Method;
+
+        let cases = [
+            (
+                Domain::Groups,
+                Method::PATCH,
+                "/v1/groups/7370273/users/375760054",
+                RouteFamily::MembershipWrite,
+            ),
+            (
+                Domain::Groups,
+                Method::POST,
+                "/v1/groups/7370273/join-requests",
+                RouteFamily::MembershipWrite,
+            ),
+            (
+                Domain::Groups,
+                Method::GET,
+                "/v1/groups/7370273/users",
+                RouteFamily::Read,
+            ),
+            (
+                Domain::Groups,
+                Method::GET,
+                "/v1/groups/search?keyword=clanny",
+                RouteFamily::Search,
+            ),
+            (
+                Domain::Groups,
+                Method::POST,
+                "/v1/groups/7370273/wall/posts",
+                RouteFamily::Write,
+            ),
+            (
+                Domain::Users,
+                Method::GET,
+                "/v1/users/search?keyword=miemper",
+                RouteFamily::Search,
+            ),
+            (
+                Domain::Users,
+                Method::POST,
+                "/v1/usernames/users",
+                RouteFamily::Batch,
+            ),
+            (
+                Domain::Thumbnails,
+                Method::GET,
+                "/v1/assets?assetIds=1818",
+                RouteFamily::Batch,
+            ),
+        ];
+
+        for (domain, method, path, family) in cases {
+            assert_eq!(
+                RouteFamily::classify(domain, &method, path),
+                family,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn rate_limiter_throttles_bursts() {
+        let (server, mut jar) = mock_jar().await;
+        let mut rate_limiter = RateLimiter::unlimited();
+        rate_limiter.set_limit(
+            Domain::Groups,
+            RouteFamily::MembershipWrite,
+            RateLimit::new(1, std::time::Duration::from_millis(100)),
+        );
+        jar.set_rate_limiter(rate_limiter);
+        server.mock(
+            Domain::Groups,
+            "PATCH",
+            "/v1/groups/7370273/users/375760054",
+            MockResponse::json("{}"),
+        );
+
+        let started = std::time::Instant::now();
+        for _ in 0..3 {
+            crate::groups::set_role(&jar, GroupId(7370273), UserId(375760054), RoleId(47932104))
+                .await
+                .unwrap();
+        }
+
+        // The first request goes through right away, the other two wait for a token each
+        assert!(started.elapsed() >= std::time::Duration::from_millis(200));
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn rate_limiter_cancelled_wait() {
+        let mut rate_limiter = RateLimiter::unlimited();
+        rate_limiter.set_limit(
+            Domain::Groups,
+            RouteFamily::Write,
+            RateLimit::new(1, std::time::Duration::from_millis(100)),
+        );
+
+        let started = std::time::Instant::now();
+        rate_limiter
+            .acquire(Domain::Groups, RouteFamily::Write)
+            .await;
+        let cancelled = tokio::time::timeout(
+            std::time::Duration::from_millis(10),
+            rate_limiter.acquire(Domain::Groups, RouteFamily::Write),
+        )
+        .await;
+        assert!(cancelled.is_err());
+
+        // The cancelled request gave its token back, so this one only waits for the next token
+        rate_limiter
+            .acquire(Domain::Groups, RouteFamily::Write)
+            .await;
+        assert!(started.elapsed() < std::time::Duration::from_millis(150));
+    }
+}
@@ -24,3 +24,7 @@ pub enum RobloxResponse<T> {
 pub struct DataWrapper<T> {
     pub data: T,
 }
+
+/// Roblox returns an empty object from most endpoints that don't return data
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct EmptyResponse {}
@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+
+/// Controls how a [`RequestJar`](super::jar::RequestJar) retries requests Roblox rejected
+/// with a 429 (Too Many Requests).
+///
+/// Delays grow exponentially from `base_delay` up to `max_delay`, and are randomized by
+/// `jitter` so many tasks hitting the same limit don't all retry at the same moment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// The total amount of attempts, including the first one. 1 disables retrying.
+    pub max_attempts: u32,
+    /// The delay before the first retry
+    pub base_delay: Duration,
+    /// The longest delay between two attempts, not counting `Retry-After`
+    pub max_delay: Duration,
+    /// How much of the delay may be randomly taken off, from 0.0 (none) to 1.0 (all of it)
+    pub jitter: f64,
+    /// Wait for as long as the `Retry-After` header says when Roblox sends it
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: 0.5,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries, rate limits are returned as [`Error::RateLimited`](super::Error::RateLimited)
+    pub fn none() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Checks that at least one attempt is made and the jitter is between 0.0 and 1.0
+    pub fn is_valid(&self) -> bool {
+        self.max_attempts >= 1 && (0.0..=1.0).contains(&self.jitter)
+    }
+
+    /// Gets how long to wait before the next attempt.
+    /// `attempt` is the attempt that just failed, starting at 1.
+    pub fn get_delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if self.respect_retry_after {
+            if let Some(retry_after) = retry_after {
+                return retry_after;
+            }
+        }
+
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.pow(exponent))
+            .min(self.max_delay);
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return delay;
+        }
+
+        delay.mul_f64(1.0 - rand::thread_rng().gen_range(0.0..jitter))
+    }
+}
+
+/// Reads the `Retry-After` header. Roblox only sends it in seconds, so dates are ignored.
+pub(crate) fn get_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mock::{testing::mock_jar, MockResponse},
+        util::{hosts::Domain, ids::GroupId},
+    };
+
+    #[tokio::test]
+    async fn rate_limit_retried() {
+        let (server, jar) = mock_jar().await;
+        server.script(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/7370273",
+            vec![
+                MockResponse::rate_limited().header("Retry-After", "0"),
+                MockResponse::rate_limited().header("Retry-After", "0"),
+            ],
+        );
+
+        let group = crate::groups::group_by_id(&jar, GroupId(7370273))
+            .await
+            .unwrap();
+        assert_eq!(group.id, GroupId(7370273));
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn rate_limit_retries_exhausted() {
+        let (server, mut jar) = mock_jar().await;
+        jar.set_retry_policy(super::RetryPolicy {
+            max_attempts: 2,
+            base_delay: std::time::Duration::ZERO,
+            ..Default::default()
+        });
+        server.script(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/7370273",
+            vec![MockResponse::rate_limited(), MockResponse::rate_limited()],
+        );
+
+        let error = crate::groups::group_by_id(&jar, GroupId(7370273))
+            .await
+            .unwrap_err();
+        assert!(matches!(*error, crate::util::Error::RateLimited(_)));
+        assert_eq!(server.requests().len(), 2);
+    }
+}
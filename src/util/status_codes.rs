@@ -1,4 +1,4 @@
-use crate::util::Error;
+use crate::util::{Error, RequestDetails};
 use reqwest::StatusCode;
 
 pub fn status_code_to_error(status_code: StatusCode) -> Option<Error> {
@@ -7,6 +7,9 @@ pub fn status_code_to_error(status_code: StatusCode) -> Option<Error> {
         StatusCode::UNAUTHORIZED => Some(Error::Authentication),
         StatusCode::FORBIDDEN => Some(Error::Authentication),
         StatusCode::TOO_MANY_REQUESTS => Some(Error::Throttled),
-        _ => Some(Error::Network),
+        _ => Some(Error::RobloxError(RequestDetails {
+            status: Some(status_code),
+            ..Default::default()
+        })),
     }
 }
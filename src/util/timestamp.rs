@@ -0,0 +1,310 @@
+//! The type timestamps in responses are stored as.
+//!
+//! A [`Timestamp`] keeps the string Roblox sent, e.g. `2017-01-23T17:41:42.853Z`, and is
+//! compared by the point in time it stands for, so timestamps can be sorted and filtered
+//! without the `chrono` feature. With it enabled, chrono parses the timestamps and
+//! `Timestamp::parsed` turns one into a `DateTime<Utc>`.
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use rspc::Type;
+use serde::{Deserialize, Serialize};
+
+/// A point in time Roblox sent in a response, kept as it was sent
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(transparent)]
+pub struct Timestamp(String);
+
+impl Timestamp {
+    pub fn new(timestamp: impl Into<String>) -> Timestamp {
+        Timestamp(timestamp.into())
+    }
+
+    /// The timestamp as Roblox sent it
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The seconds and nanoseconds since the Unix epoch. Most endpoints send RFC 3339, but
+    /// some leave out the offset, those are in UTC as well. `None` if Roblox sent something
+    /// else.
+    pub fn unix_timestamp(&self) -> Option<(i64, u32)> {
+        parse(&self.0)
+    }
+
+    /// How long ago this was, `None` if it's in the future or isn't a timestamp
+    pub fn elapsed(&self) -> Option<Duration> {
+        let (seconds, nanoseconds) = self.unix_timestamp()?;
+        let timestamp = if seconds >= 0 {
+            UNIX_EPOCH.checked_add(Duration::new(seconds as u64, nanoseconds))?
+        } else {
+            UNIX_EPOCH.checked_sub(Duration::from_secs(seconds.unsigned_abs()))?
+                + Duration::from_nanos(nanoseconds as u64)
+        };
+        SystemTime::now().duration_since(timestamp).ok()
+    }
+
+    /// The point in time this stands for, `None` if Roblox sent something that isn't a timestamp
+    #[cfg(feature = "chrono")]
+    pub fn parsed(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        parse_datetime(&self.0)
+    }
+
+    /// Orders timestamps by when they were, and anything that isn't a timestamp after them
+    fn key(&self) -> (Option<(i64, u32)>, &str) {
+        match self.unix_timestamp() {
+            Some(instant) => (Some(instant), ""),
+            None => (None, &self.0),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+fn parse(timestamp: &str) -> Option<(i64, u32)> {
+    let parsed = parse_datetime(timestamp)?;
+    Some((parsed.timestamp(), parsed.timestamp_subsec_nanos()))
+}
+
+/// Parses RFC 3339, an offset without a colon, or no offset at all for UTC
+#[cfg(feature = "chrono")]
+fn parse_datetime(timestamp: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    use chrono::{DateTime, NaiveDateTime, Utc};
+
+    DateTime::parse_from_rfc3339(timestamp)
+        .or_else(|_| DateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.f%#z"))
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.f")
+                .map(|timestamp| timestamp.and_utc())
+        })
+        .ok()
+}
+
+/// Parses `YYYY-MM-DDTHH:MM:SS`, with optional fractional seconds and an optional `Z`,
+/// `±HH:MM`, `±HHMM` or `±HH` offset
+#[cfg(not(feature = "chrono"))]
+fn parse(timestamp: &str) -> Option<(i64, u32)> {
+    let number = |digits: &str| -> Option<i64> {
+        if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()
+    };
+
+    let (date, time) = timestamp.split_once('T')?;
+    let mut date_parts = date.splitn(3, '-');
+    let year = number(date_parts.next()?)?;
+    let month = number(date_parts.next()?)?;
+    let day = number(date_parts.next()?)?;
+
+    let (time, offset) = match time.find(['Z', 'z', '+', '-']) {
+        Some(index) => time.split_at(index),
+        None => (time, ""),
+    };
+    let offset = match offset {
+        "" | "Z" | "z" => 0,
+        _ => {
+            let digits = &offset[1..];
+            let (hours, minutes) = match digits.split_once(':') {
+                Some(parts) => parts,
+                None if digits.len() == 4 => digits.split_at(2),
+                None if digits.len() == 2 => (digits, "00"),
+                None => return None,
+            };
+            let (hours, minutes) = (number(hours)?, number(minutes)?);
+            if hours > 23 || minutes > 59 {
+                return None;
+            }
+            let seconds = hours * 3600 + minutes * 60;
+            if offset.starts_with('-') {
+                -seconds
+            } else {
+                seconds
+            }
+        }
+    };
+
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut time_parts = time.splitn(3, ':');
+    let hour = number(time_parts.next()?)?;
+    let minute = number(time_parts.next()?)?;
+    let second = number(time_parts.next()?)?;
+    let nanoseconds = match fraction {
+        "" => 0,
+        _ if fraction.len() > 9 => number(&fraction[..9])?,
+        _ => number(fraction)? * 10_i64.pow(9 - fraction.len() as u32),
+    };
+
+    if !(1..=12).contains(&month)
+        || !(1..=days_in_month(year, month)).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    Some((
+        days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second - offset,
+        nanoseconds as u32,
+    ))
+}
+
+#[cfg(not(feature = "chrono"))]
+fn days_in_month(year: i64, month: i64) -> i64 {
+    let leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    match month {
+        2 if leap_year => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The days between 1970-01-01 and a date, from Howard Hinnant's `days_from_civil`
+#[cfg(not(feature = "chrono"))]
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+impl PartialEq for Timestamp {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Timestamp {}
+
+impl Hash for Timestamp {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.key(), other.key()) {
+            ((Some(a), _), (Some(b), _)) => a.cmp(&b),
+            ((Some(_), _), (None, _)) => Ordering::Less,
+            ((None, _), (Some(_), _)) => Ordering::Greater,
+            ((None, a), (None, b)) => a.cmp(b),
+        }
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for Timestamp {
+    fn from(timestamp: String) -> Self {
+        Timestamp(timestamp)
+    }
+}
+
+impl From<&str> for Timestamp {
+    fn from(timestamp: &str) -> Self {
+        Timestamp(timestamp.to_string())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::Utc>> for Timestamp {
+    fn from(timestamp: chrono::DateTime<chrono::Utc>) -> Self {
+        Timestamp(timestamp.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Timestamp;
+    use crate::{mock::testing::mock_jar, util::ids::UserId};
+
+    #[tokio::test]
+    async fn timestamps() {
+        let (_server, jar) = mock_jar().await;
+        let user = crate::users::user_by_id(&jar, UserId(375760054))
+            .await
+            .unwrap();
+        assert_eq!(user.created.as_str(), "2016-12-08T16:14:03.003Z");
+        assert_eq!(user.created.to_string(), "2016-12-08T16:14:03.003Z");
+        assert!(user.created.elapsed().unwrap().as_secs() > 365 * 86_400);
+
+        // Timestamps are compared by when they were, however Roblox formatted them
+        assert_eq!(
+            Timestamp::from("2016-12-08T16:14:03.003"),
+            Timestamp::from("2016-12-08T16:14:03.0030000Z")
+        );
+        assert_eq!(
+            Timestamp::from("2016-12-08T18:14:03.003+02:00"),
+            user.created
+        );
+        assert!(
+            Timestamp::from("2016-12-08T16:14:03.1Z") > Timestamp::from("2016-12-08T16:14:03.03Z")
+        );
+        assert!(Timestamp::from("2016-12-08T16:14:03Z") < Timestamp::from("2017-01-01T00:00:00Z"));
+        assert_eq!(
+            Timestamp::from("1970-01-02T00:00:00Z").unix_timestamp(),
+            Some((86_400, 0))
+        );
+        assert_eq!(
+            Timestamp::from("2016-12-08T18:14:03.003+0200"),
+            user.created
+        );
+        assert_eq!(Timestamp::from("2016-12-08T14:14:03.003-02"), user.created);
+
+        // Dates that don't exist aren't timestamps
+        assert!(Timestamp::from("2024-02-29T00:00:00Z")
+            .unix_timestamp()
+            .is_some());
+        assert!(Timestamp::from("2024-02-31T00:00:00Z")
+            .unix_timestamp()
+            .is_none());
+        assert!(Timestamp::from("2023-02-29T00:00:00Z")
+            .unix_timestamp()
+            .is_none());
+        assert!(Timestamp::from("2023-04-31T00:00:00Z")
+            .unix_timestamp()
+            .is_none());
+        assert!(Timestamp::from("yesterday").unix_timestamp().is_none());
+        assert!(Timestamp::from("yesterday") > user.created);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[tokio::test]
+    async fn parsed_timestamps() {
+        use chrono::{Datelike, TimeZone, Utc};
+
+        let (_server, jar) = mock_jar().await;
+        let user = crate::users::user_by_id(&jar, UserId(375760054))
+            .await
+            .unwrap();
+        let created = user.created.parsed().unwrap();
+        assert_eq!(created.year(), 2016);
+        assert_eq!(
+            created,
+            Utc.with_ymd_and_hms(2016, 12, 8, 16, 14, 3).unwrap()
+                + chrono::Duration::milliseconds(3)
+        );
+        assert_eq!(Timestamp::from(created), user.created);
+        assert!(Timestamp::from("yesterday").parsed().is_none());
+    }
+}
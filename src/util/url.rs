@@ -0,0 +1,181 @@
+use std::fmt;
+
+/// Builds a request url from a base url and query parameters.
+///
+/// Parameters are kept separate from the url until it's built, so filters, sort orders and
+/// cursors can be added in any order, and their values are percent-encoded:
+///
+/// ```
+/// # use rustyroblox::util::url::UrlBuilder;
+/// let url = UrlBuilder::new("https://users.roblox.com/v1/users/search")
+///     .query("keyword", "piano 1029")
+///     .query_opt("cursor", None::<String>)
+///     .query("limit", 10);
+/// assert_eq!(url.build(), "https://users.roblox.com/v1/users/search?keyword=piano%201029&limit=10");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UrlBuilder {
+    base: String,
+    query: Vec<(String, String)>,
+}
+
+impl UrlBuilder {
+    /// Starts building a url, the base url shouldn't contain a query string
+    pub fn new(base: &str) -> UrlBuilder {
+        UrlBuilder {
+            base: base.to_string(),
+            query: Vec::new(),
+        }
+    }
+
+    /// Adds a query parameter
+    pub fn query(mut self, key: &str, value: impl ToString) -> UrlBuilder {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds a query parameter if there is a value
+    pub fn query_opt(self, key: &str, value: Option<impl ToString>) -> UrlBuilder {
+        match value {
+            Some(value) => self.query(key, value),
+            None => self,
+        }
+    }
+
+    /// Adds a query parameter with a comma separated list of values, like the ids of a batch request
+    pub fn query_list<T: ToString>(
+        self,
+        key: &str,
+        values: impl IntoIterator<Item = T>,
+    ) -> UrlBuilder {
+        let values = values
+            .into_iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>();
+        self.query(key, values.join(","))
+    }
+
+    /// Gets the value of a query parameter that has been added
+    pub fn get_query(&self, key: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Builds the url with the percent-encoded query string
+    pub fn build(&self) -> String {
+        let mut url = self.base.clone();
+
+        for (index, (key, value)) in self.query.iter().enumerate() {
+            url.push(if index == 0 { '?' } else { '&' });
+            url.push_str(&encode(key));
+            url.push('=');
+            url.push_str(&encode(value));
+        }
+
+        url
+    }
+}
+
+impl fmt::Display for UrlBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.build())
+    }
+}
+
+/// Percent-encodes everything except the characters RFC 3986 leaves unreserved.
+/// Commas are kept as well, Roblox separates the ids in batch requests with them.
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b',' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UrlBuilder;
+    use crate::{
+        mock::{MockResponse, MockServer},
+        util::{
+            hosts::Domain,
+            ids::{GroupId, UserId},
+            paging::{PageLimit, SortOrder},
+        },
+    };
+
+    #[test]
+    fn url_builder() {
+        let url = UrlBuilder::new("https://groups.roblox.com/v1/groups/search")
+            .query("keyword", "Clanny & Friends/100%")
+            .query_opt("limit", None::<i64>)
+            .query_list("ids", [1, 2, 3]);
+        assert_eq!(
+            url.build(),
+            "https://groups.roblox.com/v1/groups/search?keyword=Clanny%20%26%20Friends%2F100%25&ids=1,2,3"
+        );
+        assert_eq!(url.get_query("ids"), Some("1,2,3"));
+        assert_eq!(
+            UrlBuilder::new("https://groups.roblox.com/v1/groups/1").build(),
+            "https://groups.roblox.com/v1/groups/1"
+        );
+    }
+
+    #[tokio::test]
+    async fn query_strings_compose() {
+        let server = MockServer::start().await;
+        let jar = server.jar().await;
+        let empty_page = MockResponse::page(Vec::new(), None);
+        server.mock(
+            Domain::Groups,
+            "GET",
+            "/v1/groups/1/audit-log",
+            empty_page.clone(),
+        );
+        server.mock(Domain::Users, "GET", "/v1/users/search", empty_page);
+
+        crate::groups::audit_log(&jar, GroupId(1), PageLimit::Limit10, None, None, None)
+            .await
+            .unwrap();
+        crate::groups::audit_log(
+            &jar,
+            GroupId(1),
+            PageLimit::Limit10,
+            Some(UserId(375760054)),
+            Some(SortOrder::Desc),
+            Some("next page".to_string()),
+        )
+        .await
+        .unwrap();
+        crate::users::username_search(&jar, "piano 1029&limit=100".to_string(), PageLimit::Limit10)
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/groups/v1/groups/1/audit-log");
+        assert_eq!(
+            requests[0].query,
+            vec![
+                ("sortOrder".to_string(), "Asc".to_string()),
+                ("limit".to_string(), "10".to_string()),
+            ]
+        );
+        assert_eq!(requests[1].query_param("userId"), Some("375760054"));
+        assert_eq!(requests[1].query_param("sortOrder"), Some("Desc"));
+        assert_eq!(requests[1].query_param("cursor"), Some("next page"));
+        assert_eq!(
+            requests[2].query_param("keyword"),
+            Some("piano 1029&limit=100")
+        );
+        assert_eq!(requests[2].query_param("limit"), Some("10"));
+    }
+}